@@ -0,0 +1,49 @@
+//! Prints a JSON log written by verbosio in human-readable form.
+//!
+//! ```text
+//! cargo run --example view_log -- run.jsonl --level warn --target my_app::net
+//! cargo run --example view_log -- run.jsonl --since 2025-07-14T09:00:00Z
+//! ```
+use std::fs::File;
+use std::io::{self, BufReader};
+use verbosio::Level;
+use verbosio::viewer::{self, ViewFilter};
+
+fn main() -> io::Result<()> {
+    let mut args = std::env::args().skip(1);
+    let mut path = None;
+    let mut filter = ViewFilter::default();
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().unwrap_or_else(|| usage(&format!("missing value for {}", arg)));
+        match arg.as_str() {
+            "--level" => {
+                filter.level = Some(match value().as_str() {
+                    "error" => Level::Error,
+                    "warn" => Level::Warn,
+                    "info" => Level::Info,
+                    "debug" => Level::Debug,
+                    other => usage(&format!("unknown level {}", other)),
+                })
+            }
+            "--target" => filter.target = Some(value()),
+            "--since" => filter.since = Some(time(&value())),
+            "--until" => filter.until = Some(time(&value())),
+            _ if path.is_none() => path = Some(arg),
+            _ => usage(&format!("unexpected argument {}", arg)),
+        }
+    }
+    let path = path.unwrap_or_else(|| usage("missing log file"));
+    let input = BufReader::new(File::open(path)?);
+    viewer::view(input, io::stdout().lock(), &filter)?;
+    Ok(())
+}
+
+fn time(text: &str) -> std::time::SystemTime {
+    viewer::parse_rfc3339(text).unwrap_or_else(|| usage(&format!("invalid time {}, expected e.g. 2025-07-14T09:00:00Z", text)))
+}
+
+fn usage(problem: &str) -> ! {
+    eprintln!("{}", problem);
+    eprintln!("usage: view_log <file.jsonl> [--level LEVEL] [--target PATH] [--since TIME] [--until TIME]");
+    std::process::exit(2)
+}
@@ -1,16 +1,16 @@
 use clap::Parser;
+use verbosio::cli::VerbosityArgs;
 use verbosio::*;
 
 #[derive(Parser)]
 struct Args {
-    /// Verbosity level (default: 1)
-    #[clap(short, long, default_value_t = 1)]
-    verbose: u8,
+    #[clap(flatten)]
+    verbosity: VerbosityArgs,
 }
 
 fn main() {
     let args = Args::parse();
-    set_verbosity!(args.verbose);
+    args.verbosity.apply().expect("failed to apply verbosity flags");
     run_ls();
 }
 
@@ -0,0 +1,30 @@
+/* C API of the verbosio crate (feature "ffi"). */
+#ifndef VERBOSIO_H
+#define VERBOSIO_H
+
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#define VERBOSIO_LEVEL_NONE  (-1)
+#define VERBOSIO_LEVEL_ERROR 0
+#define VERBOSIO_LEVEL_WARN  1
+#define VERBOSIO_LEVEL_INFO  2
+#define VERBOSIO_LEVEL_DEBUG 3
+#define VERBOSIO_LEVEL_TRACE 4
+
+/* Logs msg at level if the global verbosity is at least `verbosity`.
+ * msg must be NULL or a NUL-terminated string. */
+void verbosio_log(int level, uint8_t verbosity, const char *msg);
+
+void verbosio_set_verbosity(uint8_t verbosity);
+
+uint8_t verbosio_get_verbosity(void);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* VERBOSIO_H */
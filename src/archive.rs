@@ -0,0 +1,119 @@
+//! Collecting the log file when the process exits.
+//!
+//! [`on_exit_archive`] registers a destination the final log file is copied
+//! to during [`shutdown`](crate::shutdown::shutdown), i.e. when the
+//! [`ShutdownGuard`](crate::shutdown::ShutdownGuard) is dropped or
+//! [`exit`](crate::shutdown::exit) is called. This lets CI wrappers and
+//! support workflows pick up logs without knowing where each tool writes
+//! them.
+//!
+//! The destination is either a local directory or, with the
+//! `archive-upload` feature, an `http://` or `https://` URL the file is
+//! uploaded to with a `PUT` request, which works with S3-compatible storage
+//! through presigned URLs. Archived files are named after the log file and
+//! the [run ID](crate::run), e.g. `build-1a2b3c4d.log`.
+//!
+//! # Example
+//! ```rust
+//! use verbosio::{archive, sink};
+//!
+//! sink::set_log_file(std::env::temp_dir().join("verbosio-archive.log")).unwrap();
+//! archive::on_exit_archive(std::env::temp_dir().join("verbosio-logs")).unwrap();
+//!
+//! let _guard = verbosio::init_guarded();
+//! verbosio::vinfo!("working...");
+//! // the log file is copied to verbosio-logs/ when `_guard` is dropped
+//! ```
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+static DESTINATION: Mutex<Option<Destination>> = Mutex::new(None);
+
+#[derive(Debug, Clone)]
+enum Destination {
+    Directory(PathBuf),
+    #[cfg(feature = "archive-upload")]
+    Url(String),
+}
+
+/// Copies the log file to `destination` when the process shuts down.
+///
+/// `destination` is a local directory, created if needed, or an
+/// `http(s)://` URL. A URL ending in `/` gets the file name appended,
+/// anything else (e.g. a presigned S3 URL) is used as is. Replaces a
+/// previously registered destination. Nothing is archived if no log file is
+/// registered at shutdown.
+///
+/// # Errors
+/// Fails with [`io::ErrorKind::Unsupported`] for URLs if the
+/// `archive-upload` feature is disabled.
+pub fn on_exit_archive(destination: impl AsRef<Path>) -> io::Result<()> {
+    let destination = destination.as_ref();
+    let text = destination.to_string_lossy();
+    let destination = if text.starts_with("http://") || text.starts_with("https://") {
+        #[cfg(feature = "archive-upload")]
+        {
+            Destination::Url(text.into_owned())
+        }
+        #[cfg(not(feature = "archive-upload"))]
+        return Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "uploading logs requires the `archive-upload` feature",
+        ));
+    } else {
+        Destination::Directory(destination.to_path_buf())
+    };
+    *DESTINATION.lock().unwrap_or_else(|e| e.into_inner()) = Some(destination);
+    Ok(())
+}
+
+/// Cancels archiving registered with [`on_exit_archive`].
+pub fn clear_exit_archive() {
+    *DESTINATION.lock().unwrap_or_else(|e| e.into_inner()) = None;
+}
+
+/// Archives the log file once, called by `shutdown` after flushing.
+pub(crate) fn run() {
+    let Some(destination) = DESTINATION.lock().unwrap_or_else(|e| e.into_inner()).take() else {
+        return;
+    };
+    let Some(path) = crate::sink::log_file_path() else {
+        return;
+    };
+    match archive(&path, &destination) {
+        Ok(target) => crate::vinfo!(@lvl 2, "archived log file to {}", target),
+        Err(err) => crate::verror!("failed to archive log file {}: {}", path.display(), err),
+    }
+}
+
+fn archive(path: &Path, destination: &Destination) -> io::Result<String> {
+    let name = archive_name(path);
+    match destination {
+        Destination::Directory(dir) => {
+            fs::create_dir_all(dir)?;
+            let target = dir.join(name);
+            fs::copy(path, &target)?;
+            Ok(target.display().to_string())
+        }
+        #[cfg(feature = "archive-upload")]
+        Destination::Url(url) => {
+            let url = if url.ends_with('/') { format!("{}{}", url, name) } else { url.clone() };
+            let body = fs::read(path)?;
+            ureq::put(&url)
+                .set("Content-Type", "text/plain; charset=utf-8")
+                .send_bytes(&body)
+                .map_err(io::Error::other)?;
+            Ok(url)
+        }
+    }
+}
+
+fn archive_name(path: &Path) -> String {
+    let stem = path.file_stem().map(|stem| stem.to_string_lossy()).unwrap_or("log".into());
+    match path.extension() {
+        Some(ext) => format!("{}-{}.{}", stem, crate::run::run_id(), ext.to_string_lossy()),
+        None => format!("{}-{}", stem, crate::run::run_id()),
+    }
+}
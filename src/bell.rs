@@ -0,0 +1,73 @@
+//! Audible alerts for long unattended runs.
+//!
+//! Opt in with [`ring_on_error`] to ring the terminal bell when an error is
+//! logged, and with [`ring_after`] to ring it when a scoped section
+//! (`vsection_scoped!`) or a `vwait!` that took longer than a threshold
+//! completes. The bell is written to stderr, and only if stderr is a
+//! terminal, so logs redirected to files stay free of `BEL` characters.
+//! Rings are throttled to one per second.
+//!
+//! # Example
+//! ```rust
+//! use std::time::Duration;
+//! use verbosio::bell;
+//!
+//! bell::ring_on_error(true);
+//! bell::ring_after(Some(Duration::from_secs(60)));
+//! ```
+use std::io::{self, IsTerminal, Write};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+/// Minimum time between two rings.
+const THROTTLE: Duration = Duration::from_secs(1);
+
+static ON_ERROR: AtomicBool = AtomicBool::new(false);
+
+/// Threshold in milliseconds, disabled while zero.
+static AFTER_MILLIS: AtomicU64 = AtomicU64::new(0);
+
+static LAST_RING: Mutex<Option<Instant>> = Mutex::new(None);
+
+/// Rings the bell whenever an `[ERROR]` message is printed to the terminal.
+pub fn ring_on_error(enabled: bool) {
+    ON_ERROR.store(enabled, Ordering::Relaxed);
+}
+
+/// Rings the bell when a scoped section or `vwait!` that ran at least
+/// `threshold` completes; `None` turns it off.
+pub fn ring_after(threshold: Option<Duration>) {
+    let millis = threshold.map_or(0, |threshold| (threshold.as_millis() as u64).max(1));
+    AFTER_MILLIS.store(millis, Ordering::Relaxed);
+}
+
+/// Rings the bell now, unless it rang less than a second ago.
+pub fn ring() {
+    let mut stderr = io::stderr();
+    if !stderr.is_terminal() {
+        return;
+    }
+    let mut last = LAST_RING.lock().unwrap_or_else(|e| e.into_inner());
+    if last.is_some_and(|last| last.elapsed() < THROTTLE) {
+        return;
+    }
+    *last = Some(Instant::now());
+    let _ = stderr.write_all(b"\x07");
+    let _ = stderr.flush();
+}
+
+/// Called when an error was printed to the terminal.
+pub(crate) fn error_printed() {
+    if ON_ERROR.load(Ordering::Relaxed) {
+        ring();
+    }
+}
+
+/// Called when a section or `vwait!` completes after `elapsed`.
+pub(crate) fn finished(elapsed: Duration) {
+    let millis = AFTER_MILLIS.load(Ordering::Relaxed);
+    if millis != 0 && elapsed.as_millis() >= u128::from(millis) {
+        ring();
+    }
+}
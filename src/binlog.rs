@@ -0,0 +1,180 @@
+//! Compact binary log files for very verbose runs.
+//!
+//! Instead of rendering text, every record is written in a binary encoding:
+//! targets, field names and thread labels are stored once and referenced by
+//! number afterwards, and timestamps are varint-encoded differences to the
+//! previous record. This makes the files much smaller and cheaper to write
+//! than text logs. Read them back with [`decode`](crate::decode).
+//!
+//! Like the text log file, the binary log receives every record that is
+//! printed, and records routed away from files by a
+//! [`layer`](crate::layer) are left out.
+//!
+//! # Format
+//!
+//! The file starts with the magic bytes `VRBL`, a version byte, the start
+//! time in microseconds since the Unix epoch and the run ID. It is followed
+//! by entries, each starting with a tag byte: `0` defines the next string of
+//! the string table, `1` is a record. All integers are LEB128 varints.
+//!
+//! # Example
+//! ```rust
+//! use verbosio::{binlog, decode, set_verbosity, vinfo};
+//!
+//! set_verbosity!(1);
+//! let path = std::env::temp_dir().join("verbosio-doc.vrbl");
+//! binlog::set_binary_log(&path).unwrap();
+//! vinfo!("compiled {} crates", 12);
+//! binlog::clear_binary_log();
+//!
+//! let records = decode::read_file(&path).unwrap();
+//! assert_eq!(records[0].message, "compiled 12 crates");
+//! ```
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use crate::Level;
+use crate::record::Record;
+
+pub(crate) const MAGIC: &[u8; 4] = b"VRBL";
+pub(crate) const VERSION: u8 = 1;
+pub(crate) const TAG_STRING: u8 = 0;
+pub(crate) const TAG_RECORD: u8 = 1;
+
+static BINARY_LOG: Mutex<Option<BinaryLog>> = Mutex::new(None);
+
+struct BinaryLog {
+    out: BufWriter<File>,
+    strings: HashMap<String, u64>,
+    last_micros: u64,
+}
+
+/// Writes all subsequent records to a new binary log at `path`.
+///
+/// An existing file is truncated, and a previous binary log is closed.
+pub fn set_binary_log<P: AsRef<Path>>(path: P) -> io::Result<()> {
+    let mut out = BufWriter::new(File::create(path)?);
+    let start = micros(SystemTime::now());
+    out.write_all(MAGIC)?;
+    out.write_all(&[VERSION])?;
+    write_varint(&mut out, start)?;
+    write_str(&mut out, crate::run::run_id())?;
+    let log = BinaryLog {
+        out,
+        strings: HashMap::new(),
+        last_micros: start,
+    };
+    if let Some(mut previous) = BINARY_LOG.lock().unwrap_or_else(|e| e.into_inner()).replace(log) {
+        let _ = previous.out.flush();
+    }
+    Ok(())
+}
+
+/// Flushes and closes the binary log, if any.
+pub fn clear_binary_log() {
+    if let Some(mut log) = BINARY_LOG.lock().unwrap_or_else(|e| e.into_inner()).take() {
+        let _ = log.out.flush();
+    }
+}
+
+pub(crate) fn flush() {
+    if let Some(log) = BINARY_LOG.lock().unwrap_or_else(|e| e.into_inner()).as_mut() {
+        let _ = log.out.flush();
+    }
+}
+
+pub(crate) fn write(record: &Record) {
+    if let Some(log) = BINARY_LOG.lock().unwrap_or_else(|e| e.into_inner()).as_mut() {
+        let _ = log.write_record(record);
+    }
+}
+
+impl BinaryLog {
+    fn write_record(&mut self, record: &Record) -> io::Result<()> {
+        let target = self.intern(&record.target)?;
+        let fields: Vec<(u64, &str)> = record
+            .global_fields
+            .iter()
+            .chain(&record.fields)
+            .map(|(key, value)| Ok((self.intern(key)?, value.as_str())))
+            .collect::<io::Result<_>>()?;
+        let thread = match &record.thread_label {
+            Some(label) => self.intern(label)? + 1,
+            None => 0,
+        };
+
+        let now = micros(record.time);
+        let delta = now as i64 - self.last_micros as i64;
+        self.last_micros = now;
+
+        let out = &mut self.out;
+        out.write_all(&[TAG_RECORD])?;
+        write_varint(out, zigzag(delta))?;
+        out.write_all(&[level_byte(record.level)])?;
+        write_varint(out, target)?;
+        write_str(out, &record.message)?;
+        write_varint(out, fields.len() as u64)?;
+        for (key, value) in fields {
+            write_varint(out, key)?;
+            write_str(out, value)?;
+        }
+        write_varint(out, thread)?;
+        write_varint(out, record.span_id.map_or(0, |id| id + 1))
+    }
+
+    /// Returns the ID of `value`, defining it first if it is new.
+    fn intern(&mut self, value: &str) -> io::Result<u64> {
+        if let Some(&id) = self.strings.get(value) {
+            return Ok(id);
+        }
+        let id = self.strings.len() as u64;
+        self.out.write_all(&[TAG_STRING])?;
+        write_str(&mut self.out, value)?;
+        self.strings.insert(value.to_string(), id);
+        Ok(id)
+    }
+}
+
+fn micros(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH).unwrap_or_default().as_micros() as u64
+}
+
+pub(crate) fn level_byte(level: Option<Level>) -> u8 {
+    match level {
+        None => 0,
+        Some(Level::Error) => 1,
+        Some(Level::Warn) => 2,
+        Some(Level::Info) => 3,
+        Some(Level::Debug) => 4,
+        Some(Level::Trace) => 5,
+    }
+}
+
+fn zigzag(value: i64) -> u64 {
+    ((value << 1) ^ (value >> 63)) as u64
+}
+
+fn write_varint(out: &mut impl Write, mut value: u64) -> io::Result<()> {
+    let mut buf = [0u8; 10];
+    let mut len = 0;
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            buf[len] = byte;
+            len += 1;
+            break;
+        }
+        buf[len] = byte | 0x80;
+        len += 1;
+    }
+    out.write_all(&buf[..len])
+}
+
+fn write_str(out: &mut impl Write, value: &str) -> io::Result<()> {
+    write_varint(out, value.len() as u64)?;
+    out.write_all(value.as_bytes())
+}
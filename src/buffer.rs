@@ -0,0 +1,101 @@
+//! Per-thread output buffering for parallel jobs.
+//!
+//! When several worker threads log at the same time, their lines interleave
+//! on the terminal. While a [`BufferGuard`] is alive, terminal output of its
+//! thread is held back and written in one piece when the guard is dropped,
+//! like `cargo` does for the output of each crate. The log file is still
+//! written immediately.
+//!
+//! With [`set_buffered_sections`], every `vsection_scoped!` buffers its
+//! output this way, header and footer included.
+//!
+//! # Example
+//! ```rust
+//! use verbosio::{buffer, set_verbosity, vinfo, vsection_scoped};
+//!
+//! set_verbosity!(1);
+//! buffer::set_buffered_sections(true);
+//! let workers: Vec<_> = ["serde", "clap"]
+//!     .into_iter()
+//!     .map(|name| {
+//!         std::thread::spawn(move || {
+//!             let _section = vsection_scoped!("Compiling {}", name);
+//!             vinfo!("step 1");
+//!             vinfo!("step 2");
+//!         }) // each section is printed as one block
+//!     })
+//!     .collect();
+//! for worker in workers {
+//!     worker.join().unwrap();
+//! }
+//! # buffer::set_buffered_sections(false);
+//! ```
+use std::cell::RefCell;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+thread_local! {
+    /// Held-back lines of this thread, `None` while not buffering.
+    static BUFFER: RefCell<Option<Vec<(bool, String)>>> = const { RefCell::new(None) };
+}
+
+/// Serializes flushes, so blocks of different threads don't interleave.
+static FLUSH: Mutex<()> = Mutex::new(());
+
+static BUFFERED_SECTIONS: AtomicBool = AtomicBool::new(false);
+
+/// Writes the held-back output of its thread when dropped.
+///
+/// Guards nest; only the outermost one flushes.
+#[must_use = "output is flushed when the guard is dropped"]
+pub struct BufferGuard {
+    outermost: bool,
+}
+
+/// Holds back terminal output of the current thread until the returned
+/// guard is dropped.
+pub fn buffer_output() -> BufferGuard {
+    let outermost = BUFFER.with(|buffer| {
+        let mut buffer = buffer.borrow_mut();
+        if buffer.is_some() {
+            false
+        } else {
+            *buffer = Some(Vec::new());
+            true
+        }
+    });
+    BufferGuard { outermost }
+}
+
+/// Makes every `vsection_scoped!` buffer its output, see the [module docs](self).
+pub fn set_buffered_sections(enabled: bool) {
+    BUFFERED_SECTIONS.store(enabled, Ordering::Relaxed);
+}
+
+pub(crate) fn buffered_sections() -> bool {
+    BUFFERED_SECTIONS.load(Ordering::Relaxed)
+}
+
+/// Stores a line if the current thread is buffering; returns whether it did.
+pub(crate) fn hold(to_stderr: bool, text: &str) -> bool {
+    BUFFER.with(|buffer| match buffer.borrow_mut().as_mut() {
+        Some(lines) => {
+            lines.push((to_stderr, text.to_string()));
+            true
+        }
+        None => false,
+    })
+}
+
+impl Drop for BufferGuard {
+    fn drop(&mut self) {
+        if !self.outermost {
+            return;
+        }
+        let lines = BUFFER.with(|buffer| buffer.borrow_mut().take()).unwrap_or_default();
+        let _flush = FLUSH.lock().unwrap_or_else(|e| e.into_inner());
+        for (to_stderr, text) in lines {
+            crate::sink::write_terminal(to_stderr, &text);
+        }
+    }
+}
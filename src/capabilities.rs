@@ -0,0 +1,181 @@
+//! What the terminal can do, and environment overrides for it.
+//!
+//! [`capabilities()`] reports what was detected: whether stdout and stderr
+//! are terminals, whether the cursor can be moved (not on `TERM=dumb`), the
+//! color depth, Unicode support and the width. Printing it helps diagnose
+//! why output looks wrong, e.g. in a container.
+//!
+//! verbosio degrades according to it: without cursor control, spinners
+//! print their message once and the task footer is not drawn; without
+//! Unicode, spinners and `vwait!` marks use ASCII; without colors, level
+//! tags are plain (unless the [color mode](crate::color) was set explicitly).
+//!
+//! Each detected value can be forced through an environment variable:
+//!
+//! | Variable           | Values                                 | Overrides      |
+//! |--------------------|----------------------------------------|----------------|
+//! | `VERBOSIO_TTY`     | `0`, `1`                               | `stdout_tty`, `stderr_tty`, `interactive` |
+//! | `VERBOSIO_COLORS`  | `none`, `16`, `256`, `truecolor`       | `colors`       |
+//! | `VERBOSIO_UNICODE` | `0`, `1`                               | `unicode`      |
+//! | `VERBOSIO_WIDTH`   | columns, e.g. `80`                     | `width`        |
+//!
+//! Detection happens once, on first use.
+//!
+//! # Example
+//! ```rust
+//! let caps = verbosio::capabilities();
+//! if !caps.unicode {
+//!     verbosio::vinfo!("using ASCII output");
+//! }
+//! println!("{}", caps); // multi-line report, e.g. for a `--diagnose` flag
+//! ```
+use std::fmt;
+use std::io::{self, IsTerminal};
+use once_cell::sync::Lazy;
+
+static CAPABILITIES: Lazy<Capabilities> = Lazy::new(Capabilities::detect);
+
+/// How many colors the terminal supports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ColorDepth {
+    None,
+    Ansi16,
+    Ansi256,
+    TrueColor,
+}
+
+impl fmt::Display for ColorDepth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ColorDepth::None => "none",
+            ColorDepth::Ansi16 => "16",
+            ColorDepth::Ansi256 => "256",
+            ColorDepth::TrueColor => "truecolor",
+        })
+    }
+}
+
+/// Detected terminal capabilities, see the [module docs](self).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Capabilities {
+    pub stdout_tty: bool,
+    pub stderr_tty: bool,
+    /// Whether stdout is a terminal that supports cursor movement, which
+    /// spinners, footers and the pinned status region need.
+    pub interactive: bool,
+    pub colors: ColorDepth,
+    pub unicode: bool,
+    /// Width in columns, if known.
+    pub width: Option<u16>,
+}
+
+/// Returns the capabilities of the terminal, detected on first call.
+pub fn capabilities() -> &'static Capabilities {
+    &CAPABILITIES
+}
+
+impl Capabilities {
+    /// Detects the capabilities from the environment, without caching.
+    pub fn detect() -> Self {
+        let term = var("TERM").unwrap_or_default();
+        let dumb = term == "dumb";
+        let forced_tty = flag("VERBOSIO_TTY");
+        let stdout_tty = forced_tty.unwrap_or_else(|| io::stdout().is_terminal());
+        let stderr_tty = forced_tty.unwrap_or_else(|| io::stderr().is_terminal());
+
+        let colors = var("VERBOSIO_COLORS").and_then(|value| parse_depth(&value)).unwrap_or_else(|| {
+            if var("NO_COLOR").is_some() {
+                ColorDepth::None
+            } else if var("CLICOLOR_FORCE").is_some_and(|value| value != "0") {
+                detect_depth(&term).max(ColorDepth::Ansi16)
+            } else if !stdout_tty || dumb || var("CLICOLOR").is_some_and(|value| value == "0") {
+                ColorDepth::None
+            } else {
+                detect_depth(&term)
+            }
+        });
+
+        let unicode = flag("VERBOSIO_UNICODE").unwrap_or_else(|| {
+            if cfg!(windows) {
+                return true;
+            }
+            ["LC_ALL", "LC_CTYPE", "LANG"]
+                .iter()
+                .find_map(|name| var(name))
+                .is_some_and(|locale| {
+                    let locale = locale.to_ascii_lowercase();
+                    locale.contains("utf-8") || locale.contains("utf8")
+                })
+        });
+
+        let width = var("VERBOSIO_WIDTH")
+            .or_else(|| var("COLUMNS"))
+            .and_then(|value| value.trim().parse().ok())
+            .or_else(terminal_width);
+
+        Capabilities {
+            stdout_tty,
+            stderr_tty,
+            interactive: forced_tty.unwrap_or(stdout_tty && !dumb),
+            colors,
+            unicode,
+            width,
+        }
+    }
+}
+
+impl fmt::Display for Capabilities {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "stdout tty:  {}", self.stdout_tty)?;
+        writeln!(f, "stderr tty:  {}", self.stderr_tty)?;
+        writeln!(f, "interactive: {}", self.interactive)?;
+        writeln!(f, "colors:      {}", self.colors)?;
+        writeln!(f, "unicode:     {}", self.unicode)?;
+        match self.width {
+            Some(width) => write!(f, "width:       {}", width),
+            None => write!(f, "width:       unknown"),
+        }
+    }
+}
+
+fn var(name: &str) -> Option<String> {
+    std::env::var(name).ok().filter(|value| !value.is_empty())
+}
+
+fn flag(name: &str) -> Option<bool> {
+    match var(name)?.trim().to_ascii_lowercase().as_str() {
+        "1" | "true" | "yes" | "on" => Some(true),
+        "0" | "false" | "no" | "off" => Some(false),
+        _ => None,
+    }
+}
+
+fn parse_depth(value: &str) -> Option<ColorDepth> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "none" | "0" | "never" => Some(ColorDepth::None),
+        "16" | "ansi" => Some(ColorDepth::Ansi16),
+        "256" => Some(ColorDepth::Ansi256),
+        "truecolor" | "24bit" => Some(ColorDepth::TrueColor),
+        _ => None,
+    }
+}
+
+fn detect_depth(term: &str) -> ColorDepth {
+    if var("COLORTERM").is_some_and(|value| value == "truecolor" || value == "24bit") {
+        ColorDepth::TrueColor
+    } else if term.contains("256color") {
+        ColorDepth::Ansi256
+    } else {
+        ColorDepth::Ansi16
+    }
+}
+
+#[cfg(feature = "status")]
+fn terminal_width() -> Option<u16> {
+    crossterm::terminal::size().ok().map(|(cols, _)| cols)
+}
+
+#[cfg(not(feature = "status"))]
+fn terminal_width() -> Option<u16> {
+    None
+}
@@ -0,0 +1,127 @@
+//! Progress checkpoints that survive an interrupted run.
+//!
+//! After [`init_checkpoints`] names a state file, every
+//! [`vcheckpoint!`](crate::vcheckpoint) overwrites it with the latest
+//! checkpoint, and [`finish_checkpoints`] removes it once the run completed.
+//! If the file still exists at the next start, the previous run was
+//! interrupted: verbosio prints `resuming after step 42 (previous run
+//! interrupted)` and [`resumed`] returns the saved checkpoint, so the
+//! application can skip work that was already done.
+//!
+//! # Example
+//! ```rust
+//! use verbosio::{checkpoint, set_verbosity, vcheckpoint};
+//!
+//! set_verbosity!(1);
+//! let state = std::env::temp_dir().join("verbosio-doc.checkpoint");
+//! checkpoint::init_checkpoints(&state).unwrap();
+//! let start = match checkpoint::resumed() {
+//!     Some(saved) => saved.value.parse::<u32>().unwrap() + 1,
+//!     None => 0,
+//! };
+//! for step in start..3 {
+//!     // ... work ...
+//!     vcheckpoint!("step", step);
+//! }
+//! checkpoint::finish_checkpoints();
+//! ```
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use crate::util::{escape_newlines, unescape_newlines};
+
+static STATE: Mutex<Option<State>> = Mutex::new(None);
+
+struct State {
+    path: PathBuf,
+    resumed: Option<Checkpoint>,
+}
+
+/// A saved checkpoint: a name (e.g. `"step"`) and its value (e.g. `"42"`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Checkpoint {
+    pub name: String,
+    pub value: String,
+    /// ID of the run that saved the checkpoint.
+    pub run_id: String,
+}
+
+impl fmt::Display for Checkpoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.name, self.value)
+    }
+}
+
+/// Uses `path` as the state file and loads the checkpoint of an
+/// interrupted previous run, if there is one.
+///
+/// The resume notice is printed as `[INFO]` at verbosity 1. Returns the
+/// loaded checkpoint, which is also available through [`resumed`].
+pub fn init_checkpoints<P: AsRef<Path>>(path: P) -> io::Result<Option<Checkpoint>> {
+    let path = path.as_ref().to_path_buf();
+    let resumed = match fs::read_to_string(&path) {
+        Ok(text) => parse(&text),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => None,
+        Err(err) => return Err(err),
+    };
+    if let Some(checkpoint) = &resumed {
+        crate::vinfo!("resuming after {} (previous run interrupted)", checkpoint);
+    }
+    *STATE.lock().unwrap_or_else(|e| e.into_inner()) = Some(State {
+        path,
+        resumed: resumed.clone(),
+    });
+    Ok(resumed)
+}
+
+/// The checkpoint the previous, interrupted run reached, if any.
+pub fn resumed() -> Option<Checkpoint> {
+    STATE.lock().unwrap_or_else(|e| e.into_inner()).as_ref()?.resumed.clone()
+}
+
+/// Saves a checkpoint, replacing the previous one.
+///
+/// Does nothing if [`init_checkpoints`] wasn't called. Used by
+/// [`vcheckpoint!`](crate::vcheckpoint).
+pub fn save(name: &str, value: impl fmt::Display) -> io::Result<()> {
+    let state = STATE.lock().unwrap_or_else(|e| e.into_inner());
+    let Some(state) = state.as_ref() else {
+        return Ok(());
+    };
+    let text = format!(
+        "run_id={}\nname={}\nvalue={}\n",
+        crate::run::run_id(),
+        escape_newlines(name),
+        escape_newlines(&value.to_string())
+    );
+    // write and rename, so an interruption never leaves a partial file
+    let tmp = state.path.with_extension("tmp");
+    fs::write(&tmp, text)?;
+    fs::rename(&tmp, &state.path)
+}
+
+/// Marks the run as completed by removing the state file.
+pub fn finish_checkpoints() {
+    if let Some(state) = STATE.lock().unwrap_or_else(|e| e.into_inner()).take() {
+        let _ = fs::remove_file(state.path);
+    }
+}
+
+fn parse(text: &str) -> Option<Checkpoint> {
+    let mut checkpoint = Checkpoint {
+        name: String::new(),
+        value: String::new(),
+        run_id: String::new(),
+    };
+    for line in text.lines() {
+        match line.split_once('=') {
+            Some(("run_id", value)) => checkpoint.run_id = value.to_string(),
+            Some(("name", value)) => checkpoint.name = unescape_newlines(value),
+            Some(("value", value)) => checkpoint.value = unescape_newlines(value),
+            _ => {}
+        }
+    }
+    (!checkpoint.name.is_empty()).then_some(checkpoint)
+}
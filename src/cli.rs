@@ -0,0 +1,84 @@
+//! Reusable command line flags for verbosity, colors and log files.
+//!
+//! [`VerbosityArgs`] can be flattened into any `clap` parser (with
+//! `#[command(flatten)]` or `#[clap(flatten)]`) so tools don't have to
+//! reinvent the usual `-v`/`-q` flags; `examples/with_clap.rs` shows a
+//! complete program.
+//!
+//! # Example
+//! ```rust
+//! use clap::Parser;
+//! use verbosio::cli::VerbosityArgs;
+//!
+//! #[derive(Parser)]
+//! struct Args {
+//!     #[command(flatten)]
+//!     verbosity: VerbosityArgs,
+//! }
+//!
+//! let args = Args::parse_from(["tool", "-vv", "--color", "never"]);
+//! args.verbosity.apply().expect("failed to apply verbosity flags");
+//! assert_eq!(verbosio::get_verbosity!(), 2);
+//! ```
+use std::io;
+use std::path::PathBuf;
+use crate::color::{set_color_mode, ColorMode};
+use crate::sink::set_log_file;
+
+/// Standard verbosity flags, meant to be `#[command(flatten)]`-ed.
+///
+/// | Flag                             | Effect                                 |
+/// |----------------------------------|----------------------------------------|
+/// | `-v`, `--verbose` (repeatable)   | Raises verbosity by one per occurrence |
+/// | `-q`, `--quiet`                  | [Quiet mode](crate::set_quiet): only errors print |
+/// | `QUIET=1` (environment)          | Like `--quiet`, unless `-v` is given   |
+/// | `--color <auto\|always\|never>`  | Controls colored output                |
+/// | `--log-file <path>`              | Also writes all messages to a file     |
+#[derive(clap::Args, Debug, Clone, Default)]
+pub struct VerbosityArgs {
+    /// Increase verbosity (-v, -vv, -vvv)
+    #[arg(short, long, action = clap::ArgAction::Count, global = true)]
+    pub verbose: u8,
+
+    /// Suppress output
+    #[arg(short, long, global = true, conflicts_with = "verbose")]
+    pub quiet: bool,
+
+    /// When to use colors
+    #[arg(long, value_enum, value_name = "WHEN", default_value_t = ColorMode::Auto, global = true)]
+    pub color: ColorMode,
+
+    /// Also write log output to this file
+    #[arg(long, value_name = "PATH", global = true)]
+    pub log_file: Option<PathBuf>,
+}
+
+impl VerbosityArgs {
+    /// Returns the verbosity level selected by the flags.
+    ///
+    /// Without `-v`, the `QUIET` environment variable acts like `--quiet`
+    /// (see [`quiet_from_env`](crate::env::quiet_from_env)).
+    pub fn level(&self) -> u8 {
+        if self.quiet || (self.verbose == 0 && crate::env::quiet_from_env()) {
+            0
+        } else {
+            self.verbose
+        }
+    }
+
+    /// Applies the flags to the global verbosity, color mode and log file.
+    ///
+    /// Fails if the log file cannot be opened.
+    pub fn apply(&self) -> io::Result<()> {
+        if self.level() == 0 && (self.quiet || crate::env::quiet_from_env()) {
+            crate::set_quiet!();
+        } else {
+            crate::set_verbosity!(self.level());
+        }
+        set_color_mode(self.color);
+        if let Some(path) = &self.log_file {
+            set_log_file(path)?;
+        }
+        Ok(())
+    }
+}
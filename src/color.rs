@@ -0,0 +1,428 @@
+//! Control over when colored output is used, and which colors.
+//!
+//! Whether colors are written is decided in one place, [`colors_enabled`],
+//! for level tags, section headers and footers, notes and the status line
+//! summaries alike. In [`ColorMode::Auto`] it follows the detected
+//! [capabilities](crate::capabilities): `NO_COLOR` disables colors,
+//! `CLICOLOR_FORCE` (other than `0`) enables them even when piped, and
+//! otherwise they are used if `stdout` is a terminal that isn't `TERM=dumb`
+//! and `CLICOLOR` isn't `0`.
+use std::fmt;
+use std::str::FromStr;
+use std::sync::RwLock;
+use std::sync::atomic::{AtomicU8, Ordering};
+use crate::capabilities::ColorDepth;
+
+static COLOR_MODE: AtomicU8 = AtomicU8::new(0);
+
+/// When to use colors for level tags and section headers.
+///
+/// `Auto` leaves the decision to the terminal environment (`NO_COLOR`,
+/// `CLICOLOR`, TTY detection).
+///
+/// # Features
+/// Colors are only ever produced if the `"color"` feature is enabled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+pub enum ColorMode {
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+impl fmt::Display for ColorMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ColorMode::Auto => "auto",
+            ColorMode::Always => "always",
+            ColorMode::Never => "never",
+        })
+    }
+}
+
+impl FromStr for ColorMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "auto" => Ok(ColorMode::Auto),
+            "always" => Ok(ColorMode::Always),
+            "never" => Ok(ColorMode::Never),
+            _ => Err(format!("invalid color mode '{}', expected auto, always or never", s)),
+        }
+    }
+}
+
+/// Sets when colored output is used.
+///
+/// # Example
+/// ```rust
+/// use verbosio::color::{set_color_mode, ColorMode};
+///
+/// set_color_mode(ColorMode::Never);
+/// ```
+pub fn set_color_mode(mode: ColorMode) {
+    COLOR_MODE.store(
+        match mode {
+            ColorMode::Auto => 0,
+            ColorMode::Always => 1,
+            ColorMode::Never => 2,
+        },
+        Ordering::Relaxed,
+    );
+}
+
+/// Returns the mode last set with [`set_color_mode`], `Auto` by default.
+pub fn color_mode() -> ColorMode {
+    match COLOR_MODE.load(Ordering::Relaxed) {
+        1 => ColorMode::Always,
+        2 => ColorMode::Never,
+        _ => ColorMode::Auto,
+    }
+}
+
+/// Whether colored output is written, following the [color mode](color_mode)
+/// and, in `Auto` mode, the environment (see the [module docs](self)).
+///
+/// Always `false` without the `"color"` feature.
+///
+/// # Example
+/// ```rust
+/// use verbosio::color::{colors_enabled, set_color_mode, ColorMode};
+///
+/// set_color_mode(ColorMode::Never);
+/// assert!(!colors_enabled());
+/// # set_color_mode(ColorMode::Auto);
+/// ```
+pub fn colors_enabled() -> bool {
+    if !cfg!(feature = "color") {
+        return false;
+    }
+    match color_mode() {
+        ColorMode::Always => true,
+        ColorMode::Never => false,
+        ColorMode::Auto => crate::capabilities().colors != ColorDepth::None,
+    }
+}
+
+/// A terminal color, as used by [`Theme`].
+///
+/// Besides the 16 basic ANSI colors, themes can use a color of the 256-color
+/// palette or an RGB value, e.g. to match a brand's palette. These are
+/// downgraded to the nearest color the terminal supports, see
+/// [`Color::downgrade`].
+///
+/// # Features
+/// With the `"serde"` feature, colors (de)serialize as snake case names
+/// (`"bright_blue"`), `{"ansi256": 208}` or `{"rgb": [30, 144, 255]}`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Color {
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+    BrightBlack,
+    BrightRed,
+    BrightGreen,
+    BrightYellow,
+    BrightBlue,
+    BrightMagenta,
+    BrightCyan,
+    BrightWhite,
+    /// An index into the 256-color palette.
+    Ansi256(u8),
+    /// A 24-bit color.
+    Rgb(u8, u8, u8),
+}
+
+/// The basic colors in palette order, with their usual RGB values (xterm).
+const BASIC: [(Color, (u8, u8, u8)); 16] = [
+    (Color::Black, (0, 0, 0)),
+    (Color::Red, (205, 0, 0)),
+    (Color::Green, (0, 205, 0)),
+    (Color::Yellow, (205, 205, 0)),
+    (Color::Blue, (0, 0, 238)),
+    (Color::Magenta, (205, 0, 205)),
+    (Color::Cyan, (0, 205, 205)),
+    (Color::White, (229, 229, 229)),
+    (Color::BrightBlack, (127, 127, 127)),
+    (Color::BrightRed, (255, 0, 0)),
+    (Color::BrightGreen, (0, 255, 0)),
+    (Color::BrightYellow, (255, 255, 0)),
+    (Color::BrightBlue, (92, 92, 255)),
+    (Color::BrightMagenta, (255, 0, 255)),
+    (Color::BrightCyan, (0, 255, 255)),
+    (Color::BrightWhite, (255, 255, 255)),
+];
+
+/// Channel values of the 6x6x6 color cube of the 256-color palette.
+const CUBE: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+impl Color {
+    /// Parses a `#rrggbb` (or `rrggbb`) hex string into an RGB color.
+    ///
+    /// # Example
+    /// ```rust
+    /// use verbosio::color::Color;
+    ///
+    /// assert_eq!(Color::from_hex("#1e90ff"), Some(Color::Rgb(30, 144, 255)));
+    /// assert_eq!(Color::from_hex("blue"), None);
+    /// ```
+    pub fn from_hex(hex: &str) -> Option<Color> {
+        let hex = hex.strip_prefix('#').unwrap_or(hex);
+        if hex.len() != 6 || !hex.is_ascii() {
+            return None;
+        }
+        let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+        Some(Color::Rgb(channel(0)?, channel(2)?, channel(4)?))
+    }
+
+    /// The color to use on a terminal with color depth `depth`.
+    ///
+    /// RGB colors become the nearest palette color on 256-color terminals,
+    /// and both RGB and palette colors become the nearest basic color on
+    /// 16-color terminals, which is then made
+    /// [readable](Color::safe_for_16_colors).
+    ///
+    /// # Example
+    /// ```rust
+    /// use verbosio::capabilities::ColorDepth;
+    /// use verbosio::color::Color;
+    ///
+    /// let brand = Color::Rgb(255, 135, 0);
+    /// assert_eq!(brand.downgrade(ColorDepth::TrueColor), brand);
+    /// assert_eq!(brand.downgrade(ColorDepth::Ansi256), Color::Ansi256(208));
+    /// assert_eq!(brand.downgrade(ColorDepth::Ansi16), Color::Yellow);
+    /// ```
+    pub fn downgrade(self, depth: ColorDepth) -> Color {
+        match (depth, self) {
+            (ColorDepth::TrueColor | ColorDepth::None, color) => color,
+            (ColorDepth::Ansi256, Color::Rgb(r, g, b)) => Color::Ansi256(rgb_to_ansi256(r, g, b)),
+            (ColorDepth::Ansi256, color) => color,
+            (ColorDepth::Ansi16, color) => {
+                let basic = match color {
+                    Color::Rgb(..) | Color::Ansi256(_) => nearest_basic(color.to_rgb()),
+                    basic => basic,
+                };
+                basic.safe_for_16_colors()
+            }
+        }
+    }
+
+    /// The RGB value of this color, using the xterm palette for basic colors.
+    pub fn to_rgb(self) -> (u8, u8, u8) {
+        match self {
+            Color::Rgb(r, g, b) => (r, g, b),
+            Color::Ansi256(index @ 0..=15) => BASIC[index as usize].1,
+            Color::Ansi256(index @ 16..=231) => {
+                let index = index - 16;
+                (CUBE[(index / 36) as usize], CUBE[(index / 6 % 6) as usize], CUBE[(index % 6) as usize])
+            }
+            Color::Ansi256(index) => {
+                let gray = 8 + (index - 232) * 10;
+                (gray, gray, gray)
+            }
+            basic => BASIC.iter().find(|(color, _)| *color == basic).map_or((0, 0, 0), |(_, rgb)| *rgb),
+        }
+    }
+
+    /// The color actually used on a 16-color console.
+    ///
+    /// Such consoles (older Windows consoles, serial terminals) render black,
+    /// dark gray and dark blue nearly invisibly on their dark background, so
+    /// these are replaced by the nearest readable color. Other colors are
+    /// kept.
+    pub fn safe_for_16_colors(self) -> Color {
+        match self {
+            Color::Black | Color::BrightBlack => Color::White,
+            Color::Blue => Color::BrightBlue,
+            other => other,
+        }
+    }
+
+    /// The SGR parameter selecting this color as foreground.
+    #[cfg(feature = "color")]
+    fn sgr(self) -> String {
+        match self {
+            Color::Ansi256(index) => format!("38;5;{}", index),
+            Color::Rgb(r, g, b) => format!("38;2;{};{};{}", r, g, b),
+            basic => {
+                let index = BASIC.iter().position(|(color, _)| *color == basic).unwrap_or(7);
+                if index < 8 { (30 + index).to_string() } else { (90 + index - 8).to_string() }
+            }
+        }
+    }
+}
+
+fn rgb_to_ansi256(r: u8, g: u8, b: u8) -> u8 {
+    if r == g && g == b {
+        // the gray ramp is finer than the cube's diagonal
+        return match r {
+            0..8 => 16,
+            249.. => 231,
+            gray => 232 + ((gray - 3) / 10).min(23),
+        };
+    }
+    let level = |value: u8| -> u8 {
+        match value {
+            0..48 => 0,
+            48..115 => 1,
+            value => (value - 35) / 40,
+        }
+    };
+    16 + 36 * level(r) + 6 * level(g) + level(b)
+}
+
+fn nearest_basic((r, g, b): (u8, u8, u8)) -> Color {
+    let distance = |(r2, g2, b2): (u8, u8, u8)| {
+        let d = |a: u8, b: u8| (a as i32 - b as i32).pow(2);
+        d(r, r2) + d(g, g2) + d(b, b2)
+    };
+    BASIC.iter().min_by_key(|(_, rgb)| distance(*rgb)).map_or(Color::White, |(color, _)| *color)
+}
+
+/// The colors of level tags, section footers, summaries and notes.
+///
+/// # Example
+/// ```rust
+/// use verbosio::color::{set_theme, Color, Theme};
+///
+/// set_theme(Theme { info: Color::Cyan, ..Theme::DEFAULT });
+/// ```
+///
+/// # Features
+/// Colors are only ever produced if the `"color"` feature is enabled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct Theme {
+    pub info: Color,
+    pub warn: Color,
+    pub debug: Color,
+    pub trace: Color,
+    pub error: Color,
+    /// Successful section footers, `vwait!` marks and summaries.
+    pub success: Color,
+    /// Markers such as the `=` of `help:`/`note:` lines.
+    pub accent: Color,
+}
+
+impl Theme {
+    /// The color of the tag of `level`.
+    pub fn level(&self, level: crate::Level) -> Color {
+        match level {
+            crate::Level::Info => self.info,
+            crate::Level::Warn => self.warn,
+            crate::Level::Debug => self.debug,
+            crate::Level::Trace => self.trace,
+            crate::Level::Error => self.error,
+        }
+    }
+
+    pub const DEFAULT: Theme = Theme {
+        info: Color::Blue,
+        warn: Color::Yellow,
+        debug: Color::Yellow,
+        trace: Color::BrightBlack,
+        error: Color::Red,
+        success: Color::Green,
+        accent: Color::Blue,
+    };
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme::DEFAULT
+    }
+}
+
+static THEME: RwLock<Theme> = RwLock::new(Theme::DEFAULT);
+
+/// Sets the colors used for colored output.
+pub fn set_theme(theme: Theme) {
+    *THEME.write().unwrap_or_else(|e| e.into_inner()) = theme;
+}
+
+/// Returns the theme last set with [`set_theme`], [`Theme::DEFAULT`] otherwise.
+pub fn theme() -> Theme {
+    *THEME.read().unwrap_or_else(|e| e.into_inner())
+}
+
+static TARGET_COLORS: RwLock<Vec<(String, Color)>> = RwLock::new(Vec::new());
+
+/// Colors the prefix of messages with the named target `target`, as in
+/// `vinfo!(target: "net", "connected")`.
+///
+/// # Example
+/// ```rust
+/// use verbosio::color::{set_target_color, Color};
+/// use verbosio::{set_verbosity, vinfo};
+///
+/// set_verbosity!(1);
+/// set_target_color("net", Color::Cyan);
+/// vinfo!(target: "net", "connected"); // [INFO] net: connected, with a cyan `net:`
+/// ```
+pub fn set_target_color(target: impl Into<String>, color: Color) {
+    let target = target.into();
+    let mut colors = TARGET_COLORS.write().unwrap_or_else(|e| e.into_inner());
+    colors.retain(|(existing, _)| *existing != target);
+    colors.push((target, color));
+}
+
+/// Removes all colors set with [`set_target_color`].
+pub fn clear_target_colors() {
+    TARGET_COLORS.write().unwrap_or_else(|e| e.into_inner()).clear();
+}
+
+/// The color set for `target` with [`set_target_color`], if any.
+#[cfg(feature = "color")]
+pub(crate) fn target_color(target: &str) -> Option<Color> {
+    let colors = TARGET_COLORS.read().unwrap_or_else(|e| e.into_inner());
+    colors.iter().find(|(existing, _)| existing == target).map(|(_, color)| *color)
+}
+
+/// Colors `text` with `color` in bold, [downgraded](Color::downgrade) to
+/// what the terminal supports. Returns the plain text if
+/// [colors are disabled](colors_enabled).
+#[cfg(feature = "color")]
+pub(crate) fn paint(text: &str, color: Color) -> String {
+    if !colors_enabled() {
+        return text.to_string();
+    }
+    let color = color.downgrade(crate::capabilities().colors);
+    styled(text, &format!("1;{}", color.sgr()))
+}
+
+/// Shows `text` in bold without a color, e.g. section headers, unless
+/// [colors are disabled](colors_enabled).
+#[cfg(feature = "color")]
+pub(crate) fn bold(text: &str) -> String {
+    if !colors_enabled() {
+        return text.to_string();
+    }
+    styled(text, "1")
+}
+
+/// Shows `text` dimmed, e.g. source locations, unless [colors are
+/// disabled](colors_enabled).
+#[cfg(feature = "color")]
+pub(crate) fn dim(text: &str) -> String {
+    if !colors_enabled() {
+        return text.to_string();
+    }
+    styled(text, "2")
+}
+
+#[cfg(feature = "color")]
+fn styled(text: &str, sgr: &str) -> String {
+    format!("\x1b[{}m{}\x1b[0m", sgr, text)
+}
@@ -0,0 +1,97 @@
+//! Running child processes that use verbosio as well.
+//!
+//! [`VerbosioCommandExt`] extends [`std::process::Command`] so that child
+//! tools inherit the parent's verbosity and color settings, and so that the
+//! invocation itself can be logged.
+//!
+//! # Example
+//! ```rust
+//! use std::process::Command;
+//! use verbosio::command::VerbosioCommandExt;
+//! use verbosio::set_verbosity;
+//!
+//! set_verbosity!(2);
+//! let mut cmd = Command::new("cargo");
+//! cmd.arg("build").inherit_verbosity().log_invocation(2);
+//! // [INFO] running: cargo build
+//! ```
+use std::ffi::OsStr;
+use std::process::Command;
+use crate::color::{color_mode, ColorMode};
+use crate::env::var_name;
+
+/// Extension methods for [`std::process::Command`].
+pub trait VerbosioCommandExt {
+    /// Passes the current verbosity and color settings to the child.
+    ///
+    /// Sets `VERBOSE` to the current verbosity followed by the
+    /// [filter](crate::filter) directives, e.g. `3,hyper=0`, which the child
+    /// picks up with [`verbose_env!`](crate::verbose_env). A [terminal
+    /// format](crate::sink::set_terminal_format) other than a custom one is
+    /// passed on as `LOG_FORMAT`. In [quiet
+    /// mode](crate::set_quiet) `QUIET=1` is set as well, so the child still
+    /// prints its errors; otherwise `QUIET` is removed, as it would override
+    /// `VERBOSE`. After [`verbose_env!(prefix: "MYTOOL")`](crate::env::init_with_prefix)
+    /// the variables are `MYTOOL_VERBOSE`, `MYTOOL_LOG_FORMAT` and
+    /// `MYTOOL_QUIET` instead. A color mode set with
+    /// [`set_color_mode`](crate::color::set_color_mode) is passed on as
+    /// `CLICOLOR_FORCE=1` (`Always`) or `NO_COLOR=1` (`Never`); with `Auto`
+    /// the child decides for itself.
+    fn inherit_verbosity(&mut self) -> &mut Self;
+
+    /// Logs the program and its arguments as `running: ...` at `INFO` if
+    /// verbosity ≥ `lvl`.
+    fn log_invocation(&mut self, lvl: u8) -> &mut Self;
+}
+
+impl VerbosioCommandExt for Command {
+    fn inherit_verbosity(&mut self) -> &mut Self {
+        let spec = std::iter::once(crate::get_verbosity!().to_string())
+            .chain(crate::filter::directives().iter().map(ToString::to_string))
+            .collect::<Vec<_>>()
+            .join(",");
+        self.env(var_name("VERBOSE"), spec);
+        if let Some(format) = crate::sink::terminal_format()
+            && let Some(spec) = format.spec()
+        {
+            self.env(var_name("LOG_FORMAT"), spec);
+        }
+        if crate::filter::is_quiet() {
+            self.env(var_name("QUIET"), "1");
+        } else {
+            self.env_remove(var_name("QUIET")).env_remove("QUIET");
+        }
+        match color_mode() {
+            ColorMode::Auto => {}
+            ColorMode::Always => {
+                self.env("CLICOLOR_FORCE", "1").env_remove("NO_COLOR");
+            }
+            ColorMode::Never => {
+                self.env("NO_COLOR", "1").env_remove("CLICOLOR_FORCE");
+            }
+        }
+        self
+    }
+
+    fn log_invocation(&mut self, lvl: u8) -> &mut Self {
+        if crate::scope::verbosity() >= lvl {
+            let invocation = std::iter::once(self.get_program())
+                .chain(self.get_args())
+                .map(quote)
+                .collect::<Vec<_>>()
+                .join(" ");
+            crate::vinfo!(@lvl lvl, "running: {}", invocation);
+        }
+        self
+    }
+}
+
+/// Quotes arguments that wouldn't survive being pasted into a shell.
+fn quote(arg: &OsStr) -> String {
+    let arg = arg.to_string_lossy();
+    if !arg.is_empty() && arg.chars().all(|c| c.is_ascii_alphanumeric() || "-_./=:,+@%".contains(c)) {
+        arg.into_owned()
+    } else {
+        format!("'{}'", arg.replace('\'', "'\\''"))
+    }
+}
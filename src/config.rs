@@ -0,0 +1,239 @@
+//! Logging configuration from a TOML file, see
+//! [`init_from_file!`](crate::init_from_file).
+//!
+//! A config file lets deployments tune the output of a tool without
+//! rebuilding it. Every key is optional; settings that are left out keep
+//! their current value.
+//!
+//! ```toml
+//! verbosity = "debug"                  # a number or a name, see `env`
+//! format = "{time} {level:>7} {msg}"   # human, json, logfmt or a template
+//! output = "human"                     # the output mode, see `output`
+//! time = "elapsed"                     # wallclock, elapsed or off
+//! color = "auto"                       # auto, always or never
+//!
+//! [theme]                              # colors, see `color::Theme`
+//! info = "cyan"
+//! warn = { ansi256 = 208 }
+//!
+//! [filters]                            # per-target verbosity, see `filter`
+//! "my_app::net" = 1
+//! hyper = "quiet"
+//!
+//! [log_file]
+//! path = "/var/log/my_app.log"
+//! format = "json"                      # human, json, logfmt or a template
+//! locking = "append"                   # lock or append, see `sink::FileLocking`
+//! ```
+//!
+//! Filters are added to the directives already set, e.g. from `VERBOSE`.
+//! Templates are checked when the file is loaded, like those of
+//! [`Template::parse`](crate::template::Template::parse).
+//!
+//! # Features
+//! Requires the `"config"` feature, which enables `"serde"` as well.
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+use serde::Deserialize;
+use crate::color::{ColorMode, Theme};
+use crate::filter::Directive;
+use crate::format::Format;
+use crate::output::OutputMode;
+use crate::sink::FileLocking;
+use crate::util::TimeMode;
+
+/// Why a config file could not be loaded or applied.
+#[derive(Debug)]
+pub enum ConfigError {
+    /// The config file or the log file could not be opened.
+    Io(io::Error),
+    /// Not valid TOML, an unknown key or an invalid value.
+    Invalid(String),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Io(err) => write!(f, "failed to open file: {}", err),
+            ConfigError::Invalid(message) => write!(f, "invalid config: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ConfigError::Io(err) => Some(err),
+            ConfigError::Invalid(_) => None,
+        }
+    }
+}
+
+impl From<io::Error> for ConfigError {
+    fn from(err: io::Error) -> Self {
+        ConfigError::Io(err)
+    }
+}
+
+/// A parsed config file, see the [module docs](self) for the keys.
+///
+/// `None` and empty fields leave the current setting alone.
+#[derive(Debug, Clone, Default)]
+pub struct Config {
+    pub verbosity: Option<u8>,
+    /// Whether the verbosity is given as `"quiet"` or another name of
+    /// verbosity `0`, which switches to [quiet mode](crate::set_quiet).
+    pub quiet: bool,
+    /// Format of terminal output.
+    pub format: Option<Format>,
+    pub output: Option<OutputMode>,
+    pub time: Option<TimeMode>,
+    pub color: Option<ColorMode>,
+    pub theme: Option<Theme>,
+    pub filters: Vec<Directive>,
+    pub log_file: Option<LogFileConfig>,
+}
+
+/// The `[log_file]` section of a config file.
+#[derive(Debug, Clone)]
+pub struct LogFileConfig {
+    pub path: PathBuf,
+    pub format: Format,
+    pub locking: Option<FileLocking>,
+}
+
+impl Config {
+    /// Parses the contents of a config file.
+    ///
+    /// # Example
+    /// ```rust
+    /// use verbosio::config::Config;
+    ///
+    /// let config = Config::parse("verbosity = \"debug\"\n[filters]\nhyper = 0\n").unwrap();
+    /// assert_eq!(config.verbosity, Some(3));
+    /// assert_eq!(config.filters[0].target, "hyper");
+    /// assert!(Config::parse("verbosity = \"quiet\"").unwrap().quiet);
+    /// assert!(Config::parse("format = \"{lvl} {msg}\"").is_err());
+    /// ```
+    pub fn parse(text: &str) -> Result<Config, ConfigError> {
+        let raw: RawConfig = toml::from_str(text).map_err(|err| ConfigError::Invalid(err.to_string()))?;
+        let filters = raw
+            .filters
+            .into_iter()
+            .map(|(target, verbosity)| Ok(Directive { verbosity: verbosity.resolve()?, target }))
+            .collect::<Result<_, ConfigError>>()?;
+        let log_file = match raw.log_file {
+            Some(log_file) => Some(LogFileConfig {
+                path: log_file.path,
+                format: log_file.format.as_deref().map_or(Ok(Format::Human), parse_format)?,
+                locking: log_file.locking,
+            }),
+            None => None,
+        };
+        let quiet = matches!(&raw.verbosity, Some(RawVerbosity::Name(name)) if crate::env::is_quiet_name(name));
+        Ok(Config {
+            verbosity: raw.verbosity.map(RawVerbosity::resolve).transpose()?,
+            quiet,
+            format: raw.format.as_deref().map(parse_format).transpose()?,
+            output: raw.output,
+            time: raw.time,
+            color: raw.color,
+            theme: raw.theme,
+            filters,
+            log_file,
+        })
+    }
+
+    /// Reads and parses the config file at `path`.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Config, ConfigError> {
+        Config::parse(&std::fs::read_to_string(path)?)
+    }
+
+    /// Applies all settings of this config. Fails only if the log file
+    /// can't be opened, after everything else has been applied.
+    pub fn apply(&self) -> Result<(), ConfigError> {
+        if self.quiet {
+            crate::set_quiet!();
+        } else if let Some(verbosity) = self.verbosity {
+            crate::set_verbosity!(verbosity);
+        }
+        if let Some(format) = &self.format {
+            crate::sink::set_terminal_format(format.clone());
+        }
+        if let Some(output) = self.output {
+            crate::output::set_output_mode(output);
+        }
+        if let Some(time) = self.time {
+            crate::util::set_time_mode(time);
+        }
+        if let Some(color) = self.color {
+            crate::color::set_color_mode(color);
+        }
+        if let Some(theme) = self.theme {
+            crate::color::set_theme(theme);
+        }
+        for directive in &self.filters {
+            crate::filter::add_directive(directive.target.clone(), directive.verbosity);
+        }
+        if let Some(log_file) = &self.log_file {
+            if let Some(locking) = log_file.locking {
+                crate::sink::set_file_locking(locking);
+            }
+            crate::sink::set_file_format(log_file.format.clone());
+            crate::sink::set_log_file(&log_file.path)?;
+        }
+        Ok(())
+    }
+}
+
+/// Loads the config file at `path` and applies it, returning the config.
+pub fn init_from_file<P: AsRef<Path>>(path: P) -> Result<Config, ConfigError> {
+    let config = Config::load(path)?;
+    config.apply()?;
+    Ok(config)
+}
+
+fn parse_format(value: &str) -> Result<Format, ConfigError> {
+    value.parse().map_err(ConfigError::Invalid)
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+struct RawConfig {
+    verbosity: Option<RawVerbosity>,
+    format: Option<String>,
+    output: Option<OutputMode>,
+    time: Option<TimeMode>,
+    color: Option<ColorMode>,
+    theme: Option<Theme>,
+    filters: BTreeMap<String, RawVerbosity>,
+    log_file: Option<RawLogFile>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawLogFile {
+    path: PathBuf,
+    format: Option<String>,
+    locking: Option<FileLocking>,
+}
+
+/// A verbosity given as a number or a name.
+#[derive(Deserialize)]
+#[serde(untagged, expecting = "a verbosity number from 0 to 255 or a name like \"debug\"")]
+enum RawVerbosity {
+    Number(u8),
+    Name(String),
+}
+
+impl RawVerbosity {
+    fn resolve(self) -> Result<u8, ConfigError> {
+        match self {
+            RawVerbosity::Number(verbosity) => Ok(verbosity),
+            RawVerbosity::Name(name) => crate::env::parse_verbosity(&name)
+                .ok_or_else(|| ConfigError::Invalid(format!("invalid verbosity {:?}", name))),
+        }
+    }
+}
@@ -0,0 +1,247 @@
+//! Context fields attached to log records.
+//!
+//! Key-value pairs set with [`vcontext_set!`](crate::vcontext_set) are
+//! attached to every message logged from the same thread until they are
+//! removed (also known as MDC), so per-task context doesn't have to be
+//! threaded through every call.
+//!
+//! [`with_fields!`](crate::with_fields) pushes fields for the duration of a
+//! block and restores the previous context afterwards, nesting correctly.
+//!
+//! Threads without a label set with
+//! [`set_thread_label!`](crate::set_thread_label) can be identified by their
+//! name or ID instead, see [`show_thread`].
+//!
+//! Hostname and username enrichment is opt-in via [`enable_host_fields`].
+//! Both are looked up once and then carried by every record.
+//!
+//! Global fields set with [`set_global_fields!`](crate::set_global_fields)
+//! apply to records from all threads. They are carried by every record but
+//! only rendered in human output after [`show_global_fields`]`(true)`.
+//!
+//! # Example
+//! ```rust
+//! use verbosio::{set_verbosity, vcontext_clear, vcontext_set, vinfo};
+//!
+//! set_verbosity!(1);
+//! vcontext_set!("request_id", 42);
+//! vinfo!("connected"); // [INFO] connected request_id=42
+//! vcontext_clear!();
+//! ```
+use std::cell::RefCell;
+use once_cell::sync::Lazy;
+use std::sync::RwLock;
+use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+
+thread_local! {
+    static CONTEXT: RefCell<Vec<(String, String)>> = const { RefCell::new(Vec::new()) };
+    static THREAD_LABEL: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+static GLOBAL_FIELDS: RwLock<Vec<(String, String)>> = RwLock::new(Vec::new());
+
+static SHOW_GLOBAL_FIELDS: AtomicBool = AtomicBool::new(false);
+
+static HOST_FIELDS: AtomicBool = AtomicBool::new(false);
+
+static SHOW_THREAD: AtomicU8 = AtomicU8::new(0);
+
+/// How records of threads without a label are labeled, see [`show_thread`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ThreadDisplay {
+    /// No label (default).
+    #[default]
+    Off,
+    /// The thread name, e.g. `main`, or the ID for unnamed threads.
+    Name,
+    /// The thread ID, e.g. `#3`.
+    Id,
+}
+
+static HOSTNAME: Lazy<Option<String>> = Lazy::new(lookup_hostname);
+
+static USERNAME: Lazy<Option<String>> = Lazy::new(|| {
+    ["USER", "USERNAME", "LOGNAME"]
+        .iter()
+        .find_map(|var| std::env::var(var).ok().filter(|v| !v.is_empty()))
+});
+
+/// Sets `key` to `value` in the current thread's context.
+///
+/// An existing value for `key` is replaced in place.
+pub fn set(key: impl Into<String>, value: impl Into<String>) {
+    let key = key.into();
+    let value = value.into();
+    CONTEXT.with(|context| {
+        let mut context = context.borrow_mut();
+        match context.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, v)) => *v = value,
+            None => context.push((key, value)),
+        }
+    });
+}
+
+/// Removes `key` from the current thread's context.
+pub fn remove(key: &str) {
+    CONTEXT.with(|context| context.borrow_mut().retain(|(k, _)| k != key));
+}
+
+/// Removes all keys from the current thread's context.
+pub fn clear() {
+    CONTEXT.with(|context| context.borrow_mut().clear());
+}
+
+/// Returns the current thread's context fields in insertion order.
+pub fn fields() -> Vec<(String, String)> {
+    CONTEXT.with(|context| context.borrow().clone())
+}
+
+/// Sets the display label of the current thread, e.g. `"worker-3"`.
+pub fn set_thread_label(label: impl Into<String>) {
+    let label = label.into();
+    THREAD_LABEL.with(|current| *current.borrow_mut() = Some(label));
+}
+
+/// Removes the display label of the current thread.
+pub fn clear_thread_label() {
+    THREAD_LABEL.with(|current| *current.borrow_mut() = None);
+}
+
+/// Returns the display label of the current thread, if any.
+pub fn thread_label() -> Option<String> {
+    THREAD_LABEL.with(|current| current.borrow().clone())
+}
+
+/// Restores the thread's previous context when dropped.
+///
+/// Created by [`push_fields`] and [`with_fields!`](crate::with_fields).
+#[must_use = "the fields are removed again when the guard is dropped"]
+pub struct FieldsGuard {
+    saved: Vec<(String, String)>,
+}
+
+/// Sets `fields` in the current thread's context until the guard is dropped.
+///
+/// Dropping the guard restores the context exactly as it was, including
+/// any changes made with `vcontext_set!` in the meantime.
+pub fn push_fields(fields: Vec<(String, String)>) -> FieldsGuard {
+    let saved = self::fields();
+    for (key, value) in fields {
+        set(key, value);
+    }
+    FieldsGuard { saved }
+}
+
+impl Drop for FieldsGuard {
+    fn drop(&mut self) {
+        let saved = std::mem::take(&mut self.saved);
+        CONTEXT.with(|context| *context.borrow_mut() = saved);
+    }
+}
+
+/// Replaces the global fields attached to records from all threads.
+pub fn set_global_fields(fields: Vec<(String, String)>) {
+    *GLOBAL_FIELDS.write().unwrap_or_else(|e| e.into_inner()) = fields;
+}
+
+/// Returns the global fields.
+pub fn global_fields() -> Vec<(String, String)> {
+    GLOBAL_FIELDS.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// Sets whether global fields are rendered in human output (default: `false`).
+pub fn show_global_fields(show: bool) {
+    SHOW_GLOBAL_FIELDS.store(show, Ordering::Relaxed);
+}
+
+/// Whether global fields are rendered in human output.
+pub fn global_fields_shown() -> bool {
+    SHOW_GLOBAL_FIELDS.load(Ordering::Relaxed)
+}
+
+/// Labels the records of every thread without a
+/// [`set_thread_label!`](crate::set_thread_label) label by its name or ID,
+/// e.g. `worker-3 | [INFO] started`. The label is also available as the
+/// `{thread}` placeholder of a [template](crate::template) and as the
+/// `thread` field of JSON and logfmt output.
+///
+/// # Example
+/// ```rust
+/// use verbosio::context::{show_thread, ThreadDisplay};
+/// use verbosio::{set_format, set_verbosity, vinfo};
+///
+/// set_verbosity!(1);
+/// show_thread(ThreadDisplay::Name);
+/// set_format!("[{thread}] {level} {msg}");
+/// std::thread::Builder::new().name("worker-3".into()).spawn(|| {
+///     vinfo!("started"); // [worker-3] INFO started
+/// }).unwrap().join().unwrap();
+/// # set_format!();
+/// # show_thread(ThreadDisplay::Off);
+/// ```
+pub fn show_thread(display: ThreadDisplay) {
+    let value = match display {
+        ThreadDisplay::Off => 0,
+        ThreadDisplay::Name => 1,
+        ThreadDisplay::Id => 2,
+    };
+    SHOW_THREAD.store(value, Ordering::Relaxed);
+}
+
+/// The name or ID of the current thread, as chosen with [`show_thread`].
+pub(crate) fn thread_identity() -> Option<String> {
+    let thread = std::thread::current();
+    match SHOW_THREAD.load(Ordering::Relaxed) {
+        1 => Some(thread.name().map_or_else(|| thread_id(&thread), str::to_string)),
+        2 => Some(thread_id(&thread)),
+        _ => None,
+    }
+}
+
+/// Formats a thread ID as `#3`; `ThreadId::as_u64` is unstable.
+fn thread_id(thread: &std::thread::Thread) -> String {
+    let debug = format!("{:?}", thread.id());
+    let digits: String = debug.chars().filter(char::is_ascii_digit).collect();
+    format!("#{}", digits)
+}
+
+/// Sets whether records carry the hostname and username (default: `false`).
+///
+/// # Example
+/// ```rust
+/// verbosio::context::enable_host_fields(true);
+/// println!("logging from {:?}", verbosio::context::hostname());
+/// ```
+pub fn enable_host_fields(enable: bool) {
+    HOST_FIELDS.store(enable, Ordering::Relaxed);
+}
+
+/// Whether records carry the hostname and username.
+pub fn host_fields_enabled() -> bool {
+    HOST_FIELDS.load(Ordering::Relaxed)
+}
+
+/// Returns the name of this machine, looked up once.
+pub fn hostname() -> Option<&'static str> {
+    HOSTNAME.as_deref()
+}
+
+/// Returns the name of the current user, looked up once from the environment.
+pub fn username() -> Option<&'static str> {
+    USERNAME.as_deref()
+}
+
+fn lookup_hostname() -> Option<String> {
+    let from_env = ["HOSTNAME", "COMPUTERNAME"]
+        .iter()
+        .find_map(|var| std::env::var(var).ok());
+    let from_file = || {
+        ["/proc/sys/kernel/hostname", "/etc/hostname"]
+            .iter()
+            .find_map(|path| std::fs::read_to_string(path).ok())
+    };
+    from_env
+        .or_else(from_file)
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+}
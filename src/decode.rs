@@ -0,0 +1,179 @@
+//! Reading binary log files written by [`binlog`](crate::binlog).
+//!
+//! # Example
+//! ```rust,no_run
+//! use verbosio::decode::Decoder;
+//!
+//! let file = std::io::BufReader::new(std::fs::File::open("build.vrbl").unwrap());
+//! let decoder = Decoder::new(file).unwrap();
+//! println!("run {}", decoder.run_id());
+//! for record in decoder {
+//!     let record = record.unwrap();
+//!     println!("{:?} {}", record.level, record.message);
+//! }
+//! ```
+use std::fs::File;
+use std::io::{self, BufReader, Read};
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use crate::Level;
+use crate::binlog::{MAGIC, TAG_RECORD, TAG_STRING, VERSION};
+
+/// A record read back from a binary log.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodedRecord {
+    pub time: SystemTime,
+    pub level: Option<Level>,
+    pub target: String,
+    pub message: String,
+    /// Global and context fields, in that order.
+    pub fields: Vec<(String, String)>,
+    pub thread_label: Option<String>,
+    pub span_id: Option<u64>,
+}
+
+/// Iterates over the records of a binary log.
+pub struct Decoder<R> {
+    reader: R,
+    strings: Vec<String>,
+    last_micros: u64,
+    run_id: String,
+}
+
+/// Reads all records of the binary log at `path`.
+pub fn read_file<P: AsRef<Path>>(path: P) -> io::Result<Vec<DecodedRecord>> {
+    Decoder::new(BufReader::new(File::open(path)?))?.collect()
+}
+
+impl<R: Read> Decoder<R> {
+    /// Reads the file header and prepares decoding the records.
+    pub fn new(mut reader: R) -> io::Result<Self> {
+        let mut magic = [0u8; 4];
+        reader.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(invalid("not a verbosio binary log"));
+        }
+        let version = read_byte(&mut reader)?.ok_or_else(|| invalid("truncated header"))?;
+        if version != VERSION {
+            return Err(invalid("unsupported binary log version"));
+        }
+        let last_micros = read_varint(&mut reader)?;
+        let run_id = read_string(&mut reader)?;
+        Ok(Decoder {
+            reader,
+            strings: Vec::new(),
+            last_micros,
+            run_id,
+        })
+    }
+
+    /// ID of the run that wrote the log.
+    pub fn run_id(&self) -> &str {
+        &self.run_id
+    }
+
+    fn read_record(&mut self) -> io::Result<Option<DecodedRecord>> {
+        loop {
+            match read_byte(&mut self.reader)? {
+                None => return Ok(None),
+                Some(TAG_STRING) => {
+                    let value = read_string(&mut self.reader)?;
+                    self.strings.push(value);
+                }
+                Some(TAG_RECORD) => break,
+                Some(_) => return Err(invalid("unknown entry tag")),
+            }
+        }
+        let delta = unzigzag(read_varint(&mut self.reader)?);
+        self.last_micros = self.last_micros.wrapping_add_signed(delta);
+        let level = match read_byte(&mut self.reader)? {
+            Some(0) => None,
+            Some(1) => Some(Level::Error),
+            Some(2) => Some(Level::Warn),
+            Some(3) => Some(Level::Info),
+            Some(4) => Some(Level::Debug),
+            Some(5) => Some(Level::Trace),
+            _ => return Err(invalid("unknown level")),
+        };
+        let target = self.read_string_ref()?;
+        let message = read_string(&mut self.reader)?;
+        let count = read_varint(&mut self.reader)?;
+        let mut fields = Vec::new();
+        for _ in 0..count {
+            let key = self.read_string_ref()?;
+            fields.push((key, read_string(&mut self.reader)?));
+        }
+        let thread_label = match read_varint(&mut self.reader)? {
+            0 => None,
+            id => Some(self.string(id - 1)?),
+        };
+        let span_id = read_varint(&mut self.reader)?.checked_sub(1);
+        Ok(Some(DecodedRecord {
+            time: UNIX_EPOCH + Duration::from_micros(self.last_micros),
+            level,
+            target,
+            message,
+            fields,
+            thread_label,
+            span_id,
+        }))
+    }
+
+    fn read_string_ref(&mut self) -> io::Result<String> {
+        let id = read_varint(&mut self.reader)?;
+        self.string(id)
+    }
+
+    fn string(&self, id: u64) -> io::Result<String> {
+        self.strings
+            .get(id as usize)
+            .cloned()
+            .ok_or_else(|| invalid("undefined string reference"))
+    }
+}
+
+impl<R: Read> Iterator for Decoder<R> {
+    type Item = io::Result<DecodedRecord>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.read_record().transpose()
+    }
+}
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+}
+
+fn read_byte(reader: &mut impl Read) -> io::Result<Option<u8>> {
+    let mut byte = [0u8];
+    match reader.read(&mut byte)? {
+        0 => Ok(None),
+        _ => Ok(Some(byte[0])),
+    }
+}
+
+fn read_varint(reader: &mut impl Read) -> io::Result<u64> {
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+        let byte = read_byte(reader)?.ok_or_else(|| invalid("truncated varint"))?;
+        value |= u64::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err(invalid("varint too long"))
+}
+
+fn read_string(reader: &mut impl Read) -> io::Result<String> {
+    let len = read_varint(reader)?;
+    let mut bytes = Vec::new();
+    reader.take(len).read_to_end(&mut bytes)?;
+    if bytes.len() as u64 != len {
+        return Err(invalid("truncated string"));
+    }
+    String::from_utf8(bytes).map_err(|_| invalid("string is not UTF-8"))
+}
+
+fn unzigzag(value: u64) -> i64 {
+    ((value >> 1) as i64) ^ -((value & 1) as i64)
+}
@@ -0,0 +1,88 @@
+//! Bridge from [`defmt`](https://defmt.ferrous-systems.com) device logs to
+//! verbosio, for host-side flash and monitor tools.
+//!
+//! Raw bytes received from the device are decoded with the defmt table of
+//! the firmware ELF and re-emitted with verbosio's level tags, formatting
+//! and sinks, so device and host messages form one log stream.
+//!
+//! Device levels are gated on the global verbosity: `error`, `warn` and
+//! `info` frames need verbosity ≥ 1, `debug` ≥ 3 and `trace` ≥ 4, see
+//! [`levels`](crate::levels).
+//!
+//! # Example
+//! ```rust,no_run
+//! use verbosio::defmt_bridge::{DefmtBridge, Table};
+//!
+//! let elf = std::fs::read("firmware.elf").unwrap();
+//! let table = Table::parse(&elf).unwrap().expect(".defmt section missing");
+//! let mut bridge = DefmtBridge::new(&table);
+//!
+//! # let bytes_from_probe: Vec<u8> = Vec::new();
+//! bridge.feed(&bytes_from_probe).unwrap();
+//! ```
+use defmt_decoder::{DecodeError, Frame, StreamDecoder};
+use defmt_parser::Level as DeviceLevel;
+use crate::{Level, levels};
+
+pub use defmt_decoder::Table;
+
+/// Decodes a byte stream of defmt frames and logs each complete frame.
+pub struct DefmtBridge<'t> {
+    decoder: Box<dyn StreamDecoder + Send + Sync + 't>,
+}
+
+impl<'t> DefmtBridge<'t> {
+    /// Creates a bridge decoding frames with `table`.
+    pub fn new(table: &'t Table) -> Self {
+        DefmtBridge {
+            decoder: table.new_stream_decoder(),
+        }
+    }
+
+    /// Pushes received bytes and logs every frame that is now complete.
+    ///
+    /// Returns the number of frames decoded. Incomplete trailing data is kept
+    /// for the next call. On `DecodeError::Malformed` the frames decoded so
+    /// far have been logged; call `feed(&[])` to continue after the bad frame.
+    pub fn feed(&mut self, bytes: &[u8]) -> Result<usize, DecodeError> {
+        self.decoder.received(bytes);
+        let mut count = 0;
+        loop {
+            match self.decoder.decode() {
+                Ok(frame) => {
+                    emit_frame(&frame);
+                    count += 1;
+                }
+                Err(DecodeError::UnexpectedEof) => return Ok(count),
+                Err(err) => return Err(err),
+            }
+        }
+    }
+}
+
+/// Logs a single decoded frame if the verbosity is high enough.
+///
+/// The device timestamp, if any, is prepended to the message.
+pub fn emit_frame(frame: &Frame<'_>) {
+    let (level, verbosity) = match frame.level() {
+        Some(DeviceLevel::Error) => (Some(Level::Error), levels::NORMAL),
+        Some(DeviceLevel::Warn) => (Some(Level::Warn), levels::NORMAL),
+        Some(DeviceLevel::Info) => (Some(Level::Info), levels::NORMAL),
+        Some(DeviceLevel::Debug) => (Some(Level::Debug), levels::DEBUG),
+        Some(DeviceLevel::Trace) => (Some(Level::Trace), levels::TRACE),
+        None => (None, levels::NORMAL),
+    };
+    if !crate::filter::level_enabled("defmt", level, verbosity) {
+        return;
+    }
+    match frame.display_timestamp() {
+        Some(timestamp) => crate::sink::emit_with_fields(
+            level,
+            "defmt",
+            format_args!("{} {}", timestamp, frame.display_message()),
+            Vec::new(),
+            None,
+        ),
+        None => crate::sink::emit_with_fields(level, "defmt", format_args!("{}", frame.display_message()), Vec::new(), None),
+    }
+}
@@ -0,0 +1,56 @@
+//! Rendering of [`miette`](https://crates.io/crates/miette) diagnostics.
+//!
+//! Diagnostics are drawn with miette's graphical handler (labels, source
+//! snippets, help text) and printed with the same level tags, timestamps and
+//! streams as the logging macros. Use [`vmiette!`](crate::vmiette) to gate
+//! them on verbosity.
+use miette::{Diagnostic, GraphicalReportHandler, GraphicalTheme, Report, Severity};
+use crate::Level;
+use crate::util::{format_level, format_time};
+
+/// Renders a diagnostic with miette's graphical handler.
+///
+/// Colors are only used if the `color` feature is enabled.
+pub fn render_miette(diagnostic: &dyn Diagnostic) -> String {
+    #[cfg(feature = "color")]
+    let theme = GraphicalTheme::unicode();
+    #[cfg(not(feature = "color"))]
+    let theme = GraphicalTheme::unicode_nocolor();
+
+    let mut out = String::new();
+    if GraphicalReportHandler::new_themed(theme)
+        .render_report(&mut out, diagnostic)
+        .is_err()
+    {
+        out = diagnostic.to_string();
+    }
+    out
+}
+
+/// Prints a `miette::Report`, tagged by its severity.
+///
+/// Errors (and diagnostics without a severity) are printed to stderr as
+/// `[ERROR]`, warnings to stdout as `[WARN]` and advice to stdout as `[INFO]`.
+pub fn emit_miette(report: &Report) {
+    let diagnostic: &dyn Diagnostic = report.as_ref();
+    let rendered = render_miette(diagnostic);
+    let rendered = rendered.trim_start();
+    let level = report_level(report);
+    crate::tally::count(Some(level));
+    let line = format!("{}{}{}", format_level(level.as_str()), format_time(), rendered);
+    if level == Level::Error {
+        eprintln!("{}", line);
+    } else {
+        println!("{}", line);
+    }
+}
+
+/// The level a report is printed with: `ERROR` for errors and diagnostics
+/// without a severity, `WARN` for warnings and `INFO` for advice.
+pub fn report_level(report: &Report) -> Level {
+    match report.severity().unwrap_or(Severity::Error) {
+        Severity::Error => Level::Error,
+        Severity::Warning => Level::Warn,
+        Severity::Advice => Level::Info,
+    }
+}
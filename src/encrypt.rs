@@ -0,0 +1,203 @@
+//! Encrypted log files for sensitive operational detail.
+//!
+//! [`set_encrypted_log_file`] registers a log file whose lines are
+//! encrypted with AES-256-GCM under a user-supplied key, so that the log can
+//! be kept on shared machines without exposing its contents. Like the plain
+//! log file, it receives every message in the [file format](crate::sink::set_file_format),
+//! and records routed away from files by a [`layer`](crate::layer) are left
+//! out. Read it back with [`decrypt_log_file`].
+//!
+//! # Format
+//!
+//! The file starts with the magic bytes `VRBE` and a version byte, followed
+//! by one frame per line: a random 12-byte nonce, the length of the
+//! ciphertext as a little-endian `u32` and the ciphertext including its
+//! authentication tag.
+//!
+//! # Example
+//! ```rust
+//! use verbosio::{encrypt, set_verbosity, vinfo};
+//!
+//! set_verbosity!(1);
+//! let key = encrypt::generate_key();
+//! let path = std::env::temp_dir().join("verbosio-doc.vrbe");
+//! # let _ = std::fs::remove_file(&path);
+//! encrypt::set_encrypted_log_file(&path, &key).unwrap();
+//! vinfo!("rotated credentials for {}", "db-primary");
+//! encrypt::clear_encrypted_log_file();
+//!
+//! let lines = encrypt::decrypt_log_file(&path, &key).unwrap();
+//! assert!(lines[0].ends_with("rotated credentials for db-primary"));
+//! ```
+//!
+//! # Features
+//! Requires the `"encrypt"` feature.
+use std::fs::{File, OpenOptions};
+use std::io::{self, Read, Write};
+use std::path::Path;
+use std::sync::Mutex;
+use aes_gcm::aead::{Aead, Generate, Key, KeyInit, Nonce};
+use aes_gcm::Aes256Gcm;
+
+const MAGIC: &[u8; 4] = b"VRBE";
+const VERSION: u8 = 1;
+const NONCE_LEN: usize = 12;
+
+static ENCRYPTED_LOG: Mutex<Option<EncryptedLog>> = Mutex::new(None);
+
+struct EncryptedLog {
+    file: File,
+    cipher: Aes256Gcm,
+}
+
+/// Returns a new random 256-bit key.
+///
+/// Store it somewhere safe: without it the log can't be read.
+pub fn generate_key() -> [u8; 32] {
+    Key::<Aes256Gcm>::generate().into()
+}
+
+/// Appends all subsequent log messages, encrypted with `key`, to the file at
+/// `path`.
+///
+/// The file is created if it does not exist. Appending to an existing file
+/// requires it to be an encrypted log; whether it was written with the same
+/// key is only noticed when decrypting. A frame cut off at the end of the
+/// file, e.g. by a crash while writing, is removed first, so new frames
+/// don't get mixed up with its remains. A previously registered encrypted
+/// log file is closed.
+///
+/// # Example
+/// ```rust
+/// use std::io::Write;
+/// use verbosio::{encrypt, set_verbosity, vinfo};
+///
+/// set_verbosity!(1);
+/// let key = encrypt::generate_key();
+/// let path = std::env::temp_dir().join("verbosio-doc-append.vrbe");
+/// # let _ = std::fs::remove_file(&path);
+/// encrypt::set_encrypted_log_file(&path, &key).unwrap();
+/// vinfo!("first run");
+/// encrypt::clear_encrypted_log_file();
+/// // a crash in the middle of a frame
+/// std::fs::OpenOptions::new().append(true).open(&path).unwrap().write_all(&[7; 20]).unwrap();
+///
+/// encrypt::set_encrypted_log_file(&path, &key).unwrap();
+/// vinfo!("second run");
+/// encrypt::clear_encrypted_log_file();
+/// assert_eq!(encrypt::decrypt_log_file(&path, &key).unwrap().len(), 2);
+/// ```
+pub fn set_encrypted_log_file<P: AsRef<Path>>(path: P, key: &[u8; 32]) -> io::Result<()> {
+    let mut file = OpenOptions::new().create(true).read(true).append(true).open(path)?;
+    if file.metadata()?.len() == 0 {
+        let mut header = MAGIC.to_vec();
+        header.push(VERSION);
+        file.write_all(&header)?;
+    } else {
+        let mut data = Vec::new();
+        file.read_to_end(&mut data)?;
+        let mut frames = data.as_slice();
+        read_header(&mut frames)?;
+        let end = data.len() - frames.len() + complete_frames_len(frames);
+        if end < data.len() {
+            file.set_len(end as u64)?;
+        }
+    }
+    let cipher = Aes256Gcm::new(&Key::<Aes256Gcm>::from(*key));
+    *ENCRYPTED_LOG.lock().unwrap_or_else(|e| e.into_inner()) = Some(EncryptedLog { file, cipher });
+    Ok(())
+}
+
+/// Closes the registered encrypted log file, if any.
+pub fn clear_encrypted_log_file() {
+    *ENCRYPTED_LOG.lock().unwrap_or_else(|e| e.into_inner()) = None;
+}
+
+/// Decrypts the encrypted log file at `path` and returns its lines.
+///
+/// Fails with [`io::ErrorKind::InvalidData`] if the file isn't an encrypted
+/// log, was written with a different key or was tampered with. A frame cut
+/// off at the end, e.g. by a crash while writing, is ignored.
+pub fn decrypt_log_file<P: AsRef<Path>>(path: P, key: &[u8; 32]) -> io::Result<Vec<String>> {
+    let mut data = Vec::new();
+    File::open(path)?.read_to_end(&mut data)?;
+    let mut data = data.as_slice();
+    read_header(&mut data)?;
+    let cipher = Aes256Gcm::new(&Key::<Aes256Gcm>::from(*key));
+    let mut lines = Vec::new();
+    while data.len() >= NONCE_LEN + 4 {
+        let (nonce, rest) = data.split_at(NONCE_LEN);
+        let (len, rest) = rest.split_at(4);
+        let len = u32::from_le_bytes(len.try_into().unwrap()) as usize;
+        if rest.len() < len {
+            break;
+        }
+        let (ciphertext, rest) = rest.split_at(len);
+        let nonce = Nonce::<Aes256Gcm>::try_from(nonce).unwrap();
+        let plaintext = cipher
+            .decrypt(&nonce, ciphertext)
+            .map_err(|_| invalid("wrong key or corrupted encrypted log"))?;
+        lines.push(String::from_utf8(plaintext).map_err(|_| invalid("encrypted log line is not UTF-8"))?);
+        data = rest;
+    }
+    Ok(lines)
+}
+
+pub(crate) fn is_active() -> bool {
+    ENCRYPTED_LOG.lock().unwrap_or_else(|e| e.into_inner()).is_some()
+}
+
+/// Encrypts `text` as one line and appends it to the encrypted log file.
+pub(crate) fn write(text: &str) {
+    let mut encrypted_log = ENCRYPTED_LOG.lock().unwrap_or_else(|e| e.into_inner());
+    let Some(log) = encrypted_log.as_mut() else {
+        return;
+    };
+    let nonce = Nonce::<Aes256Gcm>::generate();
+    let Ok(ciphertext) = log.cipher.encrypt(&nonce, text.as_bytes()) else {
+        return;
+    };
+    let mut frame = Vec::with_capacity(NONCE_LEN + 4 + ciphertext.len());
+    frame.extend_from_slice(&nonce);
+    frame.extend_from_slice(&(ciphertext.len() as u32).to_le_bytes());
+    frame.extend_from_slice(&ciphertext);
+    let _ = log.file.write_all(&frame);
+}
+
+pub(crate) fn flush() {
+    if let Some(log) = ENCRYPTED_LOG.lock().unwrap_or_else(|e| e.into_inner()).as_mut() {
+        let _ = log.file.flush();
+    }
+}
+
+/// Length of the complete frames at the start of `data`, leaving out a
+/// frame cut off at the end.
+fn complete_frames_len(mut data: &[u8]) -> usize {
+    let mut len = 0;
+    while data.len() >= NONCE_LEN + 4 {
+        let ciphertext_len = u32::from_le_bytes(data[NONCE_LEN..NONCE_LEN + 4].try_into().unwrap()) as usize;
+        let frame_len = NONCE_LEN + 4 + ciphertext_len;
+        if data.len() < frame_len {
+            break;
+        }
+        len += frame_len;
+        data = &data[frame_len..];
+    }
+    len
+}
+
+fn read_header<R: Read>(reader: &mut R) -> io::Result<()> {
+    let mut header = [0u8; 5];
+    reader.read_exact(&mut header).map_err(|_| invalid("not an encrypted verbosio log"))?;
+    if &header[..4] != MAGIC {
+        return Err(invalid("not an encrypted verbosio log"));
+    }
+    if header[4] != VERSION {
+        return Err(invalid("unsupported encrypted log version"));
+    }
+    Ok(())
+}
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
@@ -0,0 +1,500 @@
+//! Verbosity from environment variables, see [`verbose_env!`](crate::verbose_env).
+//!
+//! Besides plain numbers, the names of the [`Verbosity`](crate::levels::Verbosity)
+//! tiers are accepted, so users don't need to know the numeric levels of a
+//! tool:
+//!
+//! | Value                                   | Verbosity |
+//! |-----------------------------------------|-----------|
+//! | `0`, `1`, `2`, ... (up to `255`)        | as given  |
+//! | `quiet`, `silent`, `off`, `false`, `no` | `0`       |
+//! | `normal`, `info`, `on`, `true`, `yes`   | `1`       |
+//! | `verbose`                               | `2`       |
+//! | `debug`                                 | `3`       |
+//! | `trace`                                 | `4`       |
+//!
+//! Names are case-insensitive and surrounding whitespace is ignored.
+//! Numbers take precedence, so an integer is never interpreted as a name.
+//!
+//! `quiet` and the other names of verbosity `0` switch to [quiet
+//! mode](crate::set_quiet), where errors still print, while the number `0`
+//! hides everything.
+//!
+//! The variables read by `verbose_env!` may also hold per-target directives
+//! in the style of `RUST_LOG`, e.g. `VERBOSE=3,my_app::net=1,hyper=0`, see
+//! [`filter`](crate::filter).
+//!
+//! Many CI setups conventionally set `QUIET=1`. Every form of `verbose_env!`
+//! honors it as an override, see [`quiet_from_env`].
+//!
+//! [`init_layered`] (`verbose_env!(cli: flag, default: 1)`) combines a
+//! command line value, `QUIET`, `VERBOSE` and `RUST_LOG` with a default, and
+//! reports which of them was used.
+//!
+//! Besides the verbosity, `verbose_env!()` applies these settings, see
+//! [`init_from_env`]:
+//!
+//! | Variable     | Setting                                            |
+//! |--------------|----------------------------------------------------|
+//! | `VERBOSE`    | Verbosity and per-target directives                |
+//! | `QUIET`      | Quiet mode                                         |
+//! | `COLOR`      | `auto`, `always` or `never`, see [`ColorMode`](crate::color::ColorMode) |
+//! | `LOG_FORMAT` | Terminal format: `human`, `json`, `logfmt` or a [template](crate::template) |
+//! | `LOG_FILE`   | Path of a log file to append to                    |
+//!
+//! Several tools on one machine would all react to the same variables. With
+//! an application prefix, `verbose_env!(prefix: "MYTOOL")` reads those of
+//! that tool instead, `MYTOOL_VERBOSE`, `MYTOOL_QUIET` and so on, see
+//! [`init_with_prefix`]; only `QUIET` applies to all tools.
+//! [`inherit_verbosity`](crate::command::VerbosioCommandExt::inherit_verbosity)
+//! passes the settings on to child processes under the same names.
+//!
+//! The `VERBOSIO_*` overrides of the detected [terminal
+//! capabilities](mod@crate::capabilities) describe the terminal rather than a
+//! tool, and are not prefixed.
+//!
+//! Small programs without an argument parser can read the usual `-v`/`-q`
+//! flags from the command line instead, see [`verbosity_from_args`] and
+//! [`verbose_args!`](crate::verbose_args).
+use std::borrow::Cow;
+use std::sync::RwLock;
+use crate::color::ColorMode;
+use crate::format::Format;
+use crate::levels::{self, Verbosity};
+
+/// Parses a verbosity value as described in the [module docs](self).
+///
+/// # Example
+/// ```rust
+/// use verbosio::env::parse_verbosity;
+///
+/// assert_eq!(parse_verbosity("2"), Some(2));
+/// assert_eq!(parse_verbosity("Debug"), Some(3));
+/// assert_eq!(parse_verbosity("yes"), Some(1));
+/// assert_eq!(parse_verbosity("quiet"), Some(0));
+/// assert_eq!(parse_verbosity("loud"), None);
+/// ```
+pub fn parse_verbosity(value: &str) -> Option<u8> {
+    let value = value.trim();
+    if let Ok(verbosity) = value.parse::<u8>() {
+        return Some(verbosity);
+    }
+    value.parse::<Verbosity>().ok().map(u8::from)
+}
+
+/// Reads the verbosity from the environment variable `name`.
+///
+/// Returns `0` if the variable is unset or empty. An unparseable value also
+/// gives `0`, and is reported with a `[DEBUG]` message. Per-target
+/// directives like `3,hyper=0` are installed in the [`filter`](crate::filter),
+/// and the global part is returned.
+pub fn verbosity_from_env(name: &str) -> u8 {
+    verbosity_from_env_chain(&[name])
+}
+
+/// Reads the verbosity from the first of `names` that is set and not empty.
+///
+/// Useful to let a tool-specific variable override a shared one, e.g.
+/// `["MYTOOL_VERBOSE", "VERBOSE"]`. Later names are not consulted once a
+/// variable is set, even if its value can't be parsed.
+///
+/// # Example
+/// ```rust
+/// use verbosio::env::verbosity_from_env_chain;
+///
+/// unsafe {
+///     std::env::set_var("DOCTOOL_VERBOSE", "debug");
+///     std::env::set_var("VERBOSE", "1");
+/// }
+/// assert_eq!(verbosity_from_env_chain(&["DOCTOOL_VERBOSE", "VERBOSE"]), 3);
+/// ```
+pub fn verbosity_from_env_chain(names: &[&str]) -> u8 {
+    match setting_from_chain(names) {
+        Setting::Verbosity(verbosity) => verbosity,
+        Setting::Unset | Setting::Invalid | Setting::Quiet => 0,
+    }
+}
+
+/// Applies the variables listed in the [module docs](self), see
+/// [`verbose_env!()`](crate::verbose_env).
+///
+/// Unset and empty variables leave their setting alone, except for the
+/// verbosity, which is `0` then, as with [`verbosity_from_env`]. Invalid
+/// values are reported with a `[DEBUG]` message; a log file that can't be
+/// opened with a `[WARN]` message.
+pub fn init_from_env() {
+    *PREFIX.write().unwrap_or_else(|e| e.into_inner()) = None;
+    apply_settings(&str::to_string);
+}
+
+/// Sets the verbosity from the first of `names` that is set and not empty,
+/// as [`verbosity_from_env_chain`] reads it, but switches to [quiet
+/// mode](crate::set_quiet) for `quiet` and the other names of verbosity `0`.
+///
+/// `QUIET` overrides the variables, as for `verbose_env!()`, see
+/// [`quiet_from_env`].
+///
+/// # Example
+/// ```rust
+/// use verbosio::env::init_from_env_chain;
+/// use verbosio::filter::is_quiet;
+///
+/// unsafe {std::env::set_var("DOCTOOL_VERBOSE", "quiet");}
+/// init_from_env_chain(&["DOCTOOL_VERBOSE", "VERBOSE"]);
+/// assert!(is_quiet());
+///
+/// unsafe {
+///     std::env::set_var("DOCTOOL_VERBOSE", "3");
+///     std::env::set_var("QUIET", "1");
+/// }
+/// init_from_env_chain(&["DOCTOOL_VERBOSE"]);
+/// assert!(is_quiet());
+/// # unsafe {std::env::remove_var("QUIET");}
+/// # verbosio::set_quiet!(false);
+/// ```
+pub fn init_from_env_chain(names: &[&str]) {
+    if quiet_from_env() {
+        crate::set_quiet!();
+        return;
+    }
+    match setting_from_chain(names) {
+        Setting::Quiet => crate::set_quiet!(),
+        Setting::Verbosity(verbosity) => crate::set_verbosity!(verbosity),
+        Setting::Unset | Setting::Invalid => crate::set_verbosity!(0),
+    }
+}
+
+/// What a verbosity variable asks for.
+enum Setting {
+    /// The variable is unset or empty.
+    Unset,
+    /// The value is not a verbosity, which was reported with a `[DEBUG]`
+    /// message.
+    Invalid,
+    /// One of the names of verbosity `0`, like `quiet`.
+    Quiet,
+    Verbosity(u8),
+}
+
+/// The setting of the first of `names` that is set and not empty.
+fn setting_from_chain(names: &[&str]) -> Setting {
+    for name in names {
+        match setting_from_var(name) {
+            Setting::Unset => continue,
+            setting => return setting,
+        }
+    }
+    Setting::Unset
+}
+
+/// The setting in the variable `name`. Per-target directives are installed
+/// in the [`filter`](crate::filter).
+fn setting_from_var(name: &str) -> Setting {
+    let Some(value) = var(name) else {
+        return Setting::Unset;
+    };
+    match crate::filter::parse_directives(&value) {
+        Ok((global, directives)) => {
+            if !directives.is_empty() {
+                crate::filter::set_directives(directives);
+            }
+            let quiet = value
+                .split(',')
+                .filter(|entry| !entry.contains('='))
+                .any(is_quiet_name);
+            match global {
+                Some(levels::QUIET) if quiet => Setting::Quiet,
+                global => Setting::Verbosity(global.unwrap_or(0)),
+            }
+        }
+        Err(err) => {
+            crate::vebug!("{}={:?} is not a verbosity level ({}), using 0", name, value, err);
+            Setting::Invalid
+        }
+    }
+}
+
+/// Whether `value` is one of the names of verbosity `0`, which ask for
+/// quiet mode rather than no output at all.
+pub(crate) fn is_quiet_name(value: &str) -> bool {
+    value.trim().parse::<u8>().is_err() && matches!(value.parse(), Ok(Verbosity::Quiet))
+}
+
+/// Where the verbosity set by [`init_layered`] came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VerbositySource {
+    /// The value given on the command line.
+    Cli,
+    /// The `QUIET` variable, which switched to [quiet mode](crate::set_quiet).
+    Quiet,
+    /// The `VERBOSE` variable.
+    Verbose,
+    /// The `RUST_LOG` variable.
+    RustLog,
+    /// None of the above, so the default was used.
+    Default,
+}
+
+impl std::fmt::Display for VerbositySource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            VerbositySource::Cli => "command line",
+            VerbositySource::Quiet => "QUIET",
+            VerbositySource::Verbose => "VERBOSE",
+            VerbositySource::RustLog => "RUST_LOG",
+            VerbositySource::Default => "default",
+        })
+    }
+}
+
+/// Sets the verbosity from the first source that provides one, and returns
+/// which one that was:
+///
+/// 1. `cli`, e.g. the value of a `--verbose` flag, if it was given
+/// 2. `QUIET`, see [`quiet_from_env`]
+/// 3. `VERBOSE`, including per-target directives
+/// 4. `RUST_LOG`, where `error`, `warn` and `info` count as
+///    [`NORMAL`](crate::levels::NORMAL), `debug` and `trace` as
+///    [`DEBUG`](crate::levels::DEBUG) and [`TRACE`](crate::levels::TRACE),
+///    and a target without a level as `TRACE`
+/// 5. `default`
+///
+/// Empty variables are skipped. A `VERBOSE` or `RUST_LOG` that can't be
+/// understood is skipped as well and reported with a `[DEBUG]` message.
+///
+/// # Example
+/// ```rust
+/// use verbosio::env::{init_layered, VerbositySource};
+/// use verbosio::get_verbosity;
+///
+/// unsafe {
+///     std::env::remove_var("VERBOSE");
+///     std::env::set_var("RUST_LOG", "warn,my_app::net=debug");
+/// }
+/// assert_eq!(init_layered(None, 1), VerbositySource::RustLog);
+/// assert!(verbosio::filter::enabled("my_app::net", 3));
+/// unsafe {std::env::set_var("VERBOSE", "loud");}
+/// assert_eq!(init_layered(None, 1), VerbositySource::RustLog);
+/// # unsafe {std::env::remove_var("VERBOSE");}
+/// assert_eq!(init_layered(Some(2), 1), VerbositySource::Cli);
+/// assert_eq!(get_verbosity!(), 2);
+/// ```
+pub fn init_layered(cli: Option<u8>, default: u8) -> VerbositySource {
+    if let Some(verbosity) = cli {
+        crate::set_verbosity!(verbosity);
+        return VerbositySource::Cli;
+    }
+    if quiet_from_env() {
+        crate::set_quiet!();
+        return VerbositySource::Quiet;
+    }
+    match setting_from_var("VERBOSE") {
+        Setting::Quiet => {
+            crate::set_quiet!();
+            return VerbositySource::Verbose;
+        }
+        Setting::Verbosity(verbosity) => {
+            crate::set_verbosity!(verbosity);
+            return VerbositySource::Verbose;
+        }
+        Setting::Invalid | Setting::Unset => {}
+    }
+    if let Some(verbosity) = verbosity_from_rust_log(default) {
+        crate::set_verbosity!(verbosity);
+        return VerbositySource::RustLog;
+    }
+    crate::set_verbosity!(default);
+    VerbositySource::Default
+}
+
+/// Reads `RUST_LOG` as described in [`init_layered`]. The global verbosity
+/// is `default` if it only names targets.
+fn verbosity_from_rust_log(default: u8) -> Option<u8> {
+    let value = var("RUST_LOG")?;
+    let spec = value
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| match entry.split_once('=') {
+            Some((target, level)) => format!("{}={}", target, rust_log_level(level)),
+            None if parse_verbosity(&rust_log_level(entry)).is_some() => rust_log_level(entry).into_owned(),
+            // a bare target enables everything it logs
+            None => format!("{}={}", entry, levels::TRACE),
+        })
+        .collect::<Vec<_>>()
+        .join(",");
+    match crate::filter::parse_directives(&spec) {
+        Ok((global, directives)) => {
+            if !directives.is_empty() {
+                crate::filter::set_directives(directives);
+            }
+            Some(global.unwrap_or(default))
+        }
+        Err(err) => {
+            crate::vebug!("RUST_LOG={:?} is not understood ({}), ignored", value, err);
+            None
+        }
+    }
+}
+
+/// Maps the levels of `RUST_LOG` to verbosities.
+fn rust_log_level(level: &str) -> Cow<'_, str> {
+    let level = level.trim();
+    let verbosity = match level.to_ascii_lowercase().as_str() {
+        "error" | "warn" | "info" => levels::NORMAL,
+        "debug" => levels::DEBUG,
+        "trace" => levels::TRACE,
+        _ => return Cow::Borrowed(level),
+    };
+    Cow::Owned(verbosity.to_string())
+}
+
+/// Whether the `QUIET` environment variable asks for quiet output.
+///
+/// `QUIET` counts if it is set to anything but an empty value, `0`, `false`,
+/// `no` or `off`. `verbose_env!` then switches to [quiet
+/// mode](crate::set_quiet), regardless of `VERBOSE` or the variables it was
+/// given; with `cli:`, only if no command line value was given.
+///
+/// # Example
+/// ```rust
+/// use verbosio::{get_verbosity, verbose_env};
+///
+/// unsafe {
+///     std::env::set_var("VERBOSE", "2");
+///     std::env::set_var("QUIET", "1");
+/// }
+/// verbose_env!();
+/// assert_eq!(get_verbosity!(), 0);
+/// # unsafe { std::env::remove_var("QUIET"); }
+/// ```
+pub fn quiet_from_env() -> bool {
+    quiet_from_var("QUIET")
+}
+
+fn quiet_from_var(name: &str) -> bool {
+    let value = std::env::var(name).unwrap_or_default();
+    !matches!(value.trim().to_ascii_lowercase().as_str(), "" | "0" | "false" | "no" | "off")
+}
+
+/// Like [`init_from_env`] for the variables of the application prefix
+/// `prefix`, e.g. `MYTOOL_VERBOSE` for `"MYTOOL"`; `QUIET` applies as well.
+///
+/// Child processes started with
+/// [`inherit_verbosity`](crate::command::VerbosioCommandExt::inherit_verbosity)
+/// get the settings in the prefixed variables.
+///
+/// # Example
+/// ```rust
+/// use verbosio::{get_verbosity, verbose_env};
+///
+/// unsafe {
+///     std::env::set_var("DOCTOOL_VERBOSE", "debug");
+///     std::env::set_var("DOCTOOL_COLOR", "never");
+/// }
+/// verbose_env!(prefix: "DOCTOOL");
+/// assert_eq!(get_verbosity!(), 3);
+/// assert!(!verbosio::color::colors_enabled());
+/// ```
+pub fn init_with_prefix(prefix: &str) {
+    *PREFIX.write().unwrap_or_else(|e| e.into_inner()) = Some(prefix.to_string());
+    apply_settings(&|setting| format!("{}_{}", prefix, setting));
+}
+
+/// The prefix of the variables last applied, see [`var_name`].
+static PREFIX: RwLock<Option<String>> = RwLock::new(None);
+
+/// The name of the variable for `setting`, e.g. `MYTOOL_VERBOSE` for
+/// `"VERBOSE"` after [`init_with_prefix`]`("MYTOOL")`.
+pub(crate) fn var_name(setting: &str) -> String {
+    match &*PREFIX.read().unwrap_or_else(|e| e.into_inner()) {
+        Some(prefix) => format!("{}_{}", prefix, setting),
+        None => setting.to_string(),
+    }
+}
+
+/// Applies the variables of the module docs under the names `name` gives.
+fn apply_settings(name: &dyn Fn(&str) -> String) {
+    if quiet_from_var(&name("QUIET")) {
+        crate::set_quiet!();
+    } else {
+        init_from_env_chain(&[&name("VERBOSE")]);
+    }
+    if let Some(value) = var(&name("COLOR")) {
+        match value.parse::<ColorMode>() {
+            Ok(mode) => crate::color::set_color_mode(mode),
+            Err(err) => {
+                crate::vebug!("{}: {}", name("COLOR"), err);
+            }
+        }
+    }
+    if let Some(value) = var(&name("LOG_FORMAT")) {
+        match value.parse::<Format>() {
+            Ok(format) => crate::sink::set_terminal_format(format),
+            Err(err) => {
+                crate::vebug!("{}: {}", name("LOG_FORMAT"), err);
+            }
+        }
+    }
+    if let Some(path) = var(&name("LOG_FILE"))
+        && let Err(err) = crate::sink::set_log_file(&path)
+    {
+        crate::vwarn!("failed to open log file {} from {}: {}", path, name("LOG_FILE"), err);
+    }
+}
+
+fn var(name: &str) -> Option<String> {
+    std::env::var(name).ok().filter(|value| !value.trim().is_empty())
+}
+
+/// Reads the verbosity from command line flags, ignoring everything else.
+///
+/// | Flag                              | Verbosity                       |
+/// |-----------------------------------|---------------------------------|
+/// | `-v`, `-vv`, `--verbose`, ...     | one per `v`, added up           |
+/// | `--verbose=N`                     | `N`, or a name like `debug`     |
+/// | `-q`, `--quiet`                   | `0`, overriding all other flags |
+///
+/// Scanning stops at `--`. Returns `None` if none of the flags is given.
+///
+/// # Example
+/// ```rust
+/// use verbosio::env::verbosity_from_args;
+///
+/// assert_eq!(verbosity_from_args(["-vv", "input.txt", "-v"]), Some(3));
+/// assert_eq!(verbosity_from_args(["--verbose=debug"]), Some(3));
+/// assert_eq!(verbosity_from_args(["-v", "-q"]), Some(0));
+/// assert_eq!(verbosity_from_args(["--", "-v"]), None);
+/// ```
+pub fn verbosity_from_args<I, S>(args: I) -> Option<u8>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let mut verbosity: Option<u8> = None;
+    let mut quiet = false;
+    for arg in args {
+        let arg = arg.as_ref();
+        match arg {
+            "--" => break,
+            "-q" | "--quiet" => quiet = true,
+            "--verbose" => verbosity = Some(verbosity.unwrap_or(0).saturating_add(1)),
+            _ => {
+                if let Some(value) = arg.strip_prefix("--verbose=") {
+                    match parse_verbosity(value) {
+                        Some(value) => verbosity = Some(value),
+                        None => {
+                            crate::vebug!("--verbose={:?} is not a verbosity level, ignored", value);
+                        }
+                    }
+                } else if let Some(vs) = arg.strip_prefix('-')
+                    && !vs.is_empty()
+                    && vs.bytes().all(|b| b == b'v')
+                {
+                    verbosity = Some(verbosity.unwrap_or(0).saturating_add(vs.len().min(u8::MAX as usize) as u8));
+                }
+            }
+        }
+    }
+    if quiet { Some(levels::QUIET) } else { verbosity }
+}
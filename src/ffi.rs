@@ -0,0 +1,68 @@
+//! C API for logging from C/C++ code loaded into a Rust process.
+//!
+//! The functions use the same verbosity, formatting and sinks as the
+//! macros, so plugins written in other languages produce consistent output.
+//! A matching header is shipped in `include/verbosio.h`.
+//!
+//! ```c
+//! #include "verbosio.h"
+//!
+//! verbosio_set_verbosity(2);
+//! verbosio_log(VERBOSIO_LEVEL_WARN, 1, "plugin loaded without config");
+//! ```
+use std::ffi::{c_char, c_int, CStr};
+use crate::Level;
+
+/// Untagged message, like `verbose!`.
+pub const VERBOSIO_LEVEL_NONE: c_int = -1;
+/// `[ERROR]` message, printed to stderr.
+pub const VERBOSIO_LEVEL_ERROR: c_int = 0;
+/// `[WARN]` message.
+pub const VERBOSIO_LEVEL_WARN: c_int = 1;
+/// `[INFO]` message.
+pub const VERBOSIO_LEVEL_INFO: c_int = 2;
+/// `[DEBUG]` message.
+pub const VERBOSIO_LEVEL_DEBUG: c_int = 3;
+/// `[TRACE]` message.
+pub const VERBOSIO_LEVEL_TRACE: c_int = 4;
+
+fn level_from_c(level: c_int) -> Option<Level> {
+    match level {
+        VERBOSIO_LEVEL_ERROR => Some(Level::Error),
+        VERBOSIO_LEVEL_WARN => Some(Level::Warn),
+        VERBOSIO_LEVEL_INFO => Some(Level::Info),
+        VERBOSIO_LEVEL_DEBUG => Some(Level::Debug),
+        VERBOSIO_LEVEL_TRACE => Some(Level::Trace),
+        _ => None,
+    }
+}
+
+/// Logs `msg` at `level` if the verbosity is at least `verbosity`, like the
+/// logging macros with the target `ffi`.
+///
+/// Unknown levels are logged untagged. Invalid UTF-8 is replaced with `�`.
+///
+/// # Safety
+/// `msg` must be null or point to a valid NUL-terminated string. A null
+/// `msg` is ignored.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn verbosio_log(level: c_int, verbosity: u8, msg: *const c_char) {
+    let level = level_from_c(level);
+    if msg.is_null() || !crate::filter::level_enabled("ffi", level, verbosity) {
+        return;
+    }
+    let msg = unsafe { CStr::from_ptr(msg) }.to_string_lossy();
+    crate::sink::emit_with_fields(level, "ffi", format_args!("{}", msg), Vec::new(), None);
+}
+
+/// Sets the global verbosity level.
+#[unsafe(no_mangle)]
+pub extern "C" fn verbosio_set_verbosity(verbosity: u8) {
+    crate::set_verbosity!(verbosity);
+}
+
+/// Returns the global verbosity level.
+#[unsafe(no_mangle)]
+pub extern "C" fn verbosio_get_verbosity() -> u8 {
+    crate::get_verbosity!()
+}
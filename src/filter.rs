@@ -0,0 +1,283 @@
+//! Per-target verbosity, in the style of `RUST_LOG` directives.
+//!
+//! By default every message is checked against the global verbosity. A
+//! directive gives all targets below a module path their own verbosity
+//! instead, e.g. to silence a chatty dependency or to trace a single module.
+//! The longest matching directive wins; targets without one use the global
+//! verbosity, or the thread's own verbosity set with
+//! [`with_thread_verbosity!`](crate::with_thread_verbosity).
+//!
+//! Directives are usually given through the environment:
+//! `VERBOSE=3,my_app::net=1,hyper=0` sets the global verbosity to 3, only
+//! shows `@lvl 1` messages from `my_app::net` and its submodules, and
+//! silences `hyper`. [`verbose_env!`](crate::verbose_env) installs them
+//! automatically.
+//!
+//! All logging macros consult the filter with the `module_path!()` of the
+//! call site, see [`is_enabled!`](crate::is_enabled).
+//!
+//! Each level can also get a verbosity of its own with
+//! [`set_level_threshold`], e.g. to always print warnings and errors.
+//!
+//! In quiet mode ([`set_quiet!`](crate::set_quiet), `-q`), errors logged at
+//! verbosity [`NORMAL`](crate::levels::NORMAL) or below are printed even
+//! though the verbosity is `0`, unless a directive for their target says
+//! otherwise, see [`error_enabled`].
+//!
+//! The verbosity of a running process can be raised and lowered with
+//! `SIGUSR1` and `SIGUSR2` after [`adjust_on_signals`] (`signal` feature).
+//!
+//! # Example
+//! ```rust
+//! use verbosio::{filter, set_verbosity};
+//!
+//! set_verbosity!(1);
+//! filter::add_directive("my_app::net", 3);
+//! assert!(filter::enabled("my_app::net::http", 3));
+//! assert!(!filter::enabled("my_app::db", 3));
+//! filter::clear_directives();
+//! ```
+use std::fmt;
+use std::sync::RwLock;
+use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+use crate::Level;
+
+static DIRECTIVES: RwLock<Vec<Directive>> = RwLock::new(Vec::new());
+
+/// Whether any directives are set, so the common case skips the lock.
+static HAS_DIRECTIVES: AtomicBool = AtomicBool::new(false);
+
+static QUIET: AtomicBool = AtomicBool::new(false);
+
+const UNSET: u8 = u8::MAX;
+
+/// Verbosity thresholds of the levels, indexed by `Level as usize`.
+static THRESHOLDS: [AtomicU8; 5] = [const { AtomicU8::new(UNSET) }; 5];
+
+/// The verbosity for all targets at or below a module path.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Directive {
+    pub target: String,
+    pub verbosity: u8,
+}
+
+impl Directive {
+    fn matches(&self, target: &str) -> bool {
+        target
+            .strip_prefix(self.target.as_str())
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with("::"))
+    }
+}
+
+impl fmt::Display for Directive {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}={}", self.target, self.verbosity)
+    }
+}
+
+/// Parses a comma-separated list of directives like `3,my_app::net=1,hyper=0`.
+///
+/// An entry without a target sets the global verbosity, which is returned
+/// separately. Values accept the same names as
+/// [`parse_verbosity`](crate::env::parse_verbosity).
+///
+/// # Example
+/// ```rust
+/// use verbosio::filter::{parse_directives, Directive};
+///
+/// let (global, directives) = parse_directives("debug,hyper=0").unwrap();
+/// assert_eq!(global, Some(3));
+/// assert_eq!(directives, vec![Directive { target: "hyper".into(), verbosity: 0 }]);
+/// assert!(parse_directives("hyper=loud").is_err());
+/// ```
+pub fn parse_directives(spec: &str) -> Result<(Option<u8>, Vec<Directive>), String> {
+    let mut global = None;
+    let mut directives = Vec::new();
+    for entry in spec.split(',').map(str::trim).filter(|entry| !entry.is_empty()) {
+        match entry.split_once('=') {
+            Some((target, value)) => {
+                let verbosity = crate::env::parse_verbosity(value)
+                    .ok_or_else(|| format!("invalid verbosity {:?} for target {:?}", value.trim(), target.trim()))?;
+                directives.push(Directive {
+                    target: target.trim().to_string(),
+                    verbosity,
+                });
+            }
+            None => {
+                global = Some(crate::env::parse_verbosity(entry).ok_or_else(|| format!("invalid verbosity {:?}", entry))?);
+            }
+        }
+    }
+    Ok((global, directives))
+}
+
+/// Replaces all directives.
+pub fn set_directives(directives: Vec<Directive>) {
+    let mut current = DIRECTIVES.write().unwrap_or_else(|e| e.into_inner());
+    HAS_DIRECTIVES.store(!directives.is_empty(), Ordering::Relaxed);
+    *current = directives;
+}
+
+/// Sets the verbosity of `target` and everything below it, replacing an
+/// existing directive for the same target.
+pub fn add_directive(target: impl Into<String>, verbosity: u8) {
+    let target = target.into();
+    let mut directives = DIRECTIVES.write().unwrap_or_else(|e| e.into_inner());
+    directives.retain(|directive| directive.target != target);
+    directives.push(Directive { target, verbosity });
+    HAS_DIRECTIVES.store(true, Ordering::Relaxed);
+}
+
+/// The directives currently set.
+pub fn directives() -> Vec<Directive> {
+    DIRECTIVES.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// Removes all directives, so every target uses the global verbosity.
+pub fn clear_directives() {
+    set_directives(Vec::new());
+}
+
+/// The verbosity that applies to `target`.
+pub fn verbosity_for(target: &str) -> u8 {
+    directive_verbosity(target).unwrap_or_else(crate::scope::verbosity)
+}
+
+/// The verbosity of the longest directive matching `target`, if any.
+fn directive_verbosity(target: &str) -> Option<u8> {
+    if !HAS_DIRECTIVES.load(Ordering::Relaxed) {
+        return None;
+    }
+    let directives = DIRECTIVES.read().unwrap_or_else(|e| e.into_inner());
+    directives
+        .iter()
+        .filter(|directive| directive.matches(target))
+        .max_by_key(|directive| directive.target.len())
+        .map(|directive| directive.verbosity)
+}
+
+/// Whether a message of `target` at verbosity `lvl` is printed.
+pub fn enabled(target: &str, lvl: u8) -> bool {
+    verbosity_for(target) >= lvl
+}
+
+/// Switches quiet mode on or off, see [`set_quiet!`](crate::set_quiet).
+///
+/// Only the treatment of errors changes; the verbosity is left alone.
+pub fn set_quiet(quiet: bool) {
+    QUIET.store(quiet, Ordering::Relaxed);
+}
+
+/// Whether quiet mode is on.
+pub fn is_quiet() -> bool {
+    QUIET.load(Ordering::Relaxed)
+}
+
+/// Whether an error of `target` at verbosity `lvl` is printed.
+///
+/// Like [`enabled`], except that in quiet mode errors up to
+/// [`NORMAL`](crate::levels::NORMAL) always print: `-q` hides progress, not
+/// failures.
+///
+/// A directive for the target takes precedence over quiet mode, so
+/// `hyper=0` still silences the errors of `hyper`. Quiet mode only lets the
+/// errors of targets without a directive through.
+///
+/// # Example
+/// ```rust
+/// use verbosio::{filter, set_quiet};
+///
+/// set_quiet!();
+/// assert!(!filter::enabled("my_app", 1));
+/// assert!(filter::error_enabled("my_app", 1));
+/// assert!(!filter::error_enabled("my_app", 2));
+///
+/// filter::add_directive("hyper", 0);
+/// assert!(!filter::error_enabled("hyper::client", 1));
+/// # filter::clear_directives();
+/// set_quiet!(false);
+/// ```
+pub fn error_enabled(target: &str, lvl: u8) -> bool {
+    match directive_verbosity(target) {
+        Some(verbosity) => verbosity >= lvl,
+        None => enabled(target, lvl) || (is_quiet() && lvl <= crate::levels::NORMAL),
+    }
+}
+
+/// Gives all messages of `level` their own minimum verbosity, replacing the
+/// `@lvl` of the individual calls; `None` restores the per-call levels.
+///
+/// # Example
+/// ```rust
+/// use verbosio::filter::{level_enabled, set_level_threshold};
+/// use verbosio::{set_verbosity, Level};
+///
+/// set_verbosity!(0);
+/// set_level_threshold(Level::Warn, Some(0));  // warnings always print
+/// set_level_threshold(Level::Error, Some(0));
+/// assert!(level_enabled("my_app", Some(Level::Warn), 2));
+/// assert!(!level_enabled("my_app", Some(Level::Info), 1));
+/// # set_level_threshold(Level::Warn, None);
+/// # set_level_threshold(Level::Error, None);
+/// ```
+pub fn set_level_threshold(level: Level, verbosity: Option<u8>) {
+    THRESHOLDS[level as usize].store(verbosity.unwrap_or(UNSET), Ordering::Relaxed);
+}
+
+/// The threshold set with [`set_level_threshold`], if any.
+pub fn level_threshold(level: Level) -> Option<u8> {
+    match THRESHOLDS[level as usize].load(Ordering::Relaxed) {
+        UNSET => None,
+        verbosity => Some(verbosity),
+    }
+}
+
+/// Raises the global verbosity by one whenever the process receives
+/// `SIGUSR1`, and lowers it by one on `SIGUSR2`.
+///
+/// Long-running daemons can then be made more or less verbose without a
+/// restart, e.g. with `kill -USR1 <pid>`. Each change is logged as
+/// `[INFO] verbosity changed to N` if the new verbosity is at least 1. The
+/// signals are handled on a background thread; installing the handler
+/// replaces their default behavior of terminating the process.
+///
+/// # Example
+/// ```rust
+/// use verbosio::filter;
+///
+/// filter::adjust_on_signals().expect("failed to install SIGUSR1/SIGUSR2 handler");
+/// ```
+///
+/// # Features
+/// Requires the `"signal"` feature and a Unix platform.
+#[cfg(all(feature = "signal", unix))]
+pub fn adjust_on_signals() -> std::io::Result<()> {
+    use signal_hook::consts::{SIGUSR1, SIGUSR2};
+    use signal_hook::iterator::Signals;
+
+    let mut signals = Signals::new([SIGUSR1, SIGUSR2])?;
+    std::thread::spawn(move || {
+        for signal in signals.forever() {
+            let verbosity = if signal == SIGUSR1 {
+                crate::inc_verbosity!()
+            } else {
+                crate::dec_verbosity!()
+            };
+            crate::vinfo!("verbosity changed to {}", verbosity);
+        }
+    });
+    Ok(())
+}
+
+/// Whether a message of `level` (`None` for untagged messages) and `target`
+/// at verbosity `lvl` is printed.
+///
+/// Applies the [level threshold](set_level_threshold) if one is set, and
+/// [`error_enabled`] for errors.
+pub fn level_enabled(target: &str, level: Option<Level>, lvl: u8) -> bool {
+    match level {
+        Some(level) if let Some(threshold) = level_threshold(level) => verbosity_for(target) >= threshold,
+        Some(Level::Error) => error_enabled(target, lvl),
+        _ => enabled(target, lvl),
+    }
+}
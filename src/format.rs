@@ -0,0 +1,293 @@
+//! Output formats that can be chosen per destination.
+//!
+//! By default the terminal follows the [output mode](crate::output) and the
+//! log file receives human-readable text. Each destination can be given its
+//! own [`Format`] instead, e.g. colored text on the terminal and JSON lines
+//! in the log file:
+//!
+//! ```rust
+//! use verbosio::format::Format;
+//! use verbosio::{set_verbosity, sink, vinfo};
+//!
+//! set_verbosity!(1);
+//! let path = std::env::temp_dir().join("verbosio-format-doc.jsonl");
+//! sink::set_log_file(&path).unwrap();
+//! sink::set_file_format(Format::Json);
+//! vinfo!("connected"); // "[INFO] connected" on the terminal, JSON in the file
+//! # sink::clear_log_file();
+//! # sink::set_file_format(Format::Human);
+//! ```
+use std::fmt::{self, Write as _};
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+use crate::output::{self, OutputMode};
+use crate::record::Record;
+use crate::template::Template;
+use crate::util::{format_rfc3339, strip_ansi};
+
+type CustomFormat = Arc<dyn Fn(&Record) -> String + Send + Sync>;
+
+/// How records are rendered for one destination.
+#[derive(Clone, Default)]
+pub enum Format {
+    /// `[INFO] message key=value`, as printed by default.
+    #[default]
+    Human,
+    /// One JSON object per line, with all record fields.
+    Json,
+    /// One logfmt line per record: `time=... level=info msg="..." key=value`.
+    Logfmt,
+    /// A user-supplied function. Section headers are not written.
+    Custom(CustomFormat),
+    /// A line layout like `"{time} {level:>7} {msg}"`, see
+    /// [`set_format!`](crate::set_format). Section headers are written as
+    /// human text.
+    Template(Template),
+}
+
+impl Format {
+    /// Wraps a function rendering a record as a single entry.
+    pub fn custom<F>(render: F) -> Self
+    where
+        F: Fn(&Record) -> String + Send + Sync + 'static,
+    {
+        Format::Custom(Arc::new(render))
+    }
+
+    /// Renders a record in this format.
+    pub fn render(&self, record: &Record) -> String {
+        match self {
+            Format::Human => output::render_message(OutputMode::Human, record),
+            Format::Json => render_json(record),
+            Format::Logfmt => render_logfmt(record),
+            Format::Custom(render) => render(record),
+            Format::Template(template) => template.render(record),
+        }
+    }
+
+    /// The name or template this format is parsed from, `None` for a custom
+    /// format.
+    ///
+    /// # Example
+    /// ```rust
+    /// use verbosio::format::Format;
+    ///
+    /// let format: Format = "{level} {msg}".parse().unwrap();
+    /// assert_eq!(format.spec(), Some("{level} {msg}"));
+    /// assert_eq!(Format::Logfmt.spec(), Some("logfmt"));
+    /// ```
+    pub fn spec(&self) -> Option<&str> {
+        match self {
+            Format::Human => Some("human"),
+            Format::Json => Some("json"),
+            Format::Logfmt => Some("logfmt"),
+            Format::Custom(_) => None,
+            Format::Template(template) => Some(template.as_str()),
+        }
+    }
+
+    /// Renders a record for the log file. Human text is written without
+    /// colors and always with a timestamp.
+    pub(crate) fn render_for_file(&self, record: &Record) -> String {
+        match self {
+            Format::Human => output::render_file_message(record),
+            Format::Template(template) => strip_ansi(&template.render(record)),
+            _ => self.render(record),
+        }
+    }
+
+    /// Renders a section header, `None` if the format has no representation.
+    pub(crate) fn render_section(&self, title: &str) -> Option<String> {
+        let time = format_rfc3339(std::time::SystemTime::now());
+        match self {
+            Format::Human | Format::Template(_) => Some(output::render_section(OutputMode::Human, title)),
+            Format::Json => Some(format!(
+                "{{\"timestamp\":{},\"section\":{}}}",
+                json_string(&time),
+                json_string(title)
+            )),
+            Format::Logfmt => Some(format!("time={} section={}", time, logfmt_value(title))),
+            Format::Custom(_) => None,
+        }
+    }
+
+    /// Renders the footer of a scoped section.
+    pub(crate) fn render_section_footer(&self, title: &str, elapsed: Duration, failed: bool) -> Option<String> {
+        let time = format_rfc3339(std::time::SystemTime::now());
+        match self {
+            Format::Human | Format::Template(_) => {
+                Some(output::render_section_footer(OutputMode::Human, title, elapsed, failed))
+            }
+            Format::Json => Some(format!(
+                "{{\"timestamp\":{},\"section_end\":{},\"elapsed_ms\":{},\"failed\":{}}}",
+                json_string(&time),
+                json_string(title),
+                elapsed.as_millis(),
+                failed
+            )),
+            Format::Logfmt => Some(format!(
+                "time={} section_end={} elapsed_ms={} failed={}",
+                time,
+                logfmt_value(title),
+                elapsed.as_millis(),
+                failed
+            )),
+            Format::Custom(_) => None,
+        }
+    }
+}
+
+/// Parses `human`, `json`, `logfmt` or a line format
+/// [template](crate::template), e.g. from a config file or an environment
+/// variable.
+///
+/// # Example
+/// ```rust
+/// use verbosio::format::Format;
+///
+/// assert!(matches!("json".parse(), Ok(Format::Json)));
+/// assert!(matches!("{level} {msg}".parse(), Ok(Format::Template(_))));
+/// assert!("{lvl} {msg}".parse::<Format>().is_err());
+/// ```
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "human" => Ok(Format::Human),
+            "json" => Ok(Format::Json),
+            "logfmt" => Ok(Format::Logfmt),
+            template => Template::parse(template.to_string())
+                .map(Format::Template)
+                .map_err(|err| format!("{} {:?}", err, template)),
+        }
+    }
+}
+
+impl fmt::Debug for Format {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Format::Human => f.write_str("Human"),
+            Format::Json => f.write_str("Json"),
+            Format::Logfmt => f.write_str("Logfmt"),
+            Format::Custom(_) => f.write_str("Custom(..)"),
+            Format::Template(template) => f.debug_tuple("Template").field(&template.as_str()).finish(),
+        }
+    }
+}
+
+fn level_name(record: &Record) -> String {
+    record
+        .level_name()
+        .map(str::to_ascii_lowercase)
+        .unwrap_or_else(|| "none".to_string())
+}
+
+fn render_json(record: &Record) -> String {
+    let mut out = String::from("{");
+    let _ = write!(out, "\"timestamp\":{}", json_string(&format_rfc3339(record.time)));
+    let _ = write!(out, ",\"level\":{}", json_string(&level_name(record)));
+    let _ = write!(out, ",\"target\":{}", json_string(&record.target));
+    let _ = write!(out, ",\"message\":{}", json_string(&record.message));
+    let fields: Vec<String> = record
+        .global_fields
+        .iter()
+        .chain(&record.fields)
+        .map(|(key, value)| format!("{}:{}", json_string(key), json_string(value)))
+        .collect();
+    if !fields.is_empty() {
+        let _ = write!(out, ",\"fields\":{{{}}}", fields.join(","));
+    }
+    if !record.notes.is_empty() {
+        let notes: Vec<String> = record
+            .notes
+            .iter()
+            .map(|(kind, text)| format!("{{\"kind\":{},\"text\":{}}}", json_string(kind.as_str()), json_string(text)))
+            .collect();
+        let _ = write!(out, ",\"notes\":[{}]", notes.join(","));
+    }
+    if let Some(success) = record.success {
+        let _ = write!(out, ",\"success\":{}", success);
+    }
+    if let Some(label) = &record.thread_label {
+        let _ = write!(out, ",\"thread\":{}", json_string(label));
+    }
+    if let Some(location) = record.location.filter(|_| output::location_shown()) {
+        let _ = write!(out, ",\"file\":{},\"line\":{}", json_string(location.file), location.line);
+    }
+    if let Some(hostname) = record.hostname {
+        let _ = write!(out, ",\"hostname\":{}", json_string(hostname));
+    }
+    if let Some(username) = record.username {
+        let _ = write!(out, ",\"username\":{}", json_string(username));
+    }
+    if let Some(span_id) = record.span_id {
+        let _ = write!(out, ",\"span_id\":{}", span_id);
+    }
+    if let Some(parent_span_id) = record.parent_span_id {
+        let _ = write!(out, ",\"parent_span_id\":{}", parent_span_id);
+    }
+    let _ = write!(out, ",\"run_id\":{}}}", json_string(record.run_id));
+    out
+}
+
+fn render_logfmt(record: &Record) -> String {
+    let mut out = format!(
+        "time={} level={} target={} msg={}",
+        format_rfc3339(record.time),
+        level_name(record),
+        logfmt_value(&record.target),
+        logfmt_value(&record.message)
+    );
+    for (key, value) in record.global_fields.iter().chain(&record.fields) {
+        let _ = write!(out, " {}={}", key, logfmt_value(value));
+    }
+    for (kind, text) in &record.notes {
+        let _ = write!(out, " {}={}", kind.as_str(), logfmt_value(text));
+    }
+    if let Some(success) = record.success {
+        let _ = write!(out, " success={}", success);
+    }
+    if let Some(label) = &record.thread_label {
+        let _ = write!(out, " thread={}", logfmt_value(label));
+    }
+    if let Some(location) = record.location.filter(|_| output::location_shown()) {
+        let _ = write!(out, " file={} line={}", logfmt_value(location.file), location.line);
+    }
+    if let Some(span_id) = record.span_id {
+        let _ = write!(out, " span_id={}", span_id);
+    }
+    let _ = write!(out, " run_id={}", record.run_id);
+    out
+}
+
+/// Quotes and escapes `value` as a JSON string.
+pub(crate) fn json_string(value: &str) -> String {
+    let mut out = String::with_capacity(value.len() + 2);
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if u32::from(c) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", u32::from(c));
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// Quotes a logfmt value if it contains spaces, quotes or `=`.
+fn logfmt_value(value: &str) -> String {
+    if value.is_empty() || value.contains(|c: char| c.is_whitespace() || c == '"' || c == '=') {
+        format!("{:?}", value)
+    } else {
+        value.to_string()
+    }
+}
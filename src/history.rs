@@ -0,0 +1,61 @@
+//! Ring buffer of the most recently emitted records.
+//!
+//! Disabled by default. Once enabled with [`enable_history`], the last
+//! `capacity` lines (rendered without colors) are kept in memory, e.g. for
+//! crash reports.
+//!
+//! # Example
+//! ```rust
+//! use verbosio::{set_verbosity, vinfo};
+//! use verbosio::history::{enable_history, recent_lines};
+//!
+//! set_verbosity!(1);
+//! # verbosio::util::set_time_mode(verbosio::util::TimeMode::Off);
+//! enable_history(2);
+//! vinfo!("one");
+//! vinfo!("two");
+//! vinfo!("three");
+//! let lines = recent_lines();
+//! assert_eq!(lines.len(), 2);
+//! assert!(lines[0].ends_with("two") && lines[1].ends_with("three"));
+//! ```
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use crate::util::strip_ansi;
+
+static CAPACITY: AtomicUsize = AtomicUsize::new(0);
+
+static LINES: Mutex<VecDeque<String>> = Mutex::new(VecDeque::new());
+
+/// Keeps the last `capacity` lines in memory; `0` disables the history.
+pub fn enable_history(capacity: usize) {
+    CAPACITY.store(capacity, Ordering::Relaxed);
+    let mut lines = LINES.lock().unwrap_or_else(|e| e.into_inner());
+    while lines.len() > capacity {
+        lines.pop_front();
+    }
+}
+
+/// Number of lines kept, `0` if the history is disabled.
+pub fn history_capacity() -> usize {
+    CAPACITY.load(Ordering::Relaxed)
+}
+
+/// Returns the remembered lines, oldest first.
+pub fn recent_lines() -> Vec<String> {
+    LINES.lock().unwrap_or_else(|e| e.into_inner()).iter().cloned().collect()
+}
+
+/// Remembers a rendered line if the history is enabled.
+pub(crate) fn push(line: &str) {
+    let capacity = CAPACITY.load(Ordering::Relaxed);
+    if capacity == 0 {
+        return;
+    }
+    let mut lines = LINES.lock().unwrap_or_else(|e| e.into_inner());
+    while lines.len() >= capacity {
+        lines.pop_front();
+    }
+    lines.push_back(strip_ansi(line));
+}
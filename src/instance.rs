@@ -0,0 +1,146 @@
+//! Logger instances independent of the global configuration.
+//!
+//! The logging macros share one global verbosity, output format and log
+//! file, which belong to the application. A library that wants to be
+//! configured separately creates its own [`Verbosio`] instead: an instance
+//! has its own verbosity, terminal format and log file, and its records skip
+//! the global [filter](crate::filter) directives, [layers](crate::layer) and
+//! [warning counts](crate::tally). Messages are written through
+//! [`Verbosio::logger`].
+//!
+//! Instances are cheap to clone; clones share their configuration.
+//!
+//! # Example
+//! ```rust
+//! use verbosio::format::Format;
+//! use verbosio::{set_verbosity, Verbosio};
+//!
+//! set_verbosity!(0); // the application stays quiet
+//! let lib = Verbosio::new(2);
+//! lib.set_format(Some(Format::Logfmt));
+//!
+//! let log = lib.logger();
+//! log.info("cache warmed");           // time=... level=info ... msg="cache warmed"
+//! log.at(3).info("not printed");
+//! assert!(!verbosio::logger().enabled(1));
+//! ```
+use std::fmt;
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::path::Path;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use crate::Level;
+use crate::format::Format;
+use crate::logger::Logger;
+use crate::output::{self, OutputMode};
+use crate::record::{Location, Record};
+
+/// A logger instance with its own configuration, see the
+/// [module docs](self).
+#[derive(Clone)]
+pub struct Verbosio {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    verbosity: AtomicU8,
+    /// Format of terminal output, `None` if nothing is printed.
+    terminal: RwLock<Option<Format>>,
+    file: Mutex<Option<(File, Format)>>,
+}
+
+impl Verbosio {
+    /// Creates an instance with verbosity `verbosity`, printing human text
+    /// to the terminal.
+    pub fn new(verbosity: u8) -> Self {
+        Verbosio {
+            inner: Arc::new(Inner {
+                verbosity: AtomicU8::new(verbosity),
+                terminal: RwLock::new(Some(Format::Human)),
+                file: Mutex::new(None),
+            }),
+        }
+    }
+
+    pub fn set_verbosity(&self, verbosity: u8) {
+        self.inner.verbosity.store(verbosity, Ordering::Relaxed);
+    }
+
+    pub fn verbosity(&self) -> u8 {
+        self.inner.verbosity.load(Ordering::Relaxed)
+    }
+
+    /// Whether a message at verbosity `lvl` is printed by this instance.
+    pub fn enabled(&self, lvl: u8) -> bool {
+        self.verbosity() >= lvl
+    }
+
+    /// Sets the format of terminal output; `None` prints nothing to the
+    /// terminal, e.g. for an instance that only writes a log file.
+    pub fn set_format(&self, format: Option<Format>) {
+        *self.inner.terminal.write().unwrap_or_else(|e| e.into_inner()) = format;
+    }
+
+    /// Appends all subsequent records of this instance to the file at
+    /// `path`, rendered in `format`. A previous log file is closed.
+    pub fn set_log_file<P: AsRef<Path>>(&self, path: P, format: Format) -> io::Result<()> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        *self.inner.file.lock().unwrap_or_else(|e| e.into_inner()) = Some((file, format));
+        Ok(())
+    }
+
+    /// Closes the log file of this instance, if any.
+    pub fn clear_log_file(&self) {
+        *self.inner.file.lock().unwrap_or_else(|e| e.into_inner()) = None;
+    }
+
+    /// A [`Logger`] writing through this instance.
+    pub fn logger(&self) -> Logger {
+        Logger::for_instance(self.clone())
+    }
+
+    /// Renders a record and writes it to this instance's destinations.
+    pub(crate) fn emit(
+        &self,
+        level: Option<Level>,
+        target: &str,
+        named_target: bool,
+        args: fmt::Arguments<'_>,
+        location: Location,
+    ) {
+        let mut record = Record::new(level, target, args.to_string());
+        record.named_target = named_target;
+        record.location = Some(location);
+        if let Some(format) = self.inner.terminal.read().unwrap_or_else(|e| e.into_inner()).as_ref() {
+            crate::sink::write_terminal(output::to_stderr(OutputMode::Human, level), &format.render(&record));
+        }
+        if let Some((file, format)) = self.inner.file.lock().unwrap_or_else(|e| e.into_inner()).as_mut() {
+            let _ = file.write_all(format!("{}\n", format.render_for_file(&record)).as_bytes());
+        }
+    }
+}
+
+impl Default for Verbosio {
+    fn default() -> Self {
+        Verbosio::new(0)
+    }
+}
+
+/// Instances are equal if they are clones of each other.
+impl PartialEq for Verbosio {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.inner, &other.inner)
+    }
+}
+
+impl Eq for Verbosio {}
+
+impl fmt::Debug for Verbosio {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Verbosio")
+            .field("verbosity", &self.verbosity())
+            .field("format", &*self.inner.terminal.read().unwrap_or_else(|e| e.into_inner()))
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,88 @@
+//! Middleware applied to every record before it reaches the sinks.
+//!
+//! A layer is a function registered with [`add_layer`] that receives each
+//! record mutably. It can rewrite the record (truncate, redact, change its
+//! level, add fields), and decide with the returned [`Action`] whether the
+//! record continues, is dropped, or only goes to some of the sinks. Layers
+//! run in registration order; a dropped record is not passed to later
+//! layers and is not counted by [`vsummary!`](crate::vsummary).
+//!
+//! # Example
+//! ```rust
+//! use verbosio::layer::{self, Action};
+//! use verbosio::{set_verbosity, vinfo};
+//!
+//! set_verbosity!(1);
+//! layer::add_layer(|record| {
+//!     if record.message.contains("password") {
+//!         record.message = "<redacted>".to_string();
+//!     }
+//!     Action::Keep
+//! });
+//! layer::add_layer(|record| {
+//!     if record.target.starts_with("hyper") { Action::Drop } else { Action::Keep }
+//! });
+//!
+//! vinfo!("password is hunter2"); // [INFO] <redacted>
+//! # layer::clear_layers();
+//! ```
+use std::sync::{Arc, RwLock};
+use crate::record::Record;
+
+type Layer = Arc<dyn Fn(&mut Record) -> Action + Send + Sync>;
+
+static LAYERS: RwLock<Vec<Layer>> = RwLock::new(Vec::new());
+
+/// What happens to a record after a layer has seen it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    /// Pass the record on unchanged in its destinations.
+    Keep,
+    /// Discard the record.
+    Drop,
+    /// Write the record to the terminal, but not to the log file.
+    TerminalOnly,
+    /// Write the record to the log file, but not to the terminal.
+    FileOnly,
+}
+
+/// Where a record is written, after all layers have run.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Route {
+    pub terminal: bool,
+    pub file: bool,
+}
+
+/// Registers a layer that runs after all previously registered layers.
+///
+/// Layers are called on the logging thread, after the verbosity check.
+pub fn add_layer<F>(layer: F)
+where
+    F: Fn(&mut Record) -> Action + Send + Sync + 'static,
+{
+    LAYERS.write().unwrap_or_else(|e| e.into_inner()).push(Arc::new(layer));
+}
+
+/// Removes all registered layers.
+pub fn clear_layers() {
+    LAYERS.write().unwrap_or_else(|e| e.into_inner()).clear();
+}
+
+/// Runs all layers on `record`, returning `None` if it was dropped.
+pub(crate) fn apply(record: &mut Record) -> Option<Route> {
+    // clone the list so layers may log themselves without deadlocking
+    let layers = LAYERS.read().unwrap_or_else(|e| e.into_inner()).clone();
+    let mut route = Route { terminal: true, file: true };
+    for layer in layers {
+        match layer(record) {
+            Action::Keep => {}
+            Action::Drop => return None,
+            Action::TerminalOnly => route.file = false,
+            Action::FileOnly => route.terminal = false,
+        }
+        if !route.terminal && !route.file {
+            return None;
+        }
+    }
+    Some(route)
+}
@@ -0,0 +1,193 @@
+use std::fmt;
+use std::sync::RwLock;
+use crate::color::Color;
+use crate::output::Stream;
+
+/// Severity of a log message.
+///
+/// Each level corresponds to one of the tagged logging macros
+/// (`verror!`, `vwarn!`, `vinfo!`, `vebug!`, `vtrace!`). `as_str()` returns the tag
+/// as it is printed, e.g. `"WARN"`.
+///
+/// # Example
+/// ```rust
+/// use verbosio::Level;
+///
+/// assert_eq!(Level::Warn.as_str(), "WARN");
+/// assert_eq!(Level::Debug.to_string(), "DEBUG");
+/// ```
+///
+/// # Features
+/// With the `"serde"` feature enabled, levels (de)serialize as lowercase
+/// names (`"warn"`), so they can be embedded in application config files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum Level {
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl Level {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Level::Error => "ERROR",
+            Level::Warn => "WARN",
+            Level::Info => "INFO",
+            Level::Debug => "DEBUG",
+            Level::Trace => "TRACE",
+        }
+    }
+}
+
+impl fmt::Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A level registered at runtime with [`register_level`], e.g. `NOTICE` or
+/// `AUDIT`, logged with [`vlog!(level: "NOTICE", ...)`](crate::vlog).
+///
+/// Each custom level is backed by a built-in [`Level`], its severity, which
+/// decides how its records are counted, treated in quiet mode and rendered
+/// where only built-in levels are known, e.g. in binary logs or as cargo
+/// warnings. Its tag, color, stream and default verbosity are its own.
+///
+/// # Example
+/// ```rust
+/// use verbosio::level::{register_level, LevelSpec};
+/// use verbosio::{set_verbosity, vlog, Level};
+///
+/// let mut audit = LevelSpec::new("AUDIT");
+/// audit.severity = Level::Warn;
+/// audit.verbosity = 0;
+/// register_level(audit);
+///
+/// set_verbosity!(0);
+/// vlog!(level: "AUDIT", "user {} logged in", "alice"); // [AUDIT] user alice logged in
+/// vlog!(level: "AUDIT", @lvl 2, "session details");    // not printed
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CustomLevel(usize);
+
+/// Describes a custom level for [`register_level`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LevelSpec {
+    /// The tag, printed as `[NOTICE]`. Names are case-insensitive and
+    /// printed in upper case.
+    pub name: String,
+    /// The built-in level it behaves like otherwise, [`Level::Info`] by default.
+    pub severity: Level,
+    /// Color of the tag, the theme color of `severity` if `None`.
+    pub color: Option<Color>,
+    /// Stream in human output, that of `severity` if `None`.
+    pub stream: Option<Stream>,
+    /// Minimum verbosity if a message gives no `@lvl`, 1 by default.
+    pub verbosity: u8,
+}
+
+impl LevelSpec {
+    pub fn new(name: impl Into<String>) -> Self {
+        LevelSpec {
+            name: name.into(),
+            severity: Level::Info,
+            color: None,
+            stream: None,
+            verbosity: 1,
+        }
+    }
+}
+
+struct Registered {
+    /// Leaked once per name, so records can carry it like a built-in tag.
+    name: &'static str,
+    spec: LevelSpec,
+}
+
+static CUSTOM_LEVELS: RwLock<Vec<Registered>> = RwLock::new(Vec::new());
+
+/// Registers a custom level, replacing an earlier one of the same name.
+pub fn register_level(mut spec: LevelSpec) -> CustomLevel {
+    spec.name = spec.name.to_ascii_uppercase();
+    let mut levels = CUSTOM_LEVELS.write().unwrap_or_else(|e| e.into_inner());
+    if let Some(index) = levels.iter().position(|level| level.name == spec.name) {
+        levels[index].spec = spec;
+        return CustomLevel(index);
+    }
+    let name = Box::leak(spec.name.clone().into_boxed_str());
+    levels.push(Registered { name, spec });
+    CustomLevel(levels.len() - 1)
+}
+
+/// Looks up a custom level by name, ignoring case.
+pub fn custom_level(name: &str) -> Option<CustomLevel> {
+    CUSTOM_LEVELS
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .iter()
+        .position(|level| level.name.eq_ignore_ascii_case(name))
+        .map(CustomLevel)
+}
+
+/// Like [`custom_level`] for `vlog!(level: ...)`, reporting names that are
+/// not registered with a `[DEBUG]` message.
+#[doc(hidden)]
+pub fn custom_level_or_info(name: &str) -> Option<CustomLevel> {
+    let level = custom_level(name);
+    if level.is_none() {
+        crate::vebug!("unknown level {:?} logged as INFO, see verbosio::level::register_level", name);
+    }
+    level
+}
+
+impl CustomLevel {
+    fn with<T>(&self, f: impl FnOnce(&Registered) -> T) -> T {
+        f(&CUSTOM_LEVELS.read().unwrap_or_else(|e| e.into_inner())[self.0])
+    }
+
+    /// The tag in upper case, e.g. `"NOTICE"`.
+    pub fn as_str(&self) -> &'static str {
+        self.with(|level| level.name)
+    }
+
+    /// The spec the level was registered with.
+    pub fn spec(&self) -> LevelSpec {
+        self.with(|level| level.spec.clone())
+    }
+
+    pub fn severity(&self) -> Level {
+        self.with(|level| level.spec.severity)
+    }
+
+    /// The color of the tag, resolved against the current theme.
+    pub fn color(&self) -> Color {
+        self.with(|level| level.spec.color).unwrap_or_else(|| crate::color::theme().level(self.severity()))
+    }
+
+    /// The stream in human output, if the level has its own.
+    pub fn stream(&self) -> Option<Stream> {
+        self.with(|level| level.spec.stream)
+    }
+}
+
+impl fmt::Display for CustomLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Whether a message of the custom level `level` and `target` at verbosity
+/// `lvl` is printed; without `lvl`, at the level's default verbosity.
+/// Unregistered levels behave like `INFO`.
+#[doc(hidden)]
+pub fn custom_enabled(level: Option<CustomLevel>, target: &str, lvl: Option<u8>) -> bool {
+    let lvl = lvl.unwrap_or_else(|| level.map_or(1, |level| level.with(|level| level.spec.verbosity)));
+    match level.map_or(Level::Info, |level| level.severity()) {
+        Level::Error => crate::filter::error_enabled(target, lvl),
+        _ => crate::filter::enabled(target, lvl),
+    }
+}
@@ -0,0 +1,242 @@
+//! Named verbosity tiers.
+//!
+//! Plain numbers work everywhere a verbosity is expected, but named tiers
+//! keep call sites readable and consistent across a codebase. They are
+//! ordinary `u8` constants, usable with `set_verbosity!`, `@lvl` and the
+//! positional level syntax:
+//!
+//! | Constant  | Value | `-v` flags | Name             |
+//! |-----------|-------|------------|------------------|
+//! | [`QUIET`]   | `0` |            | `quiet`          |
+//! | [`NORMAL`]  | `1` | `-v`       | `normal`, `info` |
+//! | [`VERBOSE`] | `2` | `-vv`      | `verbose`        |
+//! | [`DEBUG`]   | `3` | `-vvv`     | `debug`          |
+//! | [`TRACE`]   | `4` | `-vvvv`    | `trace`          |
+//!
+//! # Example
+//! ```rust
+//! use verbosio::levels::{DEBUG, NORMAL};
+//! use verbosio::{set_verbosity, vinfo};
+//!
+//! set_verbosity!(NORMAL);
+//! vinfo!(@lvl NORMAL, "compiling"); // printed
+//! vinfo!(@lvl DEBUG, "cache key: {}", "a1b2"); // not printed
+//! vinfo!(DEBUG, "also not printed");
+//! ```
+//!
+//! The same tiers are available as the [`Verbosity`] enum, which parses from
+//! and prints as the names, and can be passed to `set_verbosity!` like a
+//! number or a name: `set_verbosity!("debug")`. The same names are accepted
+//! in `VERBOSE`, see [`env`](crate::env).
+use std::fmt;
+use std::str::FromStr;
+
+/// Nothing but what a tool prints unconditionally.
+pub const QUIET: u8 = 0;
+
+/// Regular progress output.
+pub const NORMAL: u8 = 1;
+
+/// Additional detail for users who asked for more.
+pub const VERBOSE: u8 = 2;
+
+/// Internal state useful for debugging the tool.
+pub const DEBUG: u8 = 3;
+
+/// Everything, including per-item tracing.
+pub const TRACE: u8 = 4;
+
+/// The named verbosity tiers as an enum.
+///
+/// # Example
+/// ```rust
+/// use verbosio::levels::Verbosity;
+/// use verbosio::{get_verbosity, set_verbosity};
+///
+/// let verbosity: Verbosity = "Debug".parse().unwrap();
+/// assert_eq!(verbosity, Verbosity::Debug);
+/// assert_eq!(u8::from(verbosity), 3);
+/// assert_eq!(Verbosity::Trace.to_string(), "trace");
+///
+/// set_verbosity!(Verbosity::Verbose);
+/// assert_eq!(get_verbosity!(), 2);
+/// set_verbosity!("trace");
+/// assert_eq!(get_verbosity!(), 4);
+/// ```
+///
+/// # Features
+/// With the `"serde"` feature, it (de)serializes as the lowercase name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+pub enum Verbosity {
+    Quiet,
+    Normal,
+    Verbose,
+    Debug,
+    Trace,
+}
+
+impl From<Verbosity> for u8 {
+    fn from(verbosity: Verbosity) -> u8 {
+        match verbosity {
+            Verbosity::Quiet => QUIET,
+            Verbosity::Normal => NORMAL,
+            Verbosity::Verbose => VERBOSE,
+            Verbosity::Debug => DEBUG,
+            Verbosity::Trace => TRACE,
+        }
+    }
+}
+
+impl TryFrom<u8> for Verbosity {
+    type Error = String;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            QUIET => Ok(Verbosity::Quiet),
+            NORMAL => Ok(Verbosity::Normal),
+            VERBOSE => Ok(Verbosity::Verbose),
+            DEBUG => Ok(Verbosity::Debug),
+            TRACE => Ok(Verbosity::Trace),
+            _ => Err(format!("verbosity {} has no name", value)),
+        }
+    }
+}
+
+impl fmt::Display for Verbosity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Verbosity::Quiet => "quiet",
+            Verbosity::Normal => "normal",
+            Verbosity::Verbose => "verbose",
+            Verbosity::Debug => "debug",
+            Verbosity::Trace => "trace",
+        })
+    }
+}
+
+/// Parses the names of the tiers, ignoring case, and the numbers `0` to `4`.
+///
+/// This is the table behind [`parse_verbosity`](crate::env::parse_verbosity),
+/// which also accepts larger numbers.
+impl FromStr for Verbosity {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let value = s.trim();
+        if let Ok(number) = value.parse::<u8>() {
+            return Verbosity::try_from(number);
+        }
+        match value.to_ascii_lowercase().as_str() {
+            "quiet" | "silent" | "off" | "false" | "no" => Ok(Verbosity::Quiet),
+            "normal" | "info" | "on" | "true" | "yes" => Ok(Verbosity::Normal),
+            "verbose" => Ok(Verbosity::Verbose),
+            "debug" => Ok(Verbosity::Debug),
+            "trace" => Ok(Verbosity::Trace),
+            _ => Err(format!("invalid verbosity '{}', expected quiet, normal, verbose, debug or trace", s)),
+        }
+    }
+}
+
+/// Values accepted by [`set_verbosity!`](crate::set_verbosity): a number or
+/// a [`Verbosity`]. Names are accepted as string literals, which are checked
+/// at compile time; parse names from users with [`Verbosity::from_str`].
+pub trait IntoVerbosity {
+    fn into_verbosity(self) -> u8;
+}
+
+impl IntoVerbosity for u8 {
+    fn into_verbosity(self) -> u8 {
+        self
+    }
+}
+
+impl IntoVerbosity for Verbosity {
+    fn into_verbosity(self) -> u8 {
+        self.into()
+    }
+}
+
+/// The verbosity of a literal passed to `set_verbosity!` and the other
+/// macros taking a verbosity, as written by `stringify!`: a number, or a
+/// name or number in quotes. Panics (at compile time, in a const context)
+/// if it is neither.
+#[doc(hidden)]
+pub const fn literal_verbosity(literal: &str) -> u8 {
+    let bytes = literal.as_bytes();
+    let (mut start, mut end) = (0, bytes.len());
+    let quoted = end >= 2 && bytes[0] == b'"' && bytes[end - 1] == b'"';
+    if quoted {
+        start += 1;
+        end -= 1;
+        while start < end && bytes[start].is_ascii_whitespace() {
+            start += 1;
+        }
+        while end > start && bytes[end - 1].is_ascii_whitespace() {
+            end -= 1;
+        }
+    } else if end > 2 && bytes[end - 2] == b'u' && bytes[end - 1] == b'8' {
+        end -= 2;
+    }
+    if let Some(verbosity) = number(bytes, start, end, !quoted) {
+        return verbosity;
+    }
+    if !quoted {
+        panic!("invalid verbosity, expected a number from 0 to 255 or a name like \"debug\"");
+    }
+    const NAMES: [(&str, u8); 13] = [
+        ("quiet", QUIET),
+        ("silent", QUIET),
+        ("off", QUIET),
+        ("false", QUIET),
+        ("no", QUIET),
+        ("normal", NORMAL),
+        ("info", NORMAL),
+        ("on", NORMAL),
+        ("true", NORMAL),
+        ("yes", NORMAL),
+        ("verbose", VERBOSE),
+        ("debug", DEBUG),
+        ("trace", TRACE),
+    ];
+    let mut n = 0;
+    while n < NAMES.len() {
+        let name = NAMES[n].0.as_bytes();
+        if name.len() == end - start {
+            let mut j = 0;
+            while j < name.len() && bytes[start + j].eq_ignore_ascii_case(&name[j]) {
+                j += 1;
+            }
+            if j == name.len() {
+                return NAMES[n].1;
+            }
+        }
+        n += 1;
+    }
+    panic!("invalid verbosity, expected quiet, normal, verbose, debug or trace")
+}
+
+/// Parses `bytes[start..end]` as a number up to 255, allowing `_`
+/// separators if `underscores` is set.
+const fn number(bytes: &[u8], start: usize, end: usize, underscores: bool) -> Option<u8> {
+    let mut value: u32 = 0;
+    let mut digits = 0;
+    let mut i = start;
+    while i < end {
+        match bytes[i] {
+            b @ b'0'..=b'9' => {
+                value = value * 10 + (b - b'0') as u32;
+                if value > u8::MAX as u32 {
+                    return None;
+                }
+                digits += 1;
+            }
+            b'_' if underscores && digits > 0 => {}
+            _ => return None,
+        }
+        i += 1;
+    }
+    if digits == 0 { None } else { Some(value as u8) }
+}
@@ -7,27 +7,100 @@
 //! ## Features
 //!
 //! - Global verbosity level
-//!     - Set/get via `set_verbosity!`, `get_verbosity!`, or `verbose_env!`
+//!     - Set/get via `set_verbosity!`, `get_verbosity!`, `inc_verbosity!`/`dec_verbosity!`, `verbose_env!`, or `verbose_args!` (`-v`/`-q` flags)
+//!     - Temporarily for a block via `with_verbosity!`, or for one thread via `with_thread_verbosity!`, see [`scope`]
+//!     - Named tiers (`QUIET`, `NORMAL`, `VERBOSE`, `DEBUG`, `TRACE`) and the `Verbosity` enum in [`levels`], e.g. `set_verbosity!("debug")`
+//!     - Quiet mode via `set_quiet!` (`-q`), where errors still print
+//!     - Per-level thresholds and streams via `filter::set_level_threshold` and `output::set_level_stream`
 //! - Conditional message printing
-//!     - `verbose!`, `vinfo!`, `vwarn!`, `verror!`, `vebug!`
-//! - Distinct log levels: `INFO`, `WARN`, `ERROR`, `DEBUG`
-//! - Optional section headers via `vsection!`
-//! - Interactive terminal spinners via `status_line!` macros
+//!     - `verbose!`, `vinfo!`, `vwarn!`, `verror!`, `vebug!`, `vtrace!`, `vlog!` for runtime levels, and `verbose_err!` for stderr
+//! - Distinct log levels: `INFO`, `WARN`, `ERROR`, `DEBUG`, `TRACE`, plus custom levels (`NOTICE`, `AUDIT`, ...) with their own tag, color, stream and verbosity, see [`level`]
+//! - ✔/✖ lines for steps that succeeded or failed via `vsuccess!`/`vfail!`
+//! - `vfatal!(code, ...)` prints an error whatever the verbosity, cleans up and exits
+//! - Named targets (`vinfo!(target: "net", ...)`), filtered and colored per target
+//! - A function API mirroring the macros (`logger().info(...)`), see [`logger`](mod@logger)
+//! - Independent [`Verbosio`] instances with their own verbosity, format and log file, e.g. for libraries
+//! - Compiler-style `help:`/`note:` follow-up lines on warnings and errors
+//! - Optional call-site locations (`src/net.rs:42`) from a chosen verbosity via `output::show_location_at`
+//! - An allocation-free `verror_signal_safe!` for signal handlers
+//! - Source snippets with caret underlines via `vdiagnostic!`, optionally also as editor-parseable `file:line:col:` lines, see [`snippet`]
+//! - Optional section headers via `vsection!` (or `vsection_scoped!` with a timed footer), with span IDs via `current_span_id!()`
+//! - Timed phases via `vtime!`, with optional verbosity escalation for slow ones
+//! - A [`timeline`] of sections, timed phases and instrumented functions, exported in Chrome tracing format
+//! - Retries with backoff and per-attempt logging via `vretry!`
+//! - A [`watchdog`] that warns when a section goes silent for too long
+//! - An optional terminal [`bell`] on errors and after long sections
+//! - Per-run correlation ID via `run_id!()`
+//! - Per-thread display labels via `set_thread_label!`, or thread names/IDs via `context::show_thread`
+//! - Thread-local context fields via `vcontext_set!`/`with_fields!` and global fields via `set_global_fields!`
+//! - Output modes for other consumers, e.g. cargo build scripts or JSON lines (`set_output_mode!`)
+//! - Interactive terminal spinners via `status_line!` macros, optionally with `v`/`q` verbosity keys, in built-in (`braille`, `line`, `dots`, `arrows`) or custom styles
+//! - A status region pinned to the bottom of the terminal while logs scroll above, see `pinned` (`status` feature)
+//! - `vwait!`/`vwait_async!` show a status line around a closure or future and mark the result with ✔/✖
+//! - Item counts for async streams via `vprogress`, see `stream` (`async` feature)
 //! - Terminal-safe output (no flickering) using `crossterm`
-//! - All macros are verbosity-aware (`@lvl N`)
+//! - Detected terminal [`capabilities`](fn@capabilities) with graceful degradation and environment overrides
+//! - Configurable colors via [`color::set_theme`], including 256-color and RGB values, downgraded to what the terminal supports
+//! - Colors follow `NO_COLOR`, `CLICOLOR`, `CLICOLOR_FORCE` and TTY detection, overridable with `set_color_mode!`
+//! - Wall-clock, elapsed-since-start (`[+12.032s]`) or no timestamps via `set_time_mode!`
+//!     - strftime pattern, UTC and fractional seconds of wall-clock time via `util::set_time_format` (`time` feature)
+//! - All macros are verbosity-aware (`@lvl N`), with per-module verbosity via `VERBOSE=3,my_app::net=1`, see [`filter`]
+//! - Warning/error counts with an end-of-run summary via `vsummary!` and exit codes via `exit_with_status!`
+//! - Middleware layers that rewrite, reroute or drop records, see [`layer`]
+//! - Session recording with timing and replay, see [`replay`]
+//! - Per-destination [`format`]s: human text, JSON lines, logfmt or a custom function
+//! - A [`viewer`] that pretty-prints and filters captured JSON logs
+//! - Line format templates validated at compile time via `format_template!`, and a configurable line layout via `set_format!`, see [`template`]
+//! - Compact binary log files with a decoder, see [`binlog`] and [`decode`]
+//! - Contiguous per-thread output for parallel jobs, see [`buffer`]
+//! - Task-prefixed live output with a "currently running" footer, see [`tasks`]
+//! - Resumable runs via persisted progress checkpoints (`vcheckpoint!`), see [`checkpoint`]
+//! - Shutdown guard (`init_guarded()`) and `verbosio::exit` that stop spinners and flush output
+//! - Copying or uploading the log file on exit, see [`archive`]
+//! - Child processes that inherit verbosity and colors via [`command::VerbosioCommandExt`]
+//! - Custom destinations (GUI panes, test collectors) via the [`sink::Sink`] trait and `add_sink!`
+//! - Log files shared by several processes, with per-record advisory locking (`sink::set_file_locking`)
+//! - Panic and error reports in the style of `color-eyre` via [`report`], with optional crash report files
 //!
 //! ## Optional Features
 //!
-//! - `color`: Enables ANSI-colored output (via [`colored`](https://crates.io/crates/colored))
+//! - `color`: Enables ANSI-colored output, see [`color`](crate::color)
 //! - `status`: Enables spinner-based status lines using [`crossterm`](https://crates.io/crates/crossterm)
+//! - `serde`: Derives `Serialize`/`Deserialize` for configuration types such as [`Level`]
+//! - `clap`: Provides reusable `-v`/`-q`/`--color`/`--log-file` flags in [`cli`](crate::cli)
+//! - `ffi`: Exposes a C API (`verbosio_log`, `verbosio_set_verbosity`) in [`ffi`](crate::ffi)
+//! - `otel`: Records `vtime!` blocks as OpenTelemetry spans, see [`otel`](crate::otel)
+//! - `defmt`: Re-emits decoded defmt device logs, see [`defmt_bridge`](crate::defmt_bridge)
+//! - `log`: Renders records of the `log` facade via `init_log_bridge()`, see [`log_bridge`](crate::log_bridge)
+//! - `tracing`: A `tracing-subscriber` layer printing `tracing` events, see [`tracing_bridge`](crate::tracing_bridge)
+//! - `miette`: Renders [`miette`](https://crates.io/crates/miette) diagnostics via `vmiette!`
+//! - `instrument`: The `#[instrument]` attribute for logging function calls, see [`instrument`](macro@crate::instrument)
+//! - `notify`: Desktop notifications via `vnotify!`, see [`notify`](crate::notify)
+//! - `encrypt`: AES-256-GCM encrypted log files, see [`encrypt`](crate::encrypt)
+//! - `archive-upload`: Uploads the log file on exit to an HTTP(S) or S3-compatible endpoint, see [`archive`]
+//! - `async`: Progress reporting for async streams via `vprogress`, see [`stream`](crate::stream)
+//! - `signal`: Reopens the log file on `SIGHUP` for `logrotate`, see [`sink::reopen_on_sighup`], and changes the verbosity on `SIGUSR1`/`SIGUSR2`, see [`filter::adjust_on_signals`]
+//! - `config`: Loads verbosity, format, theme, filters and the log file from a TOML file via `init_from_file!`, see [`config`](crate::config)
 //!
 //! ## Dependencies
 //!
 //! | Name        | Purpose                          | Required by default  |
 //! |-------------|----------------------------------|----------------------|
 //! | `once_cell` | Global static verbosity state    |  Yes                 |
-//! | `colored`   | Colored output for log levels    |  No (`color`)        |
 //! | `crossterm` | Interactive terminal spinners    |  No (`status`)       |
+//! | `miette`    | Diagnostic rendering             |  No (`miette`)       |
+//! | `serde`     | (De)serializable config types    |  No (`serde`)        |
+//! | `opentelemetry` | Spans for timed phases       |  No (`otel`)         |
+//! | `defmt-decoder` | Decoding of defmt frames     |  No (`defmt`)        |
+//! | `clap`      | Reusable command line flags      |  No (`clap`)         |
+//! | `verbosio-macros` | `#[instrument]` attribute    |  No (`instrument`)   |
+//! | `signal-hook` | `SIGHUP`/`SIGUSR1`/`SIGUSR2` handlers |  No (`signal`)  |
+//! | `aes-gcm`   | Encrypted log files              |  No (`encrypt`)      |
+//! | `ureq`      | Uploading archived log files     |  No (`archive-upload`) |
+//! | `futures-core` | `Stream` progress adapter     |  No (`async`)        |
+//! | `log`       | Bridge from the `log` facade     |  No (`log`)          |
+//! | `tracing-subscriber` | Layer for `tracing` events |  No (`tracing`)     |
+//! | `toml`      | Config files                     |  No (`config`)       |
 //!
 //! ## Example
 //!
@@ -44,7 +117,8 @@
 //!
 //! ## Environment Support
 //!
-//! You can also set verbosity via the `VERBOSE` environment variable:
+//! You can also set verbosity via the `VERBOSE` environment variable, as a number
+//! or a name like `debug`, `trace` or `quiet` (see [`env`]):
 //!
 //! ```rust
 //! use verbosio::verbose_env;
@@ -52,9 +126,13 @@
 //! verbose_env!(); // Sets verbosity to 2
 //! ```
 //!
+//! `verbose_env!(prefix: "MYTOOL")` reads `MYTOOL_VERBOSE`, `MYTOOL_QUIET`, `MYTOOL_COLOR`,
+//! `MYTOOL_LOG_FORMAT` and `MYTOOL_LOG_FILE` instead, so tools don't collide on `VERBOSE`.
+//!
 //! ## Status Line Example (feature = `"status"`)
 //!
 //! ```rust
+//! # #[cfg(feature = "status")] {
 //! use verbosio::{status_line, status_line_done};
 //!
 //! if let Some(spinner) = status_line!("Building project…") {
@@ -63,6 +141,7 @@
 //!     spinner.stop();
 //!     status_line_done!("Build complete.");
 //! }
+//! # }
 //! ```
 //!
 //! ## Section Headers
@@ -83,6 +162,44 @@
 //! vebug!(@lvl 3, "Detailed: {:?}", "info"); // only if verbosity ≥ 3
 //! ```
 //!
+//! ## Named Targets
+//!
+//! `verbose!`, `vinfo!`, `vwarn!`, `verror!`, `vebug!` and `vtrace!` accept a named
+//! target in place of the calling module: `vinfo!(target: "net", "connected")`
+//! prints `[INFO] net: connected`. The target is checked against the
+//! [`filter`] directives (`VERBOSE=1,net=3`), carried in the record for
+//! structured formats and layers, and can be given its own color with
+//! [`color::set_target_color`].
+//!
+//! ```rust
+//! use verbosio::*;
+//!
+//! set_verbosity!(1);
+//! filter::add_directive("db", 0);
+//! vinfo!(target: "net", "connected");          // [INFO] net: connected
+//! vwarn!(@lvl 1, target: "db", "slow query");  // silenced by the directive
+//! filter::clear_directives();
+//! ```
+//!
+//! ## Compatibility
+//!
+//! Earlier versions took the level as a plain first argument. `verbose!`,
+//! `vinfo!`, `vwarn!`, `verror!`, `vebug!` and `vtrace!` still accept that form and
+//! treat it exactly like `@lvl`. A positional level must be an integer
+//! literal from `0` to `9` or a plain identifier (e.g. a constant); other
+//! expressions need `@lvl`. New code should prefer `@lvl`.
+//!
+//! ```rust
+//! use verbosio::*;
+//!
+//! const CHATTY: u8 = 3;
+//! set_verbosity!(2);
+//!
+//! vinfo!(2, "Loaded {} items", 3);   // same as vinfo!(@lvl 2, ...)
+//! verbose!(CHATTY, "Not printed");   // same as verbose!(@lvl CHATTY, ...)
+//! vwarn!("{} left", 1);              // a format string is never a level
+//! ```
+//!
 //! ## Philosophy
 //!
 //! `verbosio` is built to be ultra-lightweight and practical for CLI tooling,
@@ -99,6 +216,69 @@
 
 pub mod macros;
 pub mod util;
+pub mod level;
+pub mod levels;
+pub mod filter;
+pub mod scope;
+pub mod logger;
+pub mod instance;
+pub mod color;
+pub mod capabilities;
+pub mod record;
+pub mod format;
+pub mod viewer;
+pub mod template;
+pub mod context;
+pub mod env;
+pub mod run;
+pub mod span;
+pub mod tally;
+pub mod history;
+pub mod replay;
+pub mod binlog;
+pub mod decode;
+pub mod sink;
+pub mod buffer;
+pub mod tasks;
+pub mod layer;
+pub mod output;
+pub mod shutdown;
+pub mod archive;
+pub mod command;
+pub mod timer;
+pub mod timeline;
+pub mod watchdog;
+pub mod wait;
+pub mod bell;
+pub mod snippet;
+pub mod retry;
+pub mod signal_safe;
+pub mod checkpoint;
+#[cfg(feature = "otel")]
+pub mod otel;
+#[cfg(feature = "defmt")]
+pub mod defmt_bridge;
+#[cfg(feature = "log")]
+pub mod log_bridge;
+#[cfg(feature = "tracing")]
+pub mod tracing_bridge;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(feature = "notify")]
+pub mod notify;
+#[cfg(feature = "status")]
+pub mod pinned;
+#[cfg(feature = "encrypt")]
+pub mod encrypt;
+#[cfg(feature = "async")]
+pub mod stream;
+#[cfg(feature = "clap")]
+pub mod cli;
+#[cfg(feature = "config")]
+pub mod config;
+pub mod report;
+#[cfg(feature = "miette")]
+pub mod diagnostics;
 use once_cell::sync::Lazy;
 use std::sync::atomic::{AtomicU8};
 
@@ -107,4 +287,49 @@ pub static VERBOSE: Lazy<AtomicU8> = Lazy::new(|| AtomicU8::new(0));
 /// Re-exports all macros for easy access.
 pub use macros::verbosity::*;
 pub use macros::terminal::*;
-pub use util::*;
\ No newline at end of file
+pub use util::*;
+pub use level::Level;
+pub use record::Record;
+pub use logger::{logger, Logger};
+pub use instance::Verbosio;
+pub use shutdown::{exit, init_guarded, ShutdownGuard};
+pub use tally::exit_code;
+pub use capabilities::capabilities;
+#[cfg(feature = "log")]
+pub use log_bridge::init_log_bridge;
+
+/// Logs entry, exit and duration of a function at a chosen verbosity.
+///
+/// # Syntax
+///
+/// - `#[instrument]` → logs `→ name` and `← name took 1.23ms` if verbosity ≥ 1
+/// - `#[instrument(lvl = 2)]` → logs if verbosity ≥ 2
+/// - `#[instrument(args)]` → also logs the arguments (`→ name(a = 1, b = "x")`),
+///   which must implement `Debug`
+/// - `#[instrument(err)]` → logs `[ERROR] name failed: ...` if the function
+///   returns `Err`, whose error must implement `Display`
+///
+/// Options can be combined, e.g. `#[instrument(lvl = 3, args, err)]`. The
+/// function body runs in a closure, and `async fn`s are not supported.
+///
+/// # Example
+/// ```rust
+/// use verbosio::{instrument, set_verbosity};
+///
+/// #[instrument(lvl = 2, args, err)]
+/// fn parse_port(text: &str) -> Result<u16, std::num::ParseIntError> {
+///     let port = text.parse()?;
+///     Ok(port)
+/// }
+///
+/// set_verbosity!(2);
+/// assert_eq!(parse_port("8080"), Ok(8080)); // → parse_port(text = "8080")
+///                                           // ← parse_port took 1.20µs
+/// assert!(parse_port("http").is_err());     // also [ERROR] parse_port failed: ...
+/// ```
+///
+/// # Features
+/// Only available if the `"instrument"` feature is enabled, which pulls in
+/// the `verbosio-macros` crate.
+#[cfg(feature = "instrument")]
+pub use verbosio_macros::instrument;
\ No newline at end of file
@@ -0,0 +1,71 @@
+//! Bridge from the [`log`](https://crates.io/crates/log) facade to verbosio.
+//!
+//! Dependencies logging through `log::info!` and friends are rendered with
+//! verbosio's level tags, formatting and sinks once [`init_log_bridge`] is
+//! called, so their messages blend in with the application's own.
+//!
+//! Records are gated on the global verbosity like device logs in
+//! [`defmt_bridge`](crate::defmt_bridge): `error`, `warn` and `info` need
+//! verbosity ≥ 1, `debug` ≥ 3 and `trace` ≥ 4, see [`levels`](crate::levels). The record's target, usually
+//! the module path of the dependency, is kept.
+//!
+//! # Example
+//! ```rust
+//! use verbosio::set_verbosity;
+//!
+//! verbosio::init_log_bridge().expect("another logger is already installed");
+//! set_verbosity!(1);
+//! log::info!("printed as [INFO]");
+//! log::debug!("needs verbosity 3");
+//! ```
+//!
+//! # Features
+//! Requires the `"log"` feature.
+use log::{LevelFilter, Log, Metadata, Record as LogRecord, SetLoggerError};
+use crate::{Level, levels};
+use crate::record::Location;
+
+struct Bridge;
+
+static BRIDGE: Bridge = Bridge;
+
+/// Installs verbosio as the logger behind the `log` facade.
+///
+/// Fails if another logger has been installed already.
+pub fn init_log_bridge() -> Result<(), SetLoggerError> {
+    log::set_logger(&BRIDGE)?;
+    // verbosity can change at any time, so nothing is filtered up front
+    log::set_max_level(LevelFilter::Trace);
+    Ok(())
+}
+
+/// The verbosio level and minimum verbosity of a `log` level.
+fn map_level(level: log::Level) -> (Level, u8) {
+    match level {
+        log::Level::Error => (Level::Error, levels::NORMAL),
+        log::Level::Warn => (Level::Warn, levels::NORMAL),
+        log::Level::Info => (Level::Info, levels::NORMAL),
+        log::Level::Debug => (Level::Debug, levels::DEBUG),
+        log::Level::Trace => (Level::Trace, levels::TRACE),
+    }
+}
+
+impl Log for Bridge {
+    fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+        let (level, verbosity) = map_level(metadata.level());
+        crate::filter::level_enabled(metadata.target(), Some(level), verbosity)
+    }
+
+    fn log(&self, record: &LogRecord<'_>) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        let (level, _) = map_level(record.level());
+        let location = record.file_static().zip(record.line()).map(|(file, line)| Location { file, line });
+        crate::sink::emit_with_fields(Some(level), record.target(), *record.args(), Vec::new(), location);
+    }
+
+    fn flush(&self) {
+        crate::sink::flush();
+    }
+}
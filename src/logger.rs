@@ -0,0 +1,188 @@
+//! A function API mirroring the logging macros.
+//!
+//! [`Logger`] is a small value that can be stored in a struct, passed to
+//! generic code or called through a trait object, where macros are awkward.
+//! It checks the same verbosity and [`filter`](crate::filter) directives and
+//! writes through the same sinks and layers as the macros. A logger of a
+//! [`Verbosio`](crate::Verbosio) instance uses that instance's configuration
+//! instead.
+//!
+//! | Macro                              | Function                                  |
+//! |------------------------------------|-------------------------------------------|
+//! | `vinfo!("msg")`                    | `logger().info("msg")`                    |
+//! | `vwarn!(@lvl 2, "msg")`            | `logger().at(2).warn("msg")`              |
+//! | `verror!(target: "net", "msg")`    | `Logger::named("net").error("msg")`       |
+//! | `is_enabled!(@lvl 3)`              | `logger().enabled(3)`                     |
+//! | `vtrace!("msg")`                   | `logger().trace("msg")`                   |
+//!
+//! # Example
+//! ```rust
+//! use verbosio::{logger, set_verbosity, Logger};
+//!
+//! set_verbosity!(1);
+//! logger().info("App started");                  // [INFO] App started
+//! logger().at(2).info(format_args!("{} items", 3)); // not printed
+//!
+//! let net = Logger::named("net");
+//! net.warn("connection slow");                   // [WARN] net: connection slow
+//! assert!(!net.enabled(2));
+//! ```
+use std::borrow::Cow;
+use std::fmt;
+use crate::Level;
+use crate::instance::Verbosio;
+use crate::record::Location;
+
+/// Target of records from a [`logger`] without a name. Unlike the macros,
+/// functions can't see the caller's `module_path!()`.
+const UNNAMED_TARGET: &str = "verbosio::logger";
+
+/// Logs through the function API, see the [module docs](self).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Logger {
+    target: Option<Cow<'static, str>>,
+    /// The verbosity set with [`at`](Logger::at), otherwise the default of
+    /// the method, as for the macros.
+    lvl: Option<u8>,
+    /// The instance written through, the global configuration if `None`.
+    instance: Option<Verbosio>,
+}
+
+/// Returns a logger for the global verbosity, logging at level 1.
+pub fn logger() -> Logger {
+    Logger {
+        target: None,
+        lvl: None,
+        instance: None,
+    }
+}
+
+impl Default for Logger {
+    fn default() -> Self {
+        logger()
+    }
+}
+
+impl Logger {
+    /// A logger for the named target `target`, like
+    /// `vinfo!(target: "net", ...)`.
+    pub fn named(target: impl Into<Cow<'static, str>>) -> Self {
+        Logger {
+            target: Some(target.into()),
+            lvl: None,
+            instance: None,
+        }
+    }
+
+    pub(crate) fn for_instance(instance: Verbosio) -> Self {
+        Logger {
+            instance: Some(instance),
+            ..logger()
+        }
+    }
+
+    /// This logger with the named target `target`.
+    pub fn with_target(mut self, target: impl Into<Cow<'static, str>>) -> Self {
+        self.target = Some(target.into());
+        self
+    }
+
+    /// This logger, logging at verbosity `lvl` instead, like `@lvl`.
+    pub fn at(mut self, lvl: u8) -> Self {
+        self.lvl = Some(lvl);
+        self
+    }
+
+    /// The verbosity this logger logs at, 1 unless set with
+    /// [`at`](Logger::at). [`trace`](Logger::trace) defaults to 3.
+    pub fn lvl(&self) -> u8 {
+        self.lvl.unwrap_or(1)
+    }
+
+    /// The named target, if any.
+    pub fn target(&self) -> Option<&str> {
+        self.target.as_deref()
+    }
+
+    /// Whether a message at verbosity `lvl` would be printed.
+    pub fn enabled(&self, lvl: u8) -> bool {
+        if let Some(instance) = &self.instance {
+            return instance.enabled(lvl);
+        }
+        crate::filter::enabled(self.target.as_deref().unwrap_or(UNNAMED_TARGET), lvl)
+    }
+
+    /// Prints an untagged message, like `verbose!`.
+    #[track_caller]
+    pub fn verbose(&self, message: impl fmt::Display) {
+        self.emit(None, message);
+    }
+
+    /// Prints an `[INFO]` message, like `vinfo!`.
+    #[track_caller]
+    pub fn info(&self, message: impl fmt::Display) {
+        self.emit(Some(Level::Info), message);
+    }
+
+    /// Prints a `[WARN]` message, like `vwarn!`.
+    #[track_caller]
+    pub fn warn(&self, message: impl fmt::Display) {
+        self.emit(Some(Level::Warn), message);
+    }
+
+    /// Prints an `[ERROR]` message, like `verror!`.
+    #[track_caller]
+    pub fn error(&self, message: impl fmt::Display) {
+        self.emit(Some(Level::Error), message);
+    }
+
+    /// Prints a `[DEBUG]` message in debug builds, like `vebug!`.
+    #[track_caller]
+    pub fn debug(&self, message: impl fmt::Display) {
+        if cfg!(debug_assertions) {
+            self.emit(Some(Level::Debug), message);
+        }
+    }
+
+    /// Prints a `[TRACE]` message, like `vtrace!`. Logs at verbosity 3 unless
+    /// set with [`at`](Logger::at).
+    #[track_caller]
+    pub fn trace(&self, message: impl fmt::Display) {
+        self.emit(Some(Level::Trace), message);
+    }
+
+    /// Prints a message with a level chosen at runtime, like `vlog!`.
+    #[track_caller]
+    pub fn log(&self, level: Level, message: impl fmt::Display) {
+        match level {
+            Level::Debug => self.debug(message),
+            level => self.emit(Some(level), message),
+        }
+    }
+
+    #[track_caller]
+    fn emit(&self, level: Option<Level>, message: impl fmt::Display) {
+        let lvl = match (self.lvl, level) {
+            (Some(lvl), _) => lvl,
+            (None, Some(Level::Trace)) => 3,
+            (None, _) => 1,
+        };
+        let enabled = match &self.instance {
+            Some(instance) => instance.enabled(lvl),
+            None => crate::filter::level_enabled(self.target.as_deref().unwrap_or(UNNAMED_TARGET), level, lvl),
+        };
+        if !enabled {
+            return;
+        }
+        if let Some(instance) = &self.instance {
+            let target = self.target.as_deref().unwrap_or(UNNAMED_TARGET);
+            let location = Location::caller();
+            instance.emit(level, target, self.target.is_some(), format_args!("{}", message), location);
+            return;
+        }
+        match &self.target {
+            Some(target) => crate::sink::emit_named(level, target, format_args!("{}", message), Vec::new()),
+            None => crate::sink::emit(level, UNNAMED_TARGET, format_args!("{}", message)),
+        }
+    }
+}
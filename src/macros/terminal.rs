@@ -22,29 +22,84 @@
 #[macro_export]
 macro_rules! vsection {
     (@lvl $lvl:expr, $($arg:tt)+) => {
-        if $crate::VERBOSE.load(std::sync::atomic::Ordering::Relaxed) >= $lvl {
-            let msg = format!("=== {} ===\n", format!($($arg)+));
-            println!("{}", msg);
+        if $crate::is_enabled!(@lvl $lvl) {
+            $crate::sink::emit_section(format_args!($($arg)+));
+        } else {
+            $crate::span::next_section();
         }
     };
     ( $($arg:tt)+) => {
-        if $crate::VERBOSE.load(std::sync::atomic::Ordering::Relaxed) >= 1 {
-            let msg = format!("=== {} ===\n", format!($($arg)+));
-            println!("{}", msg);
+        if $crate::is_enabled!(@lvl 1) {
+            $crate::sink::emit_section(format_args!($($arg)+));
+        } else {
+            $crate::span::next_section();
         }
     };
 }
 
+/// Opens a section that lasts until the returned guard is dropped.
+///
+/// Like `vsection!`, but the section ends with a footer showing how long it
+/// took. Sections opened while the guard is alive are nested inside it.
+/// For parallel workers, [`buffer::set_buffered_sections`](crate::buffer::set_buffered_sections)
+/// prints each section as one contiguous block.
+///
+/// # Syntax
+///
+/// - `let _s = vsection_scoped!(@lvl 2, "Download {}", name);` → prints if verbosity ≥ 2
+/// - `let _s = vsection_scoped!("Download");` → prints if verbosity ≥ 1
+///
+/// # Output Format
+/// ```text
+/// === Download ===
+/// ...
+/// === Download done in 1.23s ===
+/// ```
+/// If the guard is dropped while panicking, the footer reads
+/// `=== Download failed after 1.23s ===`.
+///
+/// # Example
+/// ```rust
+/// use verbosio::{set_verbosity, vinfo, vsection_scoped};
+///
+/// set_verbosity!(1);
+/// {
+///     let _section = vsection_scoped!("Download");
+///     vinfo!("fetching index");
+/// } // footer printed here
+/// ```
+///
+/// # Features
+/// If the "color" feature is enabled, the footer is green, or red on failure.
+/// With the `"otel"` feature enabled, the section is recorded as an
+/// OpenTelemetry span.
+#[macro_export]
+macro_rules! vsection_scoped {
+    (@lvl $lvl:expr, $($arg:tt)+) => {
+        $crate::span::SectionGuard::enter(
+            $crate::is_enabled!(@lvl $lvl),
+            format!($($arg)+),
+        )
+    };
+    ( $($arg:tt)+) => {
+        $crate::vsection_scoped!(@lvl 1, $($arg)+)
+    };
+}
+
 /// Starts a status spinner in the terminal if verbosity is high enough.
 ///
 /// # Syntax
 ///
 /// - status_line!(@lvl 2, "Working on task...") → spinner if verbosity ≥ 2
 /// - status_line!("Processing…") → spinner if verbosity ≥ 1
+/// - status_line!(style: SpinnerStyle::DOTS, "Processing…") → spinner in a
+///   style other than the [global one](crate::status::set_spinner_style), see
+///   [`SpinnerStyle`](crate::status::SpinnerStyle); also after `@lvl N,`
 ///
 /// # Return
 /// Returns Some(SpinnerHandle) if spinner was shown, otherwise None.
-/// Call .stop() on the handle to stop the spinner manually.
+/// Call .stop() on the handle to stop the spinner manually; it returns the
+/// `Duration` the spinner was shown.
 ///
 /// # Example
 ///```rust
@@ -53,26 +108,40 @@ macro_rules! vsection {
 /// if let Some(spinner) = status_line!("Building…") {
 /// // Do some work
 /// std::thread::sleep(std::time::Duration::from_secs(2));
-/// spinner.stop();
+/// let elapsed = spinner.stop();
+/// verbosio::vinfo!("built in {:.1?}", elapsed);
 /// }
 ///```
 ///
 /// # Notes
-/// The spinner runs in a background thread and redraws every 100ms.
-/// You must manually .stop() it to avoid ghosting.
+/// The spinner runs in a background thread and redraws every 100ms, or as
+/// often as its style says.
+/// Dropping the handle stops the spinner and clears its line, also on an
+/// early return or a panic, so keep it in a named binding: `let _ = ` drops
+/// it right away.
 ///
 /// # Features
 /// Only available if the "status" feature is enabled.
 #[cfg(feature = "status")]
 #[macro_export]
 macro_rules! status_line {
+    (@lvl $lvl:expr, style: $style:expr, $msg:expr) => {{
+        if $crate::is_enabled!(@lvl $lvl) {
+            Some($crate::status::start_spinner_with($style, || $msg.to_string()))
+        } else {
+            None
+        }
+    }};
     (@lvl $lvl:expr, $msg:expr) => {{
-        if $crate::VERBOSE.load(std::sync::atomic::Ordering::Relaxed) >= $lvl {
+        if $crate::is_enabled!(@lvl $lvl) {
             Some($crate::status::start_spinner(|| $msg.to_string()))
         } else {
             None
         }
     }};
+    (style: $style:expr, $msg:expr) => {
+        $crate::status_line!(@lvl 1, style: $style, $msg)
+    };
     ($msg:expr) => {
         $crate::status_line!(@lvl 1, $msg)
     };
@@ -83,11 +152,12 @@ macro_rules! status_line {
 ///
 /// # Syntax
 ///
-/// - status_line_clear!();
+/// - status_line_clear!(@lvl 2); → clears if verbosity ≥ 2
+/// - status_line_clear!(); → clears if verbosity ≥ 1
 ///
 /// # Effect
 /// Clears any visible spinner or status line from the terminal output.
-/// Has no effect if no spinner is active.
+/// Has no effect if no spinner is active, or below the given verbosity.
 ///
 /// # Example
 ///```rust
@@ -100,28 +170,44 @@ macro_rules! status_line {
 #[cfg(feature = "status")]
 #[macro_export]
 macro_rules! status_line_clear {
-    () => {{
-        $crate::status::clear_status_line();
+    (@lvl $lvl:expr) => {{
+        if $crate::is_enabled!(@lvl $lvl)
+            && $crate::status::has_active_spinner()
+        {
+            $crate::status::clear_status_line();
+        }
     }};
+    () => {
+        $crate::status_line_clear!(@lvl 1)
+    };
 }
 
 /// Finishes a status line and prints a final message in its place.
 ///
 /// # Syntax
 ///
-/// - status_line_done!("Done");
+/// - status_line_done!(@lvl 2, "Done"); → finishes if verbosity ≥ 2
+/// - status_line_done!("Done"); → finishes if verbosity ≥ 1
 ///
 /// # Effect
-/// Stops any active spinner and replaces it with the given message.
+/// Stops the most recently started spinner and replaces it with the given
+/// message. The message is also printed after the spinner's handle was
+/// stopped, unless another spinner was started since. Nothing is printed if
+/// no spinner was started, or below the given verbosity, so a quiet run never
+/// shows a stray "done" line.
+///
+/// # Return
+/// Returns `Some(Duration)` with how long the spinner was shown, or `None` if
+/// nothing was printed.
 ///
 /// # Example
 ///```rust
 /// use verbosio::{status_line, status_line_done};
 ///
-/// if let Some(spinner) = status_line!("Compiling") {
+/// let _spinner = status_line!("Compiling");
 /// // Do work...
-/// spinner.stop();
-/// status_line_done!("Compiled successfully!");
+/// if let Some(elapsed) = status_line_done!("Compiled successfully!") {
+///     verbosio::vinfo!("compiling took {:.1?}", elapsed);
 /// }
 ///```
 ///
@@ -130,62 +216,535 @@ macro_rules! status_line_clear {
 #[cfg(feature = "status")]
 #[macro_export]
 macro_rules! status_line_done {
-    ($msg:expr) => {{
-        #[cfg(feature = "status")]
-        $crate::status::finish_status_line($msg);
-    }};
+    (@lvl $lvl:expr, $msg:expr) => {
+        if $crate::is_enabled!(@lvl $lvl) {
+            $crate::status::finish_status_line($msg)
+        } else {
+            None
+        }
+    };
+    ($msg:expr) => {
+        $crate::status_line_done!(@lvl 1, $msg)
+    };
 }
 
 
 
+/// Shows a status line while a closure runs, then marks it as done or failed.
+///
+/// The closure's value is returned. When it finishes, the status line is
+/// replaced by `✔ message (1.23s)`, or `✖ message (1.23s)` if the value is an
+/// `Err`, `None` or `false` (see [`wait::Outcome`](crate::wait::Outcome)).
+///
+/// # Syntax
+///
+/// - `vwait!(@lvl 2, "Resolving dependencies", || resolve())` → shown if verbosity ≥ 2
+/// - `vwait!("Resolving dependencies", || resolve())` → shown if verbosity ≥ 1
+///
+/// # Example
+/// ```rust
+/// use verbosio::{set_verbosity, vwait};
+///
+/// set_verbosity!(1);
+/// let lock: Result<u32, String> = vwait!("Resolving dependencies", || Ok(42));
+/// assert_eq!(lock, Ok(42));
+/// ```
+///
+/// # Features
+/// The spinner is only animated if the "status" feature is enabled; without
+/// it only the final line is printed.
+#[macro_export]
+macro_rules! vwait {
+    (@lvl $lvl:expr, $msg:expr, $work:expr) => {
+        $crate::wait::wait(
+            $crate::is_enabled!(@lvl $lvl),
+            $msg.to_string(),
+            $work,
+        )
+    };
+    ($msg:expr, $work:expr) => {
+        $crate::vwait!(@lvl 1, $msg, $work)
+    };
+}
+
+/// Like [`vwait!`], but for a future. Returns a future to `.await`.
+///
+/// # Syntax
+///
+/// - `vwait_async!(@lvl 2, "Fetching index", fetch_index()).await`
+/// - `vwait_async!("Fetching index", fetch_index()).await`
+///
+/// # Example
+/// ```rust
+/// use verbosio::vwait_async;
+///
+/// async fn fetch_index() -> Result<usize, std::io::Error> {
+///     Ok(128)
+/// }
+///
+/// async fn update() -> Result<usize, std::io::Error> {
+///     vwait_async!("Fetching index", fetch_index()).await
+/// }
+/// ```
+#[macro_export]
+macro_rules! vwait_async {
+    (@lvl $lvl:expr, $msg:expr, $work:expr) => {
+        $crate::wait::wait_async(
+            $crate::is_enabled!(@lvl $lvl),
+            $msg.to_string(),
+            $work,
+        )
+    };
+    ($msg:expr, $work:expr) => {
+        $crate::vwait_async!(@lvl 1, $msg, $work)
+    };
+}
+
 #[cfg(feature = "status")]
 pub mod status {
     use std::io::{stdout, Write};
-    use std::sync::{Arc, atomic::{AtomicBool, Ordering}};
-    use std::thread;
-    use std::time::Duration;
-    use crossterm::{execute, terminal::{ClearType, Clear}, cursor::{MoveToColumn}};
+    use std::borrow::Cow;
+    use std::str::FromStr;
+    use std::sync::{Arc, Mutex, OnceLock, RwLock, atomic::{AtomicBool, Ordering}};
+    use std::thread::{self, JoinHandle};
+    use std::time::{Duration, Instant};
+    use crossterm::{execute, terminal::{self, ClearType, Clear}, cursor::{MoveToColumn}};
+    use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+    use once_cell::sync::Lazy;
+
+    /// How a spinner is drawn: `{prefix}{frame} {message}{suffix}`, with the
+    /// next frame every `interval`.
+    ///
+    /// Besides the built-in styles, any frames can be used. On terminals
+    /// without Unicode support, styles with non-ASCII frames fall back to the
+    /// frames of [`LINE`](SpinnerStyle::LINE).
+    ///
+    /// # Example
+    /// ```rust
+    /// use std::time::Duration;
+    /// use verbosio::status::{set_spinner_style, SpinnerStyle};
+    ///
+    /// set_spinner_style(SpinnerStyle::DOTS.prefix("  ").suffix(" (press v for details)"));
+    /// let moon = SpinnerStyle::new(&["🌑", "🌓", "🌕", "🌗"]).interval(Duration::from_millis(250));
+    /// assert_eq!("arrows".parse::<SpinnerStyle>(), Ok(SpinnerStyle::ARROWS));
+    /// assert_eq!(SpinnerStyle::LINE.interval(Duration::ZERO), SpinnerStyle::LINE.interval(SpinnerStyle::MIN_INTERVAL));
+    /// # set_spinner_style(SpinnerStyle::DEFAULT);
+    /// ```
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct SpinnerStyle {
+        frames: &'static [&'static str],
+        interval: Duration,
+        prefix: Cow<'static, str>,
+        suffix: Cow<'static, str>,
+    }
+
+    impl SpinnerStyle {
+        /// `⠋ ⠙ ⠹ ⠸ ⠼ ⠴ ⠦ ⠧ ⠇ ⠏`, the default.
+        pub const BRAILLE: SpinnerStyle = SpinnerStyle::with_frames(&["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"], 100);
+
+        /// `| / - \`, plain ASCII.
+        pub const LINE: SpinnerStyle = SpinnerStyle::with_frames(&["|", "/", "-", "\\"], 100);
+
+        /// `.  ..  ...`, plain ASCII and calmer.
+        pub const DOTS: SpinnerStyle = SpinnerStyle::with_frames(&[".  ", ".. ", "...", "   "], 300);
+
+        /// `← ↖ ↑ ↗ → ↘ ↓ ↙`
+        pub const ARROWS: SpinnerStyle = SpinnerStyle::with_frames(&["←", "↖", "↑", "↗", "→", "↘", "↓", "↙"], 100);
 
-    static SPINNER_FRAMES: &[&str] = &["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+        pub const DEFAULT: SpinnerStyle = SpinnerStyle::BRAILLE;
 
+        /// The shortest interval between two frames, about one frame of a
+        /// 60 Hz display.
+        pub const MIN_INTERVAL: Duration = Duration::from_millis(16);
 
+        const fn with_frames(frames: &'static [&'static str], interval_ms: u64) -> Self {
+            SpinnerStyle {
+                frames,
+                interval: Duration::from_millis(interval_ms),
+                prefix: Cow::Borrowed(""),
+                suffix: Cow::Borrowed(""),
+            }
+        }
+
+        /// A style cycling through `frames`, every 100ms unless set with
+        /// [`interval`](SpinnerStyle::interval).
+        pub fn new(frames: &'static [&'static str]) -> Self {
+            SpinnerStyle::with_frames(frames, 100)
+        }
+
+        /// Shows the next frame every `interval`, but at most every
+        /// [`MIN_INTERVAL`](SpinnerStyle::MIN_INTERVAL), so a zero or tiny
+        /// interval doesn't keep the spinner thread redrawing in a busy loop.
+        pub fn interval(mut self, interval: Duration) -> Self {
+            self.interval = interval.max(SpinnerStyle::MIN_INTERVAL);
+            self
+        }
+
+        /// Text in front of the frame, e.g. an indentation.
+        pub fn prefix(mut self, prefix: impl Into<Cow<'static, str>>) -> Self {
+            self.prefix = prefix.into();
+            self
+        }
+
+        /// Text after the message.
+        pub fn suffix(mut self, suffix: impl Into<Cow<'static, str>>) -> Self {
+            self.suffix = suffix.into();
+            self
+        }
 
+        /// The frames to draw on this terminal.
+        fn frames(&self) -> &'static [&'static str] {
+            if !crate::capabilities().unicode && !self.frames.iter().all(|frame| frame.is_ascii()) {
+                return SpinnerStyle::LINE.frames;
+            }
+            self.frames
+        }
+    }
+
+    impl Default for SpinnerStyle {
+        fn default() -> Self {
+            SpinnerStyle::DEFAULT
+        }
+    }
+
+    /// Parses the name of a built-in style: `braille`, `line`, `dots` or
+    /// `arrows`.
+    impl FromStr for SpinnerStyle {
+        type Err = String;
+
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            match s.trim().to_ascii_lowercase().as_str() {
+                "braille" => Ok(SpinnerStyle::BRAILLE),
+                "line" => Ok(SpinnerStyle::LINE),
+                "dots" => Ok(SpinnerStyle::DOTS),
+                "arrows" => Ok(SpinnerStyle::ARROWS),
+                _ => Err(format!("invalid spinner style '{}', expected braille, line, dots or arrows", s)),
+            }
+        }
+    }
+
+    static STYLE: RwLock<SpinnerStyle> = RwLock::new(SpinnerStyle::DEFAULT);
+
+    /// Sets the style of all spinners started afterwards without a style of
+    /// their own.
+    pub fn set_spinner_style(style: SpinnerStyle) {
+        *STYLE.write().unwrap_or_else(|e| e.into_inner()) = style;
+    }
+
+    /// Returns the style set with [`set_spinner_style`],
+    /// [`SpinnerStyle::DEFAULT`] otherwise.
+    pub fn spinner_style() -> SpinnerStyle {
+        STYLE.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// All spinners that have been started and not yet stopped.
+    static ACTIVE_SPINNERS: Lazy<Mutex<Vec<Arc<SpinnerState>>>> = Lazy::new(|| Mutex::new(Vec::new()));
+
+    /// The spinner last stopped with [`SpinnerHandle::stop`], which
+    /// [`finish_status_line`] still finishes while no spinner is active.
+    static STOPPED_SPINNER: Mutex<Option<Arc<SpinnerState>>> = Mutex::new(None);
+
+    /// Whether spinners listen for verbosity key presses.
+    static KEY_CONTROLS: AtomicBool = AtomicBool::new(false);
+
+    /// Whether a spinner has put the terminal into raw mode to read keys.
+    static RAW_MODE: AtomicBool = AtomicBool::new(false);
+
+    /// Lets the user change the verbosity while a spinner is shown.
+    ///
+    /// While enabled and stdin is a terminal, active spinners read key
+    /// presses: `v` raises the verbosity by one, `q` sets it to `0`.
+    /// The terminal is in raw mode while a spinner is active; `Ctrl+C` still
+    /// exits the process after restoring the terminal.
+    ///
+    /// # Example
+    /// ```rust
+    /// verbosio::status::set_key_controls(true);
+    /// ```
+    pub fn set_key_controls(enabled: bool) {
+        KEY_CONTROLS.store(enabled, Ordering::Relaxed);
+    }
+
+    /// Whether the terminal is in raw mode, where `\n` needs a `\r` as well.
+    pub(crate) fn raw_mode_active() -> bool {
+        RAW_MODE.load(Ordering::Relaxed)
+    }
+
+    /// Enables raw mode for key controls, if requested and possible.
+    /// Returns whether this call enabled it.
+    fn enter_key_controls() -> bool {
+        use std::io::IsTerminal;
+        if !KEY_CONTROLS.load(Ordering::Relaxed) || !std::io::stdin().is_terminal() {
+            return false;
+        }
+        if RAW_MODE.swap(true, Ordering::Relaxed) {
+            return false;
+        }
+        if terminal::enable_raw_mode().is_err() {
+            RAW_MODE.store(false, Ordering::Relaxed);
+            return false;
+        }
+        true
+    }
+
+    fn leave_key_controls() {
+        let _ = terminal::disable_raw_mode();
+        RAW_MODE.store(false, Ordering::Relaxed);
+    }
+
+    /// Applies all pending verbosity key presses.
+    fn handle_keys() {
+        while event::poll(Duration::ZERO).unwrap_or(false) {
+            let Ok(Event::Key(key)) = event::read() else { continue };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            match key.code {
+                KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    leave_key_controls();
+                    crate::exit(130);
+                }
+                KeyCode::Char('v') => {
+                    crate::inc_verbosity!();
+                }
+                KeyCode::Char('q') => {
+                    crate::set_verbosity!(0);
+                }
+                _ => {}
+            }
+        }
+    }
+
+
+    struct SpinnerState {
+        stop: AtomicBool,
+        start: Instant,
+        /// How long the spinner was shown, set when it is stopped.
+        elapsed: OnceLock<Duration>,
+        /// The thread drawing the spinner, until it is joined.
+        thread: Mutex<Option<JoinHandle<()>>>,
+        /// Set with [`SpinnerHandle::set_message`], replacing the message
+        /// function.
+        message: Mutex<Option<String>>,
+    }
+
+    impl SpinnerState {
+        /// Stops the spinner and waits until its thread has drawn the last
+        /// frame, so nothing is drawn over what is printed next.
+        fn stop(&self) -> Duration {
+            let elapsed = *self.elapsed.get_or_init(|| self.start.elapsed());
+            self.stop.store(true, Ordering::Relaxed);
+            let thread = self.thread.lock().unwrap_or_else(|e| e.into_inner()).take();
+            // the spinner thread itself stops all spinners on Ctrl+C
+            if let Some(thread) = thread
+                && thread.thread().id() != thread::current().id()
+            {
+                thread.thread().unpark();
+                let _ = thread.join();
+            }
+            elapsed
+        }
+
+        fn is_stopped(&self) -> bool {
+            self.stop.load(Ordering::Relaxed)
+        }
+
+        fn message(&self) -> Option<String> {
+            self.message.lock().unwrap_or_else(|e| e.into_inner()).clone()
+        }
+    }
+
+    /// A running spinner, see [`status_line!`](crate::status_line).
+    ///
+    /// Dropping the handle without calling [`stop`](SpinnerHandle::stop)
+    /// stops the spinner and clears its line.
+    #[must_use = "the spinner stops when the handle is dropped"]
     pub struct SpinnerHandle {
-        stop: Arc<AtomicBool>,
+        state: Arc<SpinnerState>,
     }
 
     impl SpinnerHandle {
-        pub fn stop(self){
-            self.stop.store(true, Ordering::Relaxed);
+        /// Stops the spinner, clears its line and returns how long it was
+        /// shown. Once it returns, the spinner no longer draws anything.
+        ///
+        /// Until another spinner is started, [`finish_status_line`] still
+        /// prints its final message.
+        pub fn stop(self) -> Duration {
+            let elapsed = self.state.stop();
+            deregister(&self.state);
+            *STOPPED_SPINNER.lock().unwrap_or_else(|e| e.into_inner()) = Some(self.state.clone());
+            clear_status_line();
+            elapsed
+        }
+
+        /// Replaces the message shown next to the spinner, from the next
+        /// frame on, e.g. to show the current step of a long operation.
+        ///
+        /// Where the line can't be redrawn (CI logs, terminals without
+        /// cursor control), the new message is printed as a line of its own.
+        ///
+        /// # Example
+        /// ```rust
+        /// use verbosio::status_line;
+        ///
+        /// if let Some(spinner) = status_line!("step 1/2: compiling") {
+        ///     // compile...
+        ///     spinner.set_message("step 2/2: linking");
+        ///     // link...
+        ///     spinner.stop();
+        /// }
+        /// ```
+        pub fn set_message(&self, message: impl Into<String>) {
+            if self.state.is_stopped() {
+                return;
+            }
+            let message = message.into();
+            print_once(&message);
+            *self.state.message.lock().unwrap_or_else(|e| e.into_inner()) = Some(message);
+        }
+
+        /// How long the spinner has been shown so far.
+        pub fn elapsed(&self) -> Duration {
+            match self.state.elapsed.get() {
+                Some(elapsed) => *elapsed,
+                None => self.state.start.elapsed(),
+            }
+        }
+    }
+
+    impl Drop for SpinnerHandle {
+        fn drop(&mut self) {
+            deregister(&self.state);
+            // a stopped spinner's line may already show a final message
+            if !self.state.is_stopped() {
+                self.state.stop();
+                clear_status_line();
+            }
         }
     }
 
+    /// Removes a spinner from [`ACTIVE_SPINNERS`], so it no longer counts as
+    /// active once its handle is stopped or dropped.
+    fn deregister(state: &Arc<SpinnerState>) {
+        ACTIVE_SPINNERS
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .retain(|spinner| !Arc::ptr_eq(spinner, state));
+    }
+
+    /// Starts a spinner in the [global style](set_spinner_style).
     pub fn start_spinner<F: Fn() -> String + Send + 'static>(msg_fn: F) -> SpinnerHandle {
-        let stop = Arc::new(AtomicBool::new(false));
-        let stop_clone = stop.clone();
+        start_spinner_with(spinner_style(), msg_fn)
+    }
 
-        thread::spawn(move || {
+    /// Starts a spinner in `style`.
+    pub fn start_spinner_with<F: Fn() -> String + Send + 'static>(style: SpinnerStyle, msg_fn: F) -> SpinnerHandle {
+        let state = Arc::new(SpinnerState {
+            stop: AtomicBool::new(false),
+            start: Instant::now(),
+            elapsed: OnceLock::new(),
+            thread: Mutex::new(None),
+            message: Mutex::new(None),
+        });
+        let thread_state = state.clone();
+
+        ACTIVE_SPINNERS.lock().unwrap_or_else(|e| e.into_inner()).push(state.clone());
+        STOPPED_SPINNER.lock().unwrap_or_else(|e| e.into_inner()).take();
+
+        if print_once(&msg_fn()) {
+            return SpinnerHandle {state};
+        }
+        let frames = style.frames();
+
+        let thread = thread::spawn(move || {
+            let key_controls = enter_key_controls();
             let mut idx = 0;
-            while !stop_clone.load(Ordering::Relaxed) {
-                let spinner = SPINNER_FRAMES[idx % SPINNER_FRAMES.len()];
-                let msg = format!("{} {}", spinner, msg_fn());
-
-                execute!(
-                    stdout(),
-                    MoveToColumn(0),
-                    Clear(ClearType::CurrentLine),
-                ).unwrap();
-                print!("{}", msg);
-                stdout().flush().unwrap();
+            while !thread_state.is_stopped() {
+                if key_controls {
+                    handle_keys();
+                }
+                let spinner = frames.get(idx % frames.len().max(1)).copied().unwrap_or("");
+                let message = thread_state.message().unwrap_or_else(&msg_fn);
+                let msg = format!("{}{} {}{}", style.prefix, spinner, message, style.suffix);
+
+                if crate::pinned::is_pinned() {
+                    crate::pinned::set_status_text(0, msg);
+                } else {
+                    execute!(
+                        stdout(),
+                        MoveToColumn(0),
+                        Clear(ClearType::CurrentLine),
+                    ).unwrap();
+                    print!("{}", msg);
+                    stdout().flush().unwrap();
+                }
 
                 idx += 1;
-                thread::sleep(Duration::from_millis(100));
+                // woken early when stopped
+                thread::park_timeout(style.interval);
+            }
+            if key_controls {
+                leave_key_controls();
             }
         });
+        *state.thread.lock().unwrap_or_else(|e| e.into_inner()) = Some(thread);
 
-        SpinnerHandle {stop}
+        SpinnerHandle {state}
     }
+
+    /// Prints `msg` as a plain line if the spinner line can't be redrawn,
+    /// and returns whether it did.
+    fn print_once(msg: &str) -> bool {
+        // CI logs can't redraw a line, so print the message once instead
+        if let Some(line) = crate::output::render_progress(crate::output::output_mode(), msg) {
+            println!("{}", line);
+            return true;
+        }
+        // neither can a terminal without cursor control
+        if !crate::capabilities().interactive {
+            println!("{}", msg);
+            return true;
+        }
+        false
+    }
+
+    /// Stops every active spinner and clears the status line.
+    pub fn stop_all_spinners() {
+        // not stopped under the lock, which a spinner thread may need to exit
+        let active = std::mem::take(&mut *ACTIVE_SPINNERS.lock().unwrap_or_else(|e| e.into_inner()));
+        if active.is_empty() {
+            return;
+        }
+        for spinner in active {
+            spinner.stop();
+        }
+        clear_status_line();
+    }
+    /// Whether a spinner was started and not yet stopped, dropped or
+    /// finished with [`finish_status_line`].
+    ///
+    /// # Example
+    /// ```rust
+    /// use verbosio::status::{has_active_spinner, start_spinner};
+    ///
+    /// let spinner = start_spinner(|| "Downloading".to_string());
+    /// assert!(has_active_spinner());
+    /// drop(spinner);
+    /// assert!(!has_active_spinner());
+    /// ```
+    pub fn has_active_spinner() -> bool {
+        !ACTIVE_SPINNERS.lock().unwrap_or_else(|e| e.into_inner()).is_empty()
+    }
+
     pub fn clear_status_line(){
+        // without cursor control the spinner was printed as a plain line
+        if !crate::capabilities().interactive {
+            return;
+        }
+        if crate::pinned::is_pinned() {
+            crate::pinned::set_status_text(0, "");
+            return;
+        }
         let _ = execute!(
             stdout(),
             MoveToColumn(0),
@@ -193,9 +752,36 @@ pub mod status {
         );
         let _ = stdout().flush();
     }
-    pub fn finish_status_line(msg: &str){
+    /// Stops the most recently started spinner and replaces the status line
+    /// with `msg`.
+    ///
+    /// A spinner already stopped with [`SpinnerHandle::stop`] is finished as
+    /// well, if no other spinner was started since. Returns how long that
+    /// spinner was shown. Does nothing and returns `None` if no spinner was
+    /// started since the last one was finished.
+    ///
+    /// # Example
+    /// ```rust
+    /// use verbosio::history::{enable_history, recent_lines};
+    /// use verbosio::status::{finish_status_line, start_spinner};
+    ///
+    /// enable_history(8);
+    /// let spinner = start_spinner(|| "Processing".to_string());
+    /// spinner.stop();
+    /// assert!(finish_status_line("Done processing").is_some());
+    /// assert!(recent_lines()[0].ends_with("Done processing"));
+    ///
+    /// assert!(finish_status_line("Done again").is_none());
+    /// assert_eq!(recent_lines().len(), 1);
+    /// ```
+    pub fn finish_status_line(msg: &str) -> Option<Duration> {
+        let active = ACTIVE_SPINNERS.lock().unwrap_or_else(|e| e.into_inner()).pop();
+        let stopped = STOPPED_SPINNER.lock().unwrap_or_else(|e| e.into_inner()).take();
+        let spinner = active.or(stopped)?;
+        let elapsed = spinner.stop();
         clear_status_line();
-        println!("{}", msg);
+        crate::sink::emit_with_fields(None, module_path!(), format_args!("{}", msg), Vec::new(), None);
+        Some(elapsed)
     }
 }
 
@@ -1,9 +1,14 @@
 /// Sets the global verbosity level.
 ///
+/// Turns [quiet mode](crate::set_quiet) off, so `set_verbosity!(0)` hides
+/// errors as well.
+///
 /// # Usage
 ///
 /// - `set_verbosity!();` sets verbosity to `1`.
 /// - `set_verbosity!(3);` sets verbosity to level `3`.
+/// - `set_verbosity!("debug");` sets verbosity to a [named tier](crate::levels), here `3`. Unknown names fail to compile.
+/// - `set_verbosity!(Verbosity::Debug);` the same with a [`Verbosity`](crate::levels::Verbosity).
 ///
 /// # Example
 /// ```rust
@@ -11,19 +16,224 @@
 ///
 /// set_verbosity!();       // same as set_verbosity!(1);
 /// set_verbosity!(2);      // sets verbosity to 2
+/// set_verbosity!("trace"); // sets verbosity to 4
+/// ```
+///
+/// ```rust,compile_fail
+/// verbosio::set_verbosity!("debgu"); // not a verbosity
 /// ```
 #[macro_export]
 macro_rules! set_verbosity {
     () => {
-        $crate::VERBOSE.store(1, std::sync::atomic::Ordering::Relaxed);
+        $crate::set_verbosity!(1)
+    };
+    ($($lvl:tt)+) => {{
+        $crate::filter::set_quiet(false);
+        $crate::VERBOSE.store($crate::__verbosity!($($lvl)+), std::sync::atomic::Ordering::Relaxed);
+    }};
+}
+
+/// Converts a verbosity argument to a number. Literals are checked at
+/// compile time, see [`literal_verbosity`](crate::levels::literal_verbosity);
+/// other values go through [`IntoVerbosity`](crate::levels::IntoVerbosity).
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __verbosity {
+    ($verbosity:literal) => {{
+        const VERBOSITY: u8 = $crate::levels::literal_verbosity(stringify!($verbosity));
+        VERBOSITY
+    }};
+    ($verbosity:expr) => {
+        $crate::levels::IntoVerbosity::into_verbosity($verbosity)
+    };
+}
+
+/// Switches to quiet mode: verbosity `0`, but errors still print.
+///
+/// At verbosity `0` every message is suppressed, errors included. Quiet mode,
+/// as selected by a `-q` flag, only hides progress: `verror!` messages at
+/// `@lvl 1` (or the default level) keep printing, while `vinfo!`, `vwarn!`
+/// and everything more detailed stay silent. See
+/// [`error_enabled`](crate::filter::error_enabled).
+///
+/// # Usage
+///
+/// - `set_quiet!();` sets verbosity `0` and turns quiet mode on
+/// - `set_quiet!(false);` turns quiet mode off, leaving the verbosity alone
+///
+/// # Example
+/// ```rust
+/// use verbosio::{set_quiet, verror, vinfo, vwarn};
+///
+/// set_quiet!();
+/// vinfo!("compiling");        // not printed
+/// vwarn!("unused variable");  // not printed
+/// verror!("build failed");    // printed
+/// # set_quiet!(false);
+/// ```
+#[macro_export]
+macro_rules! set_quiet {
+    () => {
+        $crate::set_quiet!(true)
+    };
+    ($quiet:expr) => {{
+        let quiet: bool = $quiet;
+        if quiet {
+            $crate::set_verbosity!(0);
+        }
+        $crate::filter::set_quiet(quiet);
+    }};
+}
+
+/// Copies every message to a log file in addition to the terminal.
+///
+/// The file is appended to and created if needed. Lines are written without
+/// colors and always carry a timestamp, see [`sink`](crate::sink).
+/// Returns an `io::Result`.
+///
+/// # Usage
+///
+/// - `set_log_file!("build.log")?;`
+///
+/// # Example
+/// ```rust
+/// use verbosio::{set_log_file, set_verbosity, vinfo};
+///
+/// set_verbosity!(1);
+/// set_log_file!(std::env::temp_dir().join("verbosio-build.log")).expect("failed to open log file");
+/// vinfo!("compiling"); // [INFO] compiling
+/// // build.log: [INFO] [2025-07-14T09:26:53.589Z] compiling
+/// # verbosio::sink::clear_log_file();
+/// ```
+#[macro_export]
+macro_rules! set_log_file {
+    ($path:expr) => {
+        $crate::sink::set_log_file($path)
+    };
+}
+
+/// Registers a [`Sink`](crate::sink::Sink) receiving every printed record,
+/// next to the terminal. Returns its [`SinkId`](crate::sink::SinkId).
+///
+/// # Usage
+///
+/// - `let id = add_sink!(my_sink);`
+///
+/// # Example
+/// ```rust
+/// use verbosio::sink::Sink;
+/// use verbosio::{add_sink, remove_sink, Record};
+///
+/// struct Pane;
+///
+/// impl Sink for Pane {
+///     fn write(&self, record: &Record) {
+///         // append record.message to a GUI widget
+///     }
+/// }
+///
+/// let id = add_sink!(Pane);
+/// remove_sink!(id);
+/// ```
+#[macro_export]
+macro_rules! add_sink {
+    ($sink:expr) => {
+        $crate::sink::add_sink($sink)
+    };
+}
+
+/// Removes a sink registered with [`add_sink!`], or the default terminal
+/// sink with `remove_sink!(SinkId::TERMINAL)`. Returns whether it was
+/// registered.
+///
+/// # Usage
+///
+/// - `remove_sink!(id);`
+#[macro_export]
+macro_rules! remove_sink {
+    ($id:expr) => {
+        $crate::sink::remove_sink($id)
+    };
+}
+
+/// Sets the output mode (see [`OutputMode`](crate::output::OutputMode)).
+///
+/// # Usage
+///
+/// - `set_output_mode!(Human);`
+/// - `set_output_mode!(BuildScript);`
+/// - `set_output_mode!(GitHubActions);`
+/// - `set_output_mode!(TeamCity);`
+/// - `set_output_mode!(Json);` → one JSON object per line
+///
+/// # Example
+/// ```rust
+/// use verbosio::set_output_mode;
+///
+/// set_output_mode!(BuildScript);
+/// verbosio::vwarn!("printed as cargo:warning=...");
+/// ```
+#[macro_export]
+macro_rules! set_output_mode {
+    ($mode:ident) => {
+        $crate::output::set_output_mode($crate::output::OutputMode::$mode)
+    };
+}
+
+/// Sets when colored output is used (see [`ColorMode`](crate::color::ColorMode)).
+///
+/// # Usage
+///
+/// - `set_color_mode!(Auto);` → follow `NO_COLOR`, `CLICOLOR`, `CLICOLOR_FORCE` and TTY detection (default)
+/// - `set_color_mode!(Always);`
+/// - `set_color_mode!(Never);`
+///
+/// # Example
+/// ```rust
+/// use verbosio::set_color_mode;
+///
+/// set_color_mode!(Never);
+/// verbosio::vwarn!("printed without colors");
+/// # set_color_mode!(Auto);
+/// ```
+#[macro_export]
+macro_rules! set_color_mode {
+    ($mode:ident) => {
+        $crate::color::set_color_mode($crate::color::ColorMode::$mode)
     };
-    ($lvl:expr) => {
-        $crate::VERBOSE.store($lvl, std::sync::atomic::Ordering::Relaxed);
+}
+
+/// Sets what the timestamp in front of each message shows (see
+/// [`TimeMode`](crate::util::TimeMode)).
+///
+/// # Usage
+///
+/// - `set_time_mode!(Wallclock);` → `[2025-07-14 09:26:53]` (default with the `time` feature)
+/// - `set_time_mode!(Elapsed);` → `[+12.032s]` since [`mark_start`](crate::util::mark_start) or the first message
+/// - `set_time_mode!(Off);` → no timestamp (default without the `time` feature)
+///
+/// # Example
+/// ```rust
+/// use verbosio::{set_time_mode, set_verbosity, vinfo};
+///
+/// set_verbosity!(1);
+/// set_time_mode!(Elapsed);
+/// vinfo!("ready"); // [INFO] [+0.004s] ready
+/// # set_time_mode!(Off);
+/// ```
+#[macro_export]
+macro_rules! set_time_mode {
+    ($mode:ident) => {
+        $crate::util::set_time_mode($crate::util::TimeMode::$mode)
     };
 }
 
 /// Retrieves the current global verbosity level.
 ///
+/// A verbosity set for the current thread with
+/// [`with_thread_verbosity!`](crate::with_thread_verbosity) is not taken into
+/// account, see [`scope::verbosity`](crate::scope::verbosity) for that.
+///
 /// # Returns
 /// A `u8` value representing the current verbosity.
 ///
@@ -40,197 +250,1491 @@ macro_rules! get_verbosity {
     };
 }
 
-/// Sets verbosity level based on the `VERBOSE` environment variable.
+/// Raises the global verbosity relative to its current value, saturating at
+/// `255`, and returns the new verbosity.
+///
+/// # Usage
 ///
-/// If the variable is not set or cannot be parsed as a number,
-/// it defaults to `0`.
+/// - `inc_verbosity!();` raises the verbosity by `1`.
+/// - `inc_verbosity!(2);` raises it by `2`.
 ///
 /// # Example
 /// ```rust
-/// use verbosio::verbose_env;
-/// unsafe {std::env::set_var("VERBOSE", "2");}
-/// verbose_env!(); // sets verbosity to 2
+/// use verbosio::{dec_verbosity, inc_verbosity, set_verbosity};
+///
+/// set_verbosity!(1);
+/// assert_eq!(inc_verbosity!(), 2);   // e.g. entering a detailed mode
+/// assert_eq!(dec_verbosity!(5), 0);  // saturates at 0
 /// ```
 #[macro_export]
-macro_rules! verbose_env {
+macro_rules! inc_verbosity {
     () => {
-        let val = std::env::var("VERBOSE").unwrap_or_default();
-        $crate::set_verbosity!(val.parse::<u8>().unwrap_or(0));
+        $crate::inc_verbosity!(1)
     };
+    ($by:expr) => {{
+        let by: u8 = $by;
+        let previous = $crate::VERBOSE
+            .fetch_update(std::sync::atomic::Ordering::Relaxed, std::sync::atomic::Ordering::Relaxed, |verbosity| {
+                Some(verbosity.saturating_add(by))
+            })
+            .unwrap_or_else(|verbosity| verbosity);
+        // bound first, so using the macro as a statement doesn't warn
+        let verbosity = previous.saturating_add(by);
+        verbosity
+    }};
 }
 
-/// Prints a message to stdout if the verbosity is high enough.
+/// Lowers the global verbosity relative to its current value, saturating at
+/// `0`, and returns the new verbosity. See [`inc_verbosity!`](crate::inc_verbosity).
+///
+/// # Usage
+///
+/// - `dec_verbosity!();` lowers the verbosity by `1`.
+/// - `dec_verbosity!(2);` lowers it by `2`.
+#[macro_export]
+macro_rules! dec_verbosity {
+    () => {
+        $crate::dec_verbosity!(1)
+    };
+    ($by:expr) => {{
+        let by: u8 = $by;
+        let previous = $crate::VERBOSE
+            .fetch_update(std::sync::atomic::Ordering::Relaxed, std::sync::atomic::Ordering::Relaxed, |verbosity| {
+                Some(verbosity.saturating_sub(by))
+            })
+            .unwrap_or_else(|verbosity| verbosity);
+        // bound first, so using the macro as a statement doesn't warn
+        let verbosity = previous.saturating_sub(by);
+        verbosity
+    }};
+}
+
+/// Changes the global verbosity while a block runs.
+///
+/// The previous verbosity is restored when the block ends, also on an early
+/// return or a panic. Without a block, the
+/// [`VerbosityGuard`](crate::scope::VerbosityGuard) is returned instead and
+/// the verbosity is restored when it is dropped. Like `set_verbosity!`, it
+/// takes a number, a [`Verbosity`](crate::levels::Verbosity) or its name as
+/// a string literal.
+/// See the [`scope`](crate::scope) module for how it interacts with spinners.
 ///
 /// # Syntax
 ///
-/// - `verbose!(@lvl 1, "Message: {}", value);` → prints if verbosity ≥ 1
-/// - `verbose!("Message");`               → prints if verbosity ≥ 1 (shorthand)
+/// - `with_verbosity!(3, { ... })` → evaluates to the block's value
+/// - `let _guard = with_verbosity!("debug");` → applies as long as `_guard` lives
 ///
 /// # Example
 /// ```rust
-/// use verbosio::{set_verbosity, verbose};
+/// use verbosio::{get_verbosity, set_verbosity, vinfo, with_verbosity};
 ///
 /// set_verbosity!(2);
-/// verbose!(@lvl 1, "This shows");    // printed
-/// verbose!(@lvl 2, "This will show {}", "too"); // printed
-/// verbose!("And so will {}", "this"); // printed
-/// verbose!(@lvl 3, "This won't");    // not printed
+/// let count = with_verbosity!(0, {
+///     vinfo!("not printed");
+///     7
+/// });
+/// assert_eq!(count, 7);
+/// assert_eq!(get_verbosity!(), 2);
 /// ```
 #[macro_export]
-macro_rules! verbose {
-    (@lvl $lvl:expr, $($arg:tt)+) => {
-        if $crate::VERBOSE.load(std::sync::atomic::Ordering::Relaxed) >= $lvl {
-            let msg = format!("{}{}", $crate::format_time(), format!($($arg)+));
-            println!("{}", msg);
-        }
+macro_rules! with_verbosity {
+    ($verbosity:literal, $body:block) => {{
+        let __verbosio_verbosity = $crate::with_verbosity!($verbosity);
+        $body
+    }};
+    ($verbosity:expr, $body:block) => {{
+        let __verbosio_verbosity = $crate::with_verbosity!($verbosity);
+        $body
+    }};
+    ($verbosity:literal $(,)?) => {
+        $crate::scope::push_verbosity($crate::__verbosity!($verbosity))
     };
-    ($( $arg:tt )+) => {
-        if $crate::VERBOSE.load(std::sync::atomic::Ordering::Relaxed) >= 1 {
-            let msg = format!("{}{}", $crate::format_time(), format!($($arg)+));
-            println!("{}", msg);
-        }
+    ($verbosity:expr $(,)?) => {
+        $crate::scope::push_verbosity($crate::__verbosity!($verbosity))
     };
 }
 
-/// Prints an `[INFO]` message to stdout if the verbosity is high enough.
+/// Changes the verbosity of the current thread only while a block runs.
+///
+/// Messages logged on this thread are checked against this verbosity
+/// instead of the global one; other threads are not affected. Without a
+/// block, the [`ThreadVerbosityGuard`](crate::scope::ThreadVerbosityGuard)
+/// is returned instead. Takes the same values as
+/// [`with_verbosity!`](crate::with_verbosity).
 ///
 /// # Syntax
 ///
-/// - `vinfo!(@lvl 2, "Loaded {} items", count);` → prints if verbosity ≥ 2
-/// - `vinfo!("Starting...");`               → prints if verbosity ≥ 1 (default)
+/// - `with_thread_verbosity!(3, { ... })` → evaluates to the block's value
+/// - `let _guard = with_thread_verbosity!("trace");` → applies as long as `_guard` lives
 ///
 /// # Example
 /// ```rust
-/// use verbosio::{set_verbosity, vinfo};
+/// use verbosio::{set_verbosity, vinfo, with_thread_verbosity};
 ///
-/// set_verbosity!(2);
-/// vinfo!("App started");       // printed
-/// vinfo!(@lvl 3, "Details...");     // not printed
+/// set_verbosity!(1);
+/// let workers: Vec<_> = (0..4).map(|id| std::thread::spawn(move || {
+///     let _guard = (id == 2).then(|| with_thread_verbosity!("debug"));
+///     vinfo!(@lvl 3, "worker {} polling", id); // only printed by worker 2
+/// })).collect();
+/// for worker in workers {
+///     worker.join().unwrap();
+/// }
 /// ```
+#[macro_export]
+macro_rules! with_thread_verbosity {
+    ($verbosity:literal, $body:block) => {{
+        let __verbosio_thread_verbosity = $crate::with_thread_verbosity!($verbosity);
+        $body
+    }};
+    ($verbosity:expr, $body:block) => {{
+        let __verbosio_thread_verbosity = $crate::with_thread_verbosity!($verbosity);
+        $body
+    }};
+    ($verbosity:literal $(,)?) => {
+        $crate::scope::push_thread_verbosity($crate::__verbosity!($verbosity))
+    };
+    ($verbosity:expr $(,)?) => {
+        $crate::scope::push_thread_verbosity($crate::__verbosity!($verbosity))
+    };
+}
+
+/// Returns whether messages at the given level would currently be printed.
 ///
-/// # Output Format
-/// Outputs messages like `[INFO] your message...`
+/// Performs only the verbosity check the logging macros do, so expensive
+/// diagnostics can be skipped entirely instead of just their formatting.
+/// Like the logging macros, it honors per-target directives of the
+/// [`filter`](crate::filter) for the calling module.
 ///
-/// # Features
-/// If the `"colors"` feature is enabled, the `[INFO]` tag may appear colored.
+/// # Syntax
+///
+/// - `is_enabled!(@lvl 3)` → `true` if verbosity ≥ 3
+/// - `is_enabled!()`       → `true` if verbosity ≥ 1
+/// - `is_enabled!(@lvl 3, target: "net")` → checks the named target `net` instead of the calling module
+///
+/// # Example
+/// ```rust
+/// use verbosio::{is_enabled, set_verbosity, verbose};
+///
+/// set_verbosity!(2);
+/// assert!(is_enabled!());
+/// if is_enabled!(@lvl 3) {
+///     let stats = std::fs::read_dir(".").unwrap().count(); // expensive
+///     verbose!(@lvl 3, "{} entries", stats);
+/// }
+/// ```
 #[macro_export]
-macro_rules! vinfo {
-    (@lvl $lvl:expr, $($arg:tt)+) => {
-        if $crate::VERBOSE.load(std::sync::atomic::Ordering::Relaxed) >= $lvl {
-            println!("{}{}{}", $crate::format_level("INFO"), $crate::format_time(), format!($($arg)+));
-        }
+macro_rules! is_enabled {
+    (@lvl $lvl:expr, target: $target:expr) => {
+        $crate::filter::enabled($target, $lvl)
     };
-    ($( $arg:tt )+) => {
-        if $crate::VERBOSE.load(std::sync::atomic::Ordering::Relaxed) >= 1 {
-            println!("{}{}{}", $crate::format_level("INFO"), $crate::format_time(), format!($($arg)+));
-        }
+    (@lvl $lvl:expr) => {
+        $crate::filter::enabled(module_path!(), $lvl)
+    };
+    () => {
+        $crate::is_enabled!(@lvl 1)
     };
 }
 
-/// Prints a `[WARN]` message to stdout if the verbosity is high enough.
+/// Validates a line format template at compile time and returns it.
 ///
-/// # Syntax
+/// See [`template`](crate::template) for the available placeholders. An
+/// invalid template fails the build.
 ///
-/// - `vwarn!(@lvl 2, "Low memory");`      → prints if verbosity ≥ 2
-/// - `vwarn!("Disk almost full");`   → prints if verbosity ≥ 1
+/// # Example
+/// ```rust
+/// use verbosio::format_template;
 ///
-/// # Output Format
-/// Outputs messages like `[WARN] your message...`
+/// const LAYOUT: &str = format_template!("{time} {level:>7} {target} {msg}");
+/// ```
+///
+/// A typo is reported by the compiler:
+/// ```rust,compile_fail
+/// let layout = verbosio::format_template!("{time} {lvl} {msg}");
+/// ```
+#[macro_export]
+macro_rules! format_template {
+    ($template:literal) => {{
+        const TEMPLATE: &str = $template;
+        const _: () = $crate::template::validate(TEMPLATE);
+        TEMPLATE
+    }};
+}
+
+/// Prints all terminal output in the layout of a line format template.
+///
+/// The template is validated at compile time, see
+/// [`template`](crate::template) for the placeholders. Section headers keep
+/// their `=== title ===` form. To use a template for the log file, pass a
+/// [`Template`](crate::template::Template) to
+/// [`set_file_format`](crate::sink::set_file_format).
+///
+/// # Usage
+///
+/// - `set_format!("{time} {level:>7} {target} {msg}");`
+/// - `set_format!();` → back to the default layout of the output mode
 ///
 /// # Example
 /// ```rust
-/// use verbosio::{set_verbosity, vwarn};
+/// use verbosio::{set_format, set_verbosity, vwarn};
 ///
 /// set_verbosity!(1);
-/// vwarn!("Something might be wrong");
+/// set_format!("{level:<5} {target}: {msg}");
+/// vwarn!("disk almost full"); // WARN  rust_out: disk almost full
+/// set_format!();
 /// ```
-///
-/// # Features
-/// With `"colors"` feature enabled, the `[WARN]` tag may be yellow.
 #[macro_export]
-macro_rules! vwarn {
-    (@lvl $lvl:expr, $($arg:tt)+) => {
-        if $crate::VERBOSE.load(std::sync::atomic::Ordering::Relaxed) >= $lvl {
-            println!("{}{}{}", $crate::format_level("WARN"), $crate::format_time(), format!($($arg)+));
-        }
+macro_rules! set_format {
+    () => {
+        $crate::sink::reset_terminal_format()
     };
-    ($( $arg:tt )+) => {
-        if $crate::VERBOSE.load(std::sync::atomic::Ordering::Relaxed) >= 1 {
-            println!("{}{}{}", $crate::format_level("WARN"), $crate::format_time(), format!($($arg)+));
-        }
+    ($template:literal) => {
+        $crate::sink::set_terminal_format($crate::format::Format::Template(
+            $crate::template::Template::parse($crate::format_template!($template))
+                .expect("template validated at compile time"),
+        ))
     };
 }
 
-/// Prints an `[ERROR]` message to stderr if the verbosity is high enough.
+/// Returns the short random ID of the current run as `&'static str`.
 ///
-/// # Syntax
+/// # Example
+/// ```rust
+/// use verbosio::run_id;
 ///
-/// - `verror!(@lvl 3, "Critical: {}", reason);` → prints if verbosity ≥ 3
-/// - `verror!("Oops");`                    → prints if verbosity ≥ 1
+/// println!("run {}", run_id!());
+/// ```
+#[macro_export]
+macro_rules! run_id {
+    () => {
+        $crate::run::run_id()
+    };
+}
+
+/// Returns the ID of the innermost open section as `Option<u64>`.
 ///
-/// # Output Format
-/// Messages appear as `[ERROR] ...` and are printed to `stderr`.
+/// See [`span`](crate::span) for how section IDs are assigned.
 ///
 /// # Example
 /// ```rust
-/// use verbosio::{set_verbosity, verror};
+/// use verbosio::{current_span_id, set_verbosity, vsection};
 ///
-/// set_verbosity!(2);
-/// verror!("An error occurred"); // printed
-/// verror!(@lvl 3, "Only for debug"); // not printed
+/// set_verbosity!(1);
+/// vsection!("Build");
+/// assert!(current_span_id!().is_some());
 /// ```
+#[macro_export]
+macro_rules! current_span_id {
+    () => {
+        $crate::span::current_span_id()
+    };
+}
+
+/// Sets verbosity level based on the `VERBOSE` environment variable.
 ///
-/// # Features
-/// If the `"colors"` feature is enabled, the `[ERROR]` tag may be red.
+/// # Usage
+///
+/// - `verbose_env!();` reads `VERBOSE`, unless `QUIET` is set (see
+///   [`quiet_from_env`](crate::env::quiet_from_env)), which switches to
+///   [quiet mode](crate::set_quiet). The color mode, format and log file
+///   are read from `COLOR`, `LOG_FORMAT` and `LOG_FILE` as well, see
+///   [`init_from_env`](crate::env::init_from_env).
+/// - `verbose_env!("MYTOOL_VERBOSE");` reads a custom variable instead.
+/// - `verbose_env!(["MYTOOL_VERBOSE", "VERBOSE"]);` reads the first variable
+///   that is set, see [`verbosity_from_env_chain`](crate::env::verbosity_from_env_chain).
+/// - `let source = verbose_env!(cli: flag, default: 1);` takes the verbosity
+///   from the command line value `flag` (an `Option<u8>`), `QUIET`,
+///   `VERBOSE`, `RUST_LOG` or the default, whichever comes first, and returns
+///   the [`VerbositySource`](crate::env::VerbositySource) used, see
+///   [`init_layered`](crate::env::init_layered). `default` may be left out
+///   and is `0` then.
+/// - `verbose_env!(prefix: "MYTOOL");` reads `MYTOOL_VERBOSE`, `MYTOOL_QUIET`,
+///   and also the color mode, format and log file from `MYTOOL_COLOR`,
+///   `MYTOOL_LOG_FORMAT` and `MYTOOL_LOG_FILE`, see
+///   [`init_with_prefix`](crate::env::init_with_prefix).
+///
+/// `QUIET` overrides the variables in every form; with `cli:`, a command
+/// line value still takes precedence over it.
+///
+/// Accepts numbers as well as names like `debug`, `trace`, `quiet` or
+/// `true`, see [`env`](crate::env) for the full list; `quiet` switches to
+/// quiet mode. If the variable is not set or cannot be parsed, it defaults
+/// to `0`; an unparseable value is reported with a `[DEBUG]` message in
+/// debug builds.
+///
+/// # Example
+/// ```rust
+/// use verbosio::{get_verbosity, verbose_env};
+/// unsafe {std::env::set_var("VERBOSE", "2");}
+/// verbose_env!(); // sets verbosity to 2
+///
+/// unsafe {std::env::set_var("VERBOSE", "trace");}
+/// verbose_env!();
+/// assert_eq!(get_verbosity!(), 4);
+///
+/// unsafe {std::env::set_var("MYTOOL_VERBOSE", "quiet");}
+/// verbose_env!(["MYTOOL_VERBOSE", "VERBOSE"]);
+/// assert_eq!(get_verbosity!(), 0);
+/// assert!(verbosio::filter::is_quiet()); // errors still print
+/// # unsafe {std::env::remove_var("MYTOOL_VERBOSE");}
+///
+/// let flag: Option<u8> = None; // e.g. from an argument parser
+/// let source = verbose_env!(cli: flag, default: "normal");
+/// assert_eq!(source, verbosio::env::VerbositySource::Verbose);
+/// ```
 #[macro_export]
-macro_rules! verror {
-    (@lvl $lvl:expr, $($arg:tt)+) => {
-        if $crate::VERBOSE.load(std::sync::atomic::Ordering::Relaxed) >= $lvl {
-            eprintln!("{}{}{}", $crate::format_level("ERROR"), $crate::format_time(), format!($($arg)+));
-        }
+macro_rules! verbose_env {
+    () => {
+        $crate::env::init_from_env()
     };
-    ($( $arg:tt )+) => {
-        if $crate::VERBOSE.load(std::sync::atomic::Ordering::Relaxed) >= 1 {
-            eprintln!("{}{}{}", $crate::format_level("ERROR"), $crate::format_time(), format!($($arg)+));
-        }
+    (cli: $cli:expr $(,)?) => {
+        $crate::verbose_env!(cli: $cli, default: 0)
+    };
+    (cli: $cli:expr, default: $default:literal $(,)?) => {
+        $crate::env::init_layered($cli, $crate::__verbosity!($default))
+    };
+    (cli: $cli:expr, default: $default:expr $(,)?) => {
+        $crate::env::init_layered($cli, $crate::__verbosity!($default))
+    };
+    (prefix: $prefix:expr) => {
+        $crate::env::init_with_prefix($prefix)
+    };
+    ([$($name:expr),+ $(,)?]) => {
+        $crate::env::init_from_env_chain(&[$($name),+])
+    };
+    ($name:expr) => {
+        $crate::env::init_from_env_chain(&[$name])
     };
 }
 
-
-/// Prints a `[DEBUG]` message to stdout if in debug mode.
+/// Applies a TOML config file, see [`config`](crate::config) for its keys.
 ///
-/// # Syntax
+/// Returns the loaded [`Config`](crate::config::Config), or a
+/// [`ConfigError`](crate::config::ConfigError) if the file can't be read,
+/// isn't valid or names a log file that can't be opened.
 ///
-/// - `vebug!(@lvl 3, "Critical: {}", reason);` → prints if verbosity ≥ 3 and in debug mode
-/// - `vebug!("Oops");`                    → prints if in debug mode
+/// # Usage
 ///
-/// # Output Format
-/// Messages appear as `[DEBUG] ...` and are printed to `stdout`.
+/// - `init_from_file!("verbosio.toml")?;`
 ///
 /// # Example
 /// ```rust
-/// use verbosio::{set_verbosity, vebug};
+/// use verbosio::{get_verbosity, init_from_file};
 ///
-/// vebug!("Always in debug mode!"); // printed
-/// vebug!(@lvl 3, "Only for debug when level is high enough"); // not printed
+/// let path = std::env::temp_dir().join("verbosio-config-doc.toml");
+/// std::fs::write(&path, "verbosity = 2\ntime = \"off\"\n").unwrap();
+/// init_from_file!(&path).expect("invalid config");
+/// assert_eq!(get_verbosity!(), 2);
 /// ```
 ///
 /// # Features
-/// If the `"colors"` feature is enabled, the `[DEBUG]` tag may be yellow.
+/// Only available if the `"config"` feature is enabled.
+#[cfg(feature = "config")]
 #[macro_export]
-macro_rules! vebug {
-    (@lvl $lvl:expr, $($arg:tt)+) => {
-        #[cfg(debug_assertions)]
-        {
-            if $crate::VERBOSE.load(std::sync::atomic::Ordering::Relaxed) >= $lvl {
-                println!("{}{}{}", $crate::format_level("DEBUG"), $crate::format_time(), format!($($arg)+));
-            }
-        }
+macro_rules! init_from_file {
+    ($path:expr) => {
+        $crate::config::init_from_file($path)
     };
+}
+
+/// Sets the verbosity from the `-v`/`-q` flags of the command line.
+///
+/// Scans `std::env::args_os()` without an argument parser, so tiny tools get
+/// the usual behavior for free: `-v`, `-vv`, `-vvv` (or repeated `-v`) add
+/// one level each, `--verbose=N` sets `N`, and `-q`/`--quiet` switches to
+/// [quiet mode](crate::set_quiet), where only errors print. See
+/// [`verbosity_from_args`](crate::env::verbosity_from_args). Other arguments
+/// are left alone for the program to handle, and those that aren't valid
+/// UTF-8 are skipped instead of panicking.
+///
+/// # Usage
+///
+/// - `verbose_args!();` → leaves the verbosity unchanged if no flag is given
+/// - `verbose_args!(1);` → uses `1` if no flag is given
+///
+/// # Example
+/// ```rust
+/// use verbosio::{verbose_args, vinfo};
+///
+/// verbose_args!(1); // `script -vv file.txt` sets verbosity 2
+/// vinfo!("processing"); // printed unless run with -q
+/// ```
+#[macro_export]
+macro_rules! verbose_args {
+    () => {
+        match $crate::env::verbosity_from_args(std::env::args_os().skip(1).filter_map(|arg| arg.into_string().ok())) {
+            Some($crate::levels::QUIET) => $crate::set_quiet!(),
+            Some(verbosity) => {
+                $crate::set_verbosity!(verbosity);
+            }
+            None => {}
+        }
+    };
+    ($default:expr) => {
+        match $crate::env::verbosity_from_args(std::env::args_os().skip(1).filter_map(|arg| arg.into_string().ok())) {
+            Some($crate::levels::QUIET) => $crate::set_quiet!(),
+            Some(verbosity) => {
+                $crate::set_verbosity!(verbosity);
+            }
+            None => {
+                $crate::set_verbosity!($default);
+            }
+        }
+    };
+}
+
+/// Rewrites the positional level syntax (`vinfo!(2, ...)`) to `@lvl`, and
+/// anything else to the macro's default form given in brackets.
+///
+/// Only integer literals `0` to `9` and plain identifiers are recognized as
+/// positional levels, since those can never start a format string.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __positional_level {
+    ($mac:ident, [$($default:tt)*], $lvl:ident, $($arg:tt)+) => { $crate::$mac!(@lvl $lvl, $($arg)+) };
+    ($mac:ident, [$($default:tt)*], 0, $($arg:tt)+) => { $crate::$mac!(@lvl 0, $($arg)+) };
+    ($mac:ident, [$($default:tt)*], 1, $($arg:tt)+) => { $crate::$mac!(@lvl 1, $($arg)+) };
+    ($mac:ident, [$($default:tt)*], 2, $($arg:tt)+) => { $crate::$mac!(@lvl 2, $($arg)+) };
+    ($mac:ident, [$($default:tt)*], 3, $($arg:tt)+) => { $crate::$mac!(@lvl 3, $($arg)+) };
+    ($mac:ident, [$($default:tt)*], 4, $($arg:tt)+) => { $crate::$mac!(@lvl 4, $($arg)+) };
+    ($mac:ident, [$($default:tt)*], 5, $($arg:tt)+) => { $crate::$mac!(@lvl 5, $($arg)+) };
+    ($mac:ident, [$($default:tt)*], 6, $($arg:tt)+) => { $crate::$mac!(@lvl 6, $($arg)+) };
+    ($mac:ident, [$($default:tt)*], 7, $($arg:tt)+) => { $crate::$mac!(@lvl 7, $($arg)+) };
+    ($mac:ident, [$($default:tt)*], 8, $($arg:tt)+) => { $crate::$mac!(@lvl 8, $($arg)+) };
+    ($mac:ident, [$($default:tt)*], 9, $($arg:tt)+) => { $crate::$mac!(@lvl 9, $($arg)+) };
+    ($mac:ident, [$($default:tt)*], $($arg:tt)+) => { $crate::$mac!($($default)* $($arg)+) };
+}
+
+/// Prints a message to stdout if the verbosity is high enough.
+///
+/// # Syntax
+///
+/// - `verbose!(@lvl 1, "Message: {}", value);` → prints if verbosity ≥ 1
+/// - `verbose!("Message");`               → prints if verbosity ≥ 1 (shorthand)
+/// - `verbose!(2, "Message");`            → positional level, see [Compatibility](crate#compatibility)
+/// - `verbose!(target: "net", "Message");` → with a [named target](crate#named-targets)
+///
+/// # Example
+/// ```rust
+/// use verbosio::{set_verbosity, verbose};
+///
+/// set_verbosity!(2);
+/// verbose!(@lvl 1, "This shows");    // printed
+/// verbose!(@lvl 2, "This will show {}", "too"); // printed
+/// verbose!("And so will {}", "this"); // printed
+/// verbose!(@lvl 3, "This won't");    // not printed
+/// ```
+#[macro_export]
+macro_rules! verbose {
+    (@lvl $lvl:expr, target: $target:expr, $($arg:tt)+) => {
+        if $crate::is_enabled!(@lvl $lvl, target: $target) {
+            $crate::sink::emit_named(None, $target, format_args!($($arg)+), Vec::new());
+        }
+    };
+    (@lvl $lvl:expr, $($arg:tt)+) => {
+        if $crate::is_enabled!(@lvl $lvl) {
+            $crate::sink::emit(None, module_path!(), format_args!($($arg)+));
+        }
+    };
+    ($( $arg:tt )+) => {
+        $crate::__positional_level!(verbose, [@lvl 1,], $($arg)+)
+    };
+}
+
+/// Prints a message to stderr if the verbosity is high enough.
+///
+/// Works like `verbose!`, but keeps stdout free for the program's actual
+/// output, e.g. when it is piped into another tool.
+///
+/// # Syntax
+///
+/// - `verbose_err!(@lvl 2, "Read {} rows", n);` → prints if verbosity ≥ 2
+/// - `verbose_err!("Reading input");`          → prints if verbosity ≥ 1 (shorthand)
+///
+/// # Example
+/// ```rust
+/// use verbosio::{set_verbosity, verbose_err};
+///
+/// set_verbosity!(1);
+/// verbose_err!("Reading input");          // printed to stderr
+/// verbose_err!(@lvl 2, "Parsed header");  // not printed
+/// println!("id,name");                    // data stays alone on stdout
+/// ```
+#[macro_export]
+macro_rules! verbose_err {
+    (@lvl $lvl:expr, $($arg:tt)+) => {
+        if $crate::is_enabled!(@lvl $lvl) {
+            $crate::sink::emit_stderr(module_path!(), format_args!($($arg)+));
+        }
+    };
+    ($( $arg:tt )+) => {
+        if $crate::is_enabled!(@lvl 1) {
+            $crate::sink::emit_stderr(module_path!(), format_args!($($arg)+));
+        }
+    };
+}
+
+/// Short alias for [`verbose_err!`], named after `eprint!`.
+///
+/// # Example
+/// ```rust
+/// use verbosio::{set_verbosity, veprint};
+///
+/// set_verbosity!(2);
+/// veprint!(@lvl 2, "Progress: {}%", 40); // printed to stderr
+/// ```
+#[macro_export]
+macro_rules! veprint {
     ($($arg:tt)+) => {
+        $crate::verbose_err!($($arg)+)
+    };
+}
+
+/// Prints an `[INFO]` message to stdout if the verbosity is high enough.
+///
+/// # Syntax
+///
+/// - `vinfo!(@lvl 2, "Loaded {} items", count);` → prints if verbosity ≥ 2
+/// - `vinfo!("Starting...");`               → prints if verbosity ≥ 1 (default)
+/// - `vinfo!(2, "Loaded {} items", count);`  → positional level, see [Compatibility](crate#compatibility)
+/// - `vinfo!(target: "net", "Connected");`  → with a [named target](crate#named-targets), printed as `[INFO] net: Connected`
+///
+/// # Example
+/// ```rust
+/// use verbosio::{set_verbosity, vinfo};
+///
+/// set_verbosity!(2);
+/// vinfo!("App started");       // printed
+/// vinfo!(@lvl 3, "Details...");     // not printed
+/// ```
+///
+/// # Output Format
+/// Outputs messages like `[INFO] your message...`
+///
+/// # Features
+/// If the `"colors"` feature is enabled, the `[INFO]` tag may appear colored.
+#[macro_export]
+macro_rules! vinfo {
+    (@lvl $lvl:expr, target: $target:expr, $($arg:tt)+) => {
+        if $crate::filter::level_enabled($target, Some($crate::Level::Info), $lvl) {
+            $crate::sink::emit_named(Some($crate::Level::Info), $target, format_args!($($arg)+), Vec::new());
+        }
+    };
+    (@lvl $lvl:expr, $($arg:tt)+) => {
+        if $crate::filter::level_enabled(module_path!(), Some($crate::Level::Info), $lvl) {
+            $crate::sink::emit(Some($crate::Level::Info), module_path!(), format_args!($($arg)+));
+        }
+    };
+    ($( $arg:tt )+) => {
+        $crate::__positional_level!(vinfo, [@lvl 1,], $($arg)+)
+    };
+}
+
+/// Prints a `[WARN]` message to stdout if the verbosity is high enough.
+///
+/// # Syntax
+///
+/// - `vwarn!(@lvl 2, "Low memory");`      → prints if verbosity ≥ 2
+/// - `vwarn!("Disk almost full");`   → prints if verbosity ≥ 1
+/// - `vwarn!(2, "Low memory");`      → positional level, see [Compatibility](crate#compatibility)
+/// - `vwarn!(target: "net", "Slow response");` → with a [named target](crate#named-targets)
+/// - `vwarn!("unknown key {}", k; help: "did you mean {}?", s);` → with follow-up lines
+///
+/// # Output Format
+/// Outputs messages like `[WARN] your message...`
+///
+/// `help:` and `note:` lines, separated from the message and from each
+/// other by `;`, are printed indented below it, like compiler diagnostics:
+/// ```text
+/// [WARN] unknown key `colour`
+///    = help: did you mean `color`?
+/// ```
+///
+/// # Example
+/// ```rust
+/// use verbosio::{set_verbosity, vwarn};
+///
+/// set_verbosity!(1);
+/// vwarn!("Something might be wrong");
+/// vwarn!("unknown key `{}`", "colour"; help: "did you mean `{}`?", "color"; note: "keys are case-sensitive");
+/// ```
+///
+/// # Features
+/// With `"colors"` feature enabled, the `[WARN]` tag may be yellow.
+#[macro_export]
+macro_rules! vwarn {
+    (@lvl $lvl:expr, target: $target:expr, $fmt:literal $(, $arg:expr)* ; $($notes:tt)+) => {
+        if $crate::filter::level_enabled($target, Some($crate::Level::Warn), $lvl) {
+            $crate::sink::emit_named(
+                Some($crate::Level::Warn),
+                $target,
+                format_args!($fmt $(, $arg)*),
+                $crate::__notes!([] $($notes)+),
+            );
+        }
+    };
+    (@lvl $lvl:expr, target: $target:expr, $($arg:tt)+) => {
+        if $crate::filter::level_enabled($target, Some($crate::Level::Warn), $lvl) {
+            $crate::sink::emit_named(Some($crate::Level::Warn), $target, format_args!($($arg)+), Vec::new());
+        }
+    };
+    (@lvl $lvl:expr, $fmt:literal $(, $arg:expr)* ; $($notes:tt)+) => {
+        if $crate::filter::level_enabled(module_path!(), Some($crate::Level::Warn), $lvl) {
+            $crate::sink::emit_with_notes(
+                Some($crate::Level::Warn),
+                module_path!(),
+                format_args!($fmt $(, $arg)*),
+                $crate::__notes!([] $($notes)+),
+            );
+        }
+    };
+    (@lvl $lvl:expr, $($arg:tt)+) => {
+        if $crate::filter::level_enabled(module_path!(), Some($crate::Level::Warn), $lvl) {
+            $crate::sink::emit(Some($crate::Level::Warn), module_path!(), format_args!($($arg)+));
+        }
+    };
+    ($( $arg:tt )+) => {
+        $crate::__positional_level!(vwarn, [@lvl 1,], $($arg)+)
+    };
+}
+
+/// Prints a message with a source snippet underlining where it applies.
+///
+/// The first argument is the level (`Error`, `Warn`, `Info` or `Debug`), the
+/// second a [`Snippet`](crate::snippet::Snippet) pointing into the source
+/// text. `help:`/`note:` lines can follow as with [`vwarn!`]. With
+/// [`set_editor_diagnostics`](crate::snippet::set_editor_diagnostics), a
+/// `file:line:col: error: message` line is written to stderr as well.
+///
+/// # Syntax
+///
+/// - `vdiagnostic!(@lvl 2, Warn, snippet, "unused key `{}`", key);` → prints if verbosity ≥ 2
+/// - `vdiagnostic!(Error, snippet, "expected a value"; help: "remove the second `=`");` → prints if verbosity ≥ 1
+///
+/// # Output Format
+/// ```text
+/// [ERROR] expected a value
+///   --> config.toml:2:8
+///    |
+///  2 | name = = "demo"
+///    |        ^ unexpected `=`
+///    = help: remove the second `=`
+/// ```
+///
+/// # Example
+/// ```rust
+/// use verbosio::snippet::Snippet;
+/// use verbosio::{set_verbosity, vdiagnostic};
+///
+/// set_verbosity!(1);
+/// let source = "[package]\nname = = \"demo\"\n";
+/// let snippet = Snippet::new("config.toml", source, 17..18).label("unexpected `=`");
+/// vdiagnostic!(Error, snippet, "expected a value"; help: "remove the second `=`");
+/// ```
+#[macro_export]
+macro_rules! vdiagnostic {
+    (@lvl $lvl:expr, $level:ident, $snippet:expr, $fmt:literal $(, $arg:expr)* ; $($notes:tt)+) => {
+        if $crate::filter::level_enabled(module_path!(), Some($crate::Level::$level), $lvl) {
+            $crate::snippet::emit(
+                $crate::Level::$level,
+                module_path!(),
+                &$snippet,
+                format!($fmt $(, $arg)*),
+                $crate::__notes!([] $($notes)+),
+            );
+        }
+    };
+    (@lvl $lvl:expr, $level:ident, $snippet:expr, $($arg:tt)+) => {
+        if $crate::filter::level_enabled(module_path!(), Some($crate::Level::$level), $lvl) {
+            $crate::snippet::emit($crate::Level::$level, module_path!(), &$snippet, format!($($arg)+), Vec::new());
+        }
+    };
+    ($level:ident, $snippet:expr, $($arg:tt)+) => {
+        $crate::vdiagnostic!(@lvl 1, $level, $snippet, $($arg)+)
+    };
+}
+
+/// Builds the `help:`/`note:` lines of `vwarn!`/`verror!`.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __notes {
+    ([$($done:expr),*]) => {
+        vec![$($done),*]
+    };
+    ([$($done:expr),*] help: $fmt:literal $(, $arg:expr)* $(; $($rest:tt)*)?) => {
+        $crate::__notes!([$($done,)* ($crate::record::NoteKind::Help, format!($fmt $(, $arg)*))] $($($rest)*)?)
+    };
+    ([$($done:expr),*] note: $fmt:literal $(, $arg:expr)* $(; $($rest:tt)*)?) => {
+        $crate::__notes!([$($done,)* ($crate::record::NoteKind::Note, format!($fmt $(, $arg)*))] $($($rest)*)?)
+    };
+}
+
+/// Prints an `[ERROR]` message to stderr if the verbosity is high enough.
+///
+/// # Syntax
+///
+/// - `verror!(@lvl 3, "Critical: {}", reason);` → prints if verbosity ≥ 3
+/// - `verror!("Oops");`                    → prints if verbosity ≥ 1
+/// - `verror!(3, "Critical: {}", reason);`  → positional level, see [Compatibility](crate#compatibility)
+/// - `verror!(target: "net", "Connection lost");` → with a [named target](crate#named-targets)
+/// - `verror!("cannot open {}", path; note: "required by {}", dep);` → with follow-up lines, see [`vwarn!`]
+///
+/// # Output Format
+/// Messages appear as `[ERROR] ...` and are printed to `stderr`.
+///
+/// # Example
+/// ```rust
+/// use verbosio::{set_verbosity, verror};
+///
+/// set_verbosity!(2);
+/// verror!("An error occurred"); // printed
+/// verror!(@lvl 3, "Only for debug"); // not printed
+/// ```
+///
+/// # Features
+/// If the `"colors"` feature is enabled, the `[ERROR]` tag may be red.
+#[macro_export]
+macro_rules! verror {
+    (@lvl $lvl:expr, target: $target:expr, $fmt:literal $(, $arg:expr)* ; $($notes:tt)+) => {
+        if $crate::filter::level_enabled($target, Some($crate::Level::Error), $lvl) {
+            $crate::sink::emit_named(
+                Some($crate::Level::Error),
+                $target,
+                format_args!($fmt $(, $arg)*),
+                $crate::__notes!([] $($notes)+),
+            );
+        }
+    };
+    (@lvl $lvl:expr, target: $target:expr, $($arg:tt)+) => {
+        if $crate::filter::level_enabled($target, Some($crate::Level::Error), $lvl) {
+            $crate::sink::emit_named(Some($crate::Level::Error), $target, format_args!($($arg)+), Vec::new());
+        }
+    };
+    (@lvl $lvl:expr, $fmt:literal $(, $arg:expr)* ; $($notes:tt)+) => {
+        if $crate::filter::level_enabled(module_path!(), Some($crate::Level::Error), $lvl) {
+            $crate::sink::emit_with_notes(
+                Some($crate::Level::Error),
+                module_path!(),
+                format_args!($fmt $(, $arg)*),
+                $crate::__notes!([] $($notes)+),
+            );
+        }
+    };
+    (@lvl $lvl:expr, $($arg:tt)+) => {
+        if $crate::filter::level_enabled(module_path!(), Some($crate::Level::Error), $lvl) {
+            $crate::sink::emit(Some($crate::Level::Error), module_path!(), format_args!($($arg)+));
+        }
+    };
+    ($( $arg:tt )+) => {
+        $crate::__positional_level!(verror, [@lvl 1,], $($arg)+)
+    };
+}
+
+/// Prints a terse `[ERROR]` line from a context where regular logging is
+/// unsafe, such as a signal handler or a panic hook running out of memory.
+///
+/// The message must be a string literal; it can be followed by one integer,
+/// e.g. a signal number or an exit code. The line is assembled on the stack
+/// and written with a single `write(2)` to `stderr`, without allocating or
+/// locking. Colors, timestamps, log files and layers are skipped, see
+/// [`signal_safe`](crate::signal_safe).
+///
+/// # Syntax
+///
+/// - `verror_signal_safe!(@lvl 2, "interrupted");` → prints if verbosity ≥ 2
+/// - `verror_signal_safe!("interrupted");` → prints if verbosity ≥ 1
+/// - `verror_signal_safe!("killed by signal", signo);` → `[ERROR] killed by signal 15`
+///
+/// # Example
+/// ```rust
+/// use verbosio::{set_verbosity, verror_signal_safe};
+///
+/// set_verbosity!(1);
+/// extern "C" fn on_signal(signo: i32) {
+///     verror_signal_safe!("received signal", signo);
+/// }
+/// # on_signal(15);
+/// ```
+#[macro_export]
+macro_rules! verror_signal_safe {
+    (@lvl $lvl:expr, $msg:literal, $number:expr $(,)?) => {
+        $crate::signal_safe::error($lvl, $msg, Some(($number) as i64))
+    };
+    (@lvl $lvl:expr, $msg:literal $(,)?) => {
+        $crate::signal_safe::error($lvl, $msg, None)
+    };
+    ($msg:literal $(, $number:expr)? $(,)?) => {
+        $crate::verror_signal_safe!(@lvl 1, $msg $(, $number)?)
+    };
+}
+
+
+/// Prints a `[DEBUG]` message to stdout if in debug mode.
+///
+/// # Syntax
+///
+/// - `vebug!(@lvl 3, "Critical: {}", reason);` → prints if verbosity ≥ 3 and in debug mode
+/// - `vebug!("Oops");`                    → prints if in debug mode
+/// - `vebug!(3, "Critical: {}", reason);`  → positional level, see [Compatibility](crate#compatibility)
+/// - `vebug!(target: "net", "Oops");`      → with a [named target](crate#named-targets)
+///
+/// # Output Format
+/// Messages appear as `[DEBUG] ...` and are printed to `stdout`.
+///
+/// # Example
+/// ```rust
+/// use verbosio::{set_verbosity, vebug};
+///
+/// vebug!("Always in debug mode!"); // printed
+/// vebug!(@lvl 3, "Only for debug when level is high enough"); // not printed
+/// ```
+///
+/// # Features
+/// If the `"colors"` feature is enabled, the `[DEBUG]` tag may be yellow.
+#[macro_export]
+macro_rules! vebug {
+    (@lvl $lvl:expr, target: $target:expr, $($arg:tt)+) => {
         #[cfg(debug_assertions)]
         {
-            println!("{}{}{}", $crate::format_level("DEBUG"), $crate::format_time(), format!($($arg)+));
+            if $crate::filter::level_enabled($target, Some($crate::Level::Debug), $lvl) {
+                $crate::sink::emit_named(Some($crate::Level::Debug), $target, format_args!($($arg)+), Vec::new());
+            }
         }
     };
+    (@lvl $lvl:expr, $($arg:tt)+) => {
+        #[cfg(debug_assertions)]
+        {
+            if $crate::filter::level_enabled(module_path!(), Some($crate::Level::Debug), $lvl) {
+                $crate::sink::emit(Some($crate::Level::Debug), module_path!(), format_args!($($arg)+));
+            }
+        }
+    };
+    (@any target: $target:expr, $($arg:tt)+) => {
+        #[cfg(debug_assertions)]
+        {
+            // only a level threshold can hide these
+            if $crate::filter::level_enabled($target, Some($crate::Level::Debug), 0) {
+                $crate::sink::emit_named(Some($crate::Level::Debug), $target, format_args!($($arg)+), Vec::new());
+            }
+        }
+    };
+    (@any $($arg:tt)+) => {
+        #[cfg(debug_assertions)]
+        {
+            // only a level threshold can hide these
+            if $crate::filter::level_enabled(module_path!(), Some($crate::Level::Debug), 0) {
+                $crate::sink::emit(Some($crate::Level::Debug), module_path!(), format_args!($($arg)+));
+            }
+        }
+    };
+    ($($arg:tt)+) => {
+        $crate::__positional_level!(vebug, [@any], $($arg)+)
+    };
+}
+
+/// Reports a step that succeeded, marked with a green `✔` instead of a level
+/// tag.
+///
+/// Logged as an `INFO` record, so it is filtered, routed and counted like
+/// `vinfo!`. Structured formats carry `success: true`. Terminals without
+/// Unicode get `ok` instead of the mark.
+///
+/// # Syntax
+///
+/// - `vsuccess!("Built {} crates", 12);` → prints if verbosity ≥ 1
+/// - `vsuccess!(@lvl 2, "Cache hit");`   → prints if verbosity ≥ 2
+///
+/// # Example
+/// ```rust
+/// use verbosio::{set_verbosity, vfail, vsuccess};
+///
+/// set_verbosity!(1);
+/// vsuccess!("Built {} crates", 12); // ✔ Built 12 crates
+/// vfail!("Tests failed");           // ✖ Tests failed
+/// ```
+#[macro_export]
+macro_rules! vsuccess {
+    (@lvl $lvl:expr, $($arg:tt)+) => {
+        if $crate::filter::level_enabled(module_path!(), Some($crate::Level::Info), $lvl) {
+            $crate::sink::emit_step(true, module_path!(), format_args!($($arg)+));
+        }
+    };
+    ($($arg:tt)+) => {
+        $crate::vsuccess!(@lvl 1, $($arg)+)
+    };
+}
+
+/// Reports a step that failed, marked with a red `✖` instead of a level tag.
+///
+/// Logged as an `ERROR` record, so like `verror!` it goes to `stderr`, is
+/// printed in quiet mode and counts towards [`vsummary!`](crate::vsummary).
+/// Structured formats carry `success: false`.
+///
+/// # Syntax
+///
+/// - `vfail!("{} tests failed", 3);` → prints if verbosity ≥ 1
+/// - `vfail!(@lvl 2, "Cache miss");` → prints if verbosity ≥ 2
+///
+/// # Example
+/// ```rust
+/// use verbosio::{set_verbosity, vfail};
+///
+/// set_verbosity!(1);
+/// vfail!("{} tests failed", 3); // ✖ 3 tests failed
+/// ```
+#[macro_export]
+macro_rules! vfail {
+    (@lvl $lvl:expr, $($arg:tt)+) => {
+        if $crate::filter::level_enabled(module_path!(), Some($crate::Level::Error), $lvl) {
+            $crate::sink::emit_step(false, module_path!(), format_args!($($arg)+));
+        }
+    };
+    ($($arg:tt)+) => {
+        $crate::vfail!(@lvl 1, $($arg)+)
+    };
+}
+
+/// Prints a `[TRACE]` message to stdout if the verbosity is at least 3.
+///
+/// The default of 3 is reached by `-vvv` and by `VERBOSE=debug`, so
+/// `VERBOSE=trace` (4) always shows trace output.
+///
+/// For very chatty output such as per-item details, one step below `vebug!`.
+/// Unlike `vebug!`, trace messages are kept in release builds.
+///
+/// # Syntax
+///
+/// - `vtrace!("Visiting {}", path);`          → prints if verbosity ≥ 3 (default)
+/// - `vtrace!(@lvl 5, "Token {:?}", token);`  → prints if verbosity ≥ 5
+/// - `vtrace!(5, "Token {:?}", token);`       → positional level, see [Compatibility](crate#compatibility)
+/// - `vtrace!(target: "net", "Frame sent");`  → with a [named target](crate#named-targets)
+///
+/// # Example
+/// ```rust
+/// use verbosio::{set_verbosity, verbose_env, vtrace};
+/// use verbosio::history::{enable_history, recent_lines};
+///
+/// enable_history(8);
+/// set_verbosity!(verbosio::levels::VERBOSE);
+/// vtrace!("not printed");
+/// assert!(recent_lines().is_empty());
+///
+/// unsafe {std::env::set_var("VERBOSE", "trace");}
+/// verbose_env!();
+/// vtrace!("Visiting {}", "src/main.rs"); // [TRACE] Visiting src/main.rs
+/// assert_eq!(recent_lines().len(), 1);
+/// ```
+///
+/// # Features
+/// If the `"colors"` feature is enabled, the `[TRACE]` tag may appear gray.
+#[macro_export]
+macro_rules! vtrace {
+    (@lvl $lvl:expr, target: $target:expr, $($arg:tt)+) => {
+        if $crate::filter::level_enabled($target, Some($crate::Level::Trace), $lvl) {
+            $crate::sink::emit_named(Some($crate::Level::Trace), $target, format_args!($($arg)+), Vec::new());
+        }
+    };
+    (@lvl $lvl:expr, $($arg:tt)+) => {
+        if $crate::filter::level_enabled(module_path!(), Some($crate::Level::Trace), $lvl) {
+            $crate::sink::emit(Some($crate::Level::Trace), module_path!(), format_args!($($arg)+));
+        }
+    };
+    ($( $arg:tt )+) => {
+        $crate::__positional_level!(vtrace, [@lvl 3,], $($arg)+)
+    };
+}
+
+/// Prints a message with a level chosen at runtime if the verbosity is high enough.
+///
+/// Equivalent to calling `verror!`, `vwarn!`, `vinfo!`, `vebug!` or `vtrace!` for the
+/// given [`Level`](crate::Level), so severities computed at runtime don't need
+/// a `match` over the macros. As with `vebug!`, `Level::Debug` messages are
+/// only printed in debug builds.
+///
+/// # Syntax
+///
+/// - `vlog!(level, @lvl 2, "Status {}", code);` → prints if verbosity ≥ 2
+/// - `vlog!(level, "Status {}", code);`         → prints if verbosity ≥ 1
+/// - `vlog!(level: "NOTICE", "Disk {}% full", 80);` → a [custom level](crate::level::CustomLevel), printed at its default verbosity
+/// - `vlog!(level: "NOTICE", @lvl 2, "Details");`  → a custom level, printed if verbosity ≥ 2
+///
+/// Names of unregistered custom levels are logged as `INFO`, after a
+/// `[DEBUG]` message naming the unknown level in debug builds.
+///
+/// # Example
+/// ```rust
+/// use verbosio::{set_verbosity, vlog, Level};
+///
+/// set_verbosity!(1);
+/// let status = 503;
+/// let level = match status {
+///     500.. => Level::Error,
+///     400.. => Level::Warn,
+///     _ => Level::Info,
+/// };
+/// vlog!(level, "GET /index.html returned {}", status); // [ERROR] GET /index.html returned 503
+/// vlog!(Level::Info, @lvl 2, "Connection reused");    // not printed
+/// ```
+#[macro_export]
+macro_rules! vlog {
+    (level: $name:expr, @lvl $lvl:expr, $($arg:tt)+) => {{
+        let level = $crate::level::custom_level_or_info($name);
+        if $crate::level::custom_enabled(level, module_path!(), Some($lvl)) {
+            $crate::sink::emit_custom(level, module_path!(), format_args!($($arg)+));
+        }
+    }};
+    (level: $name:expr, $($arg:tt)+) => {{
+        let level = $crate::level::custom_level_or_info($name);
+        if $crate::level::custom_enabled(level, module_path!(), None) {
+            $crate::sink::emit_custom(level, module_path!(), format_args!($($arg)+));
+        }
+    }};
+    ($level:expr, @lvl $lvl:expr, $($arg:tt)+) => {{
+        let level: $crate::Level = $level;
+        if $crate::filter::level_enabled(module_path!(), Some(level), $lvl)
+            && (cfg!(debug_assertions) || level != $crate::Level::Debug)
+        {
+            $crate::sink::emit(Some(level), module_path!(), format_args!($($arg)+));
+        }
+    }};
+    ($level:expr, $($arg:tt)+) => {
+        $crate::vlog!($level, @lvl 1, $($arg)+)
+    };
+}
+
+/// Prints a `miette::Report` with its labels, source snippets and help text
+/// if the verbosity is high enough.
+///
+/// # Syntax
+///
+/// - `vmiette!(@lvl 2, report);` → prints if verbosity ≥ 2
+/// - `vmiette!(report);`          → prints if verbosity ≥ 1
+///
+/// # Output Format
+/// The graphical miette rendering, tagged `[ERROR]`, `[WARN]` or `[INFO]`
+/// depending on the diagnostic's severity. Errors go to `stderr`.
+///
+/// # Example
+/// ```rust
+/// use verbosio::{set_verbosity, vmiette};
+///
+/// set_verbosity!(1);
+/// let report = miette::miette!(help = "check the manifest", "invalid version");
+/// vmiette!(report); // printed
+/// ```
+///
+/// # Features
+/// Only available if the `"miette"` feature is enabled.
+#[cfg(feature = "miette")]
+#[macro_export]
+macro_rules! vmiette {
+    (@lvl $lvl:expr, $report:expr) => {{
+        let report = &$report;
+        let level = $crate::diagnostics::report_level(report);
+        if $crate::filter::level_enabled(module_path!(), Some(level), $lvl) {
+            $crate::diagnostics::emit_miette(report);
+        }
+    }};
+    ($report:expr) => {
+        $crate::vmiette!(@lvl 1, $report)
+    };
+}
+
+/// Runs a fallible closure, retrying it with backoff and logging every attempt.
+///
+/// The closure returns a `Result` whose error implements `Display`. Failed
+/// attempts are logged as warnings with the delay before the next attempt,
+/// which doubles each time. If the last attempt fails too, an error is
+/// logged and its `Err` returned; on success the attempt count is logged as
+/// info. All messages are printed if verbosity ≥ level.
+///
+/// # Syntax
+///
+/// - `vretry!(@lvl 1, attempts = 5, backoff = 2s, "fetching {}", url, || fetch(url))`
+/// - `vretry!("fetching {}", url, || fetch(url))` → verbosity ≥ 1, 3 attempts, 1s backoff
+///
+/// `attempts` and `backoff` are optional and must come in this order.
+/// `backoff` takes a literal with a unit (`500ms`, `2s`, `1m`, `1h`), checked
+/// at compile time, or any `Duration` expression.
+///
+/// # Output Format
+/// ```text
+/// [WARN] fetching index failed (attempt 1/5): timed out, retrying in 2s
+/// [INFO] fetching index succeeded on attempt 2/5
+/// ```
+///
+/// # Example
+/// ```rust
+/// use verbosio::{set_verbosity, vretry};
+///
+/// set_verbosity!(1);
+/// let mut calls = 0;
+/// let result: Result<u32, String> = vretry!(attempts = 3, backoff = 1ms, "fetching {}", "index", || {
+///     calls += 1;
+///     if calls < 2 { Err("timed out".to_string()) } else { Ok(calls) }
+/// });
+/// assert_eq!(result, Ok(2));
+/// ```
+#[macro_export]
+macro_rules! vretry {
+    (@lvl $lvl:expr, $($rest:tt)+) => {
+        $crate::__vretry!([$lvl] [$crate::retry::DEFAULT_ATTEMPTS] [$crate::retry::DEFAULT_BACKOFF] $($rest)+)
+    };
+    ($($rest:tt)+) => {
+        $crate::__vretry!([1] [$crate::retry::DEFAULT_ATTEMPTS] [$crate::retry::DEFAULT_BACKOFF] $($rest)+)
+    };
+}
+
+/// Parses the options and message arguments of `vretry!`.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __vretry {
+    ([$lvl:expr] [$attempts:expr] [$backoff:expr] attempts = $new:expr, $($rest:tt)+) => {
+        $crate::__vretry!([$lvl] [$new] [$backoff] $($rest)+)
+    };
+    ([$lvl:expr] [$attempts:expr] [$backoff:expr] backoff = $new:literal, $($rest:tt)+) => {
+        $crate::__vretry!([$lvl] [$attempts] [const { $crate::retry::parse_backoff(stringify!($new)) }] $($rest)+)
+    };
+    ([$lvl:expr] [$attempts:expr] [$backoff:expr] backoff = $new:expr, $($rest:tt)+) => {
+        $crate::__vretry!([$lvl] [$attempts] [$new] $($rest)+)
+    };
+    ([$lvl:expr] [$attempts:expr] [$backoff:expr] $fmt:literal, $($rest:tt)+) => {
+        $crate::__vretry!(@args [$lvl] [$attempts] [$backoff] [$fmt] [] $($rest)+)
+    };
+    (@args [$lvl:expr] [$attempts:expr] [$backoff:expr] [$fmt:literal] [$($arg:expr),*] $work:expr) => {
+        $crate::retry::retry($lvl, $attempts, $backoff, module_path!(), format!($fmt $(, $arg)*), $work)
+    };
+    (@args [$lvl:expr] [$attempts:expr] [$backoff:expr] [$fmt:literal] [$($arg:expr),*] $next:expr, $($rest:tt)+) => {
+        $crate::__vretry!(@args [$lvl] [$attempts] [$backoff] [$fmt] [$($arg,)* $next] $($rest)+)
+    };
+}
+
+/// Runs a block, then prints how long it took if the verbosity is high enough.
+///
+/// The block's value is returned, so `vtime!` can wrap expressions in place.
+/// Blocks that run unexpectedly long can temporarily raise the verbosity, see
+/// [`timer::escalate_slow_phases`](crate::timer::escalate_slow_phases).
+///
+/// # Syntax
+///
+/// - `vtime!(@lvl 2, "Resolving", { ... })` → prints timing if verbosity ≥ 2
+/// - `vtime!("Resolving", { ... })`         → prints timing if verbosity ≥ 1
+///
+/// # Output Format
+/// Outputs messages like `Resolving took 1.23s`.
+///
+/// # Example
+/// ```rust
+/// use verbosio::{set_verbosity, vtime};
+///
+/// set_verbosity!(1);
+/// let sum = vtime!("Summing", { (1..=100).sum::<u32>() });
+/// assert_eq!(sum, 5050);
+/// ```
+///
+/// # Features
+/// With the `"otel"` feature enabled, the block is recorded as an
+/// OpenTelemetry span, regardless of verbosity.
+#[macro_export]
+macro_rules! vtime {
+    (@lvl $lvl:expr, $label:expr, $body:block) => {{
+        let __verbosio_timer = $crate::timer::Timer::start($label);
+        let __verbosio_value = $body;
+        if $crate::is_enabled!(@lvl $lvl) {
+            $crate::sink::emit(None, module_path!(), format_args!("{} took {:.2?}", __verbosio_timer.label(), __verbosio_timer.elapsed()));
+        }
+        __verbosio_value
+    }};
+    ($label:expr, $body:block) => {
+        $crate::vtime!(@lvl 1, $label, $body)
+    };
+}
+
+/// Attaches a key-value pair to every message logged from this thread.
+///
+/// # Syntax
+///
+/// - `vcontext_set!("request_id", id);` → value is formatted with `Display`
+///
+/// # Example
+/// ```rust
+/// use verbosio::{set_verbosity, vcontext_set, vinfo};
+///
+/// set_verbosity!(1);
+/// vcontext_set!("job", "build");
+/// vinfo!("started"); // [INFO] started job=build
+/// ```
+#[macro_export]
+macro_rules! vcontext_set {
+    ($key:expr, $value:expr) => {
+        $crate::context::set($key, format!("{}", $value))
+    };
+}
+
+/// Removes a key set with `vcontext_set!` from this thread's context.
+///
+/// # Example
+/// ```rust
+/// use verbosio::{vcontext_remove, vcontext_set};
+///
+/// vcontext_set!("job", "build");
+/// vcontext_remove!("job");
+/// ```
+#[macro_export]
+macro_rules! vcontext_remove {
+    ($key:expr) => {
+        $crate::context::remove($key)
+    };
+}
+
+/// Removes all keys from this thread's context.
+///
+/// # Example
+/// ```rust
+/// use verbosio::{vcontext_clear, vcontext_set};
+///
+/// vcontext_set!("job", "build");
+/// vcontext_clear!();
+/// ```
+#[macro_export]
+macro_rules! vcontext_clear {
+    () => {
+        $crate::context::clear()
+    };
+}
+
+/// Sets fields attached to every record from every thread.
+///
+/// Meant to be called once at startup. Global fields always appear in
+/// structured output; in human output they are only rendered after
+/// `verbosio::context::show_global_fields(true)`.
+///
+/// # Syntax
+///
+/// - `set_global_fields!(version = env!("CARGO_PKG_VERSION"), host = name);`
+///
+/// # Example
+/// ```rust
+/// use verbosio::{set_global_fields, set_verbosity, vinfo};
+///
+/// set_verbosity!(1);
+/// set_global_fields!(version = "1.2.0", profile = "release");
+/// verbosio::context::show_global_fields(true);
+/// vinfo!("started"); // [INFO] started version=1.2.0 profile=release
+/// ```
+#[macro_export]
+macro_rules! set_global_fields {
+    ($($key:ident = $value:expr),* $(,)?) => {
+        $crate::context::set_global_fields(vec![
+            $((stringify!($key).to_string(), format!("{}", $value))),*
+        ])
+    };
+}
+
+/// Attaches fields to everything logged on this thread while a block runs.
+///
+/// Fields are removed again when the block ends, restoring outer values of
+/// the same keys, so nested scopes work as expected. Without a block, the
+/// [`FieldsGuard`](crate::context::FieldsGuard) is returned instead and the
+/// fields stay until it is dropped.
+///
+/// # Syntax
+///
+/// - `with_fields!(file = path, { ... })` → evaluates to the block's value
+/// - `let _guard = with_fields!(file = path);` → fields live as long as `_guard`
+///
+/// # Example
+/// ```rust
+/// use verbosio::{set_verbosity, vinfo, with_fields};
+///
+/// set_verbosity!(1);
+/// for file in ["a.txt", "b.txt"] {
+///     let size = with_fields!(file = file, {
+///         vinfo!("processing"); // [INFO] processing file=a.txt
+///         42
+///     });
+///     assert_eq!(size, 42);
+/// }
+/// ```
+#[macro_export]
+macro_rules! with_fields {
+    ($($key:ident = $value:expr),+ , $body:block) => {{
+        let __verbosio_fields = $crate::with_fields!($($key = $value),+);
+        $body
+    }};
+    ($($key:ident = $value:expr),+ $(,)?) => {
+        $crate::context::push_fields(vec![
+            $((stringify!($key).to_string(), format!("{}", $value))),+
+        ])
+    };
+}
+
+/// Labels the current thread, so its messages are prefixed with `label | `.
+///
+/// Makes the interleaved output of parallel workers readable.
+///
+/// # Example
+/// ```rust
+/// use verbosio::{set_thread_label, set_verbosity, vinfo};
+///
+/// set_verbosity!(1);
+/// std::thread::spawn(|| {
+///     set_thread_label!("worker-{}", 3);
+///     vinfo!("started"); // worker-3 | [INFO] started
+/// }).join().unwrap();
+/// ```
+#[macro_export]
+macro_rules! set_thread_label {
+    ($($arg:tt)+) => {
+        $crate::context::set_thread_label(format!($($arg)+))
+    };
+}
+
+/// Prints how many warnings and errors were emitted during the run.
+///
+/// # Syntax
+///
+/// - `vsummary!(@lvl 2);` → prints if verbosity ≥ 2
+/// - `vsummary!();`       → prints if verbosity ≥ 1
+///
+/// # Output Format
+/// Outputs e.g. `finished with 3 warnings, 1 error`.
+///
+/// # Example
+/// ```rust
+/// use verbosio::{set_verbosity, vsummary, vwarn};
+///
+/// set_verbosity!(1);
+/// vwarn!("disk almost full");
+/// vsummary!(); // finished with 1 warning
+/// ```
+///
+/// # Features
+/// With the `"color"` feature enabled, the summary is red if errors were
+/// emitted, yellow for warnings only, and green otherwise.
+#[macro_export]
+macro_rules! vsummary {
+    (@lvl $lvl:expr) => {
+        if $crate::is_enabled!(@lvl $lvl) {
+            $crate::sink::emit(None, module_path!(), format_args!("{}", $crate::tally::summary()));
+        }
+    };
+    () => {
+        $crate::vsummary!(@lvl 1)
+    };
+}
+
+/// Raises a desktop notification if the verbosity is high enough.
+///
+/// Meant for the end of long runs, when the user has likely switched to
+/// another window. Failures to show the notification are ignored.
+///
+/// # Syntax
+///
+/// - `vnotify!(@lvl 2, "Build finished", ok);` → notifies if verbosity ≥ 2
+/// - `vnotify!("Build finished", ok);`         → notifies if verbosity ≥ 1
+///
+/// # Example
+/// ```rust,no_run
+/// use verbosio::{exit_code, vnotify};
+///
+/// vnotify!("Build finished", exit_code() == 0);
+/// ```
+///
+/// # Features
+/// Only available if the `"notify"` feature is enabled. See
+/// [`notify`](crate::notify) for the tools used on each platform.
+#[cfg(feature = "notify")]
+#[macro_export]
+macro_rules! vnotify {
+    (@lvl $lvl:expr, $message:expr, $success:expr) => {
+        if $crate::is_enabled!(@lvl $lvl) {
+            let _ = $crate::notify::notify(&$message, $success);
+        }
+    };
+    ($message:expr, $success:expr) => {
+        $crate::vnotify!(@lvl 1, $message, $success)
+    };
+}
+
+/// Saves a progress checkpoint and prints it if the verbosity is high enough.
+///
+/// The checkpoint is persisted regardless of verbosity, so an interrupted
+/// run can be resumed, see [`checkpoint`](crate::checkpoint). Saving errors
+/// are ignored.
+///
+/// # Syntax
+///
+/// - `vcheckpoint!(@lvl 2, "step", 42);` → prints if verbosity ≥ 2
+/// - `vcheckpoint!("step", 42);`         → prints if verbosity ≥ 1
+///
+/// # Output Format
+/// Outputs messages like `checkpoint: step 42`.
+///
+/// # Example
+/// ```rust
+/// use verbosio::{checkpoint, vcheckpoint};
+///
+/// checkpoint::init_checkpoints(std::env::temp_dir().join("verbosio-macro-doc.checkpoint")).unwrap();
+/// vcheckpoint!("downloaded", "index.json");
+/// checkpoint::finish_checkpoints();
+/// ```
+#[macro_export]
+macro_rules! vcheckpoint {
+    (@lvl $lvl:expr, $name:expr, $value:expr) => {{
+        let name: &str = &$name;
+        let value = &$value;
+        let _ = $crate::checkpoint::save(name, value);
+        if $crate::is_enabled!(@lvl $lvl) {
+            $crate::sink::emit(None, module_path!(), format_args!("checkpoint: {} {}", name, value));
+        }
+    }};
+    ($name:expr, $value:expr) => {
+        $crate::vcheckpoint!(@lvl 1, $name, $value)
+    };
+}
+
+/// Prints an `[ERROR]` message and exits the process with `code`.
+///
+/// The message is printed whatever the verbosity, quiet mode or level
+/// thresholds. Spinners are stopped before it is printed, and output is
+/// flushed before exiting, like [`verbosio::exit`](crate::exit).
+///
+/// # Syntax
+///
+/// - `vfatal!(2, "cannot read {}", path);` → prints and exits with `2`
+///
+/// # Example
+/// ```rust,no_run
+/// use verbosio::vfatal;
+///
+/// let path = "config.toml";
+/// if std::fs::metadata(path).is_err() {
+///     vfatal!(66, "missing {}", path); // [ERROR] missing config.toml
+/// }
+/// ```
+#[macro_export]
+macro_rules! vfatal {
+    ($code:expr, $($arg:tt)+) => {
+        $crate::shutdown::fatal($code, module_path!(), format_args!($($arg)+))
+    };
+}
+
+/// Exits the process with a status derived from the logged severities.
+///
+/// Exits with `0` if nothing notable was logged, `1` if warnings and `2` if
+/// errors were emitted (see [`exit_code`](crate::exit_code)). Spinners are
+/// stopped and output is flushed first, like [`verbosio::exit`](crate::exit).
+///
+/// # Example
+/// ```rust,no_run
+/// use verbosio::{exit_with_status, verror};
+///
+/// verror!("3 files failed to compile");
+/// exit_with_status!(); // exits with 2
+/// ```
+#[macro_export]
+macro_rules! exit_with_status {
+    () => {
+        $crate::exit($crate::exit_code())
+    };
 }
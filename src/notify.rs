@@ -0,0 +1,80 @@
+//! Desktop notifications for long-running commands.
+//!
+//! Notifications are raised with the platform's own tools, so no extra
+//! dependencies are needed: `notify-send` on Linux and BSD, `osascript` on
+//! macOS and PowerShell toasts on Windows. If the tool is missing, nothing
+//! is shown and an error is returned.
+use std::io;
+use std::process::{Command, Stdio};
+
+/// Shows a desktop notification with `message`, titled with the name of
+/// the running executable and whether it `success`fully finished.
+///
+/// Returns once the notification tool was started, without waiting for it.
+///
+/// # Example
+/// ```rust,no_run
+/// verbosio::notify::notify("Build finished in 4m 12s", true).ok();
+/// ```
+pub fn notify(message: &str, success: bool) -> io::Result<()> {
+    let app = std::env::current_exe()
+        .ok()
+        .and_then(|exe| exe.file_stem().map(|stem| stem.to_string_lossy().into_owned()))
+        .unwrap_or_else(|| "verbosio".to_string());
+    let title = if success { format!("{} succeeded", app) } else { format!("{} failed", app) };
+    command(&app, &title, message, success)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map(drop)
+}
+
+#[cfg(target_os = "macos")]
+fn command(_app: &str, title: &str, message: &str, _success: bool) -> Command {
+    let script = format!(
+        "display notification {} with title {}",
+        applescript_string(message),
+        applescript_string(title)
+    );
+    let mut command = Command::new("osascript");
+    command.arg("-e").arg(script);
+    command
+}
+
+#[cfg(target_os = "macos")]
+fn applescript_string(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+#[cfg(windows)]
+fn command(app: &str, title: &str, message: &str, _success: bool) -> Command {
+    // the texts are passed as environment variables to avoid quoting issues
+    const SCRIPT: &str = "\
+        [Windows.UI.Notifications.ToastNotificationManager, Windows.UI.Notifications, ContentType = WindowsRuntime] > $null; \
+        $xml = [Windows.UI.Notifications.ToastNotificationManager]::GetTemplateContent([Windows.UI.Notifications.ToastTemplateType]::ToastText02); \
+        $text = $xml.GetElementsByTagName('text'); \
+        $text.Item(0).AppendChild($xml.CreateTextNode($env:VERBOSIO_TITLE)) > $null; \
+        $text.Item(1).AppendChild($xml.CreateTextNode($env:VERBOSIO_MESSAGE)) > $null; \
+        $toast = [Windows.UI.Notifications.ToastNotification]::new($xml); \
+        [Windows.UI.Notifications.ToastNotificationManager]::CreateToastNotifier($env:VERBOSIO_APP).Show($toast)";
+    let mut command = Command::new("powershell");
+    command
+        .args(["-NoProfile", "-NonInteractive", "-Command", SCRIPT])
+        .env("VERBOSIO_APP", app)
+        .env("VERBOSIO_TITLE", title)
+        .env("VERBOSIO_MESSAGE", message);
+    command
+}
+
+#[cfg(not(any(target_os = "macos", windows)))]
+fn command(app: &str, title: &str, message: &str, success: bool) -> Command {
+    let mut command = Command::new("notify-send");
+    command
+        .args(["--app-name", app])
+        .args(["--urgency", if success { "normal" } else { "critical" }])
+        .args(["--icon", if success { "dialog-information" } else { "dialog-error" }])
+        .arg(title)
+        .arg(message);
+    command
+}
@@ -0,0 +1,29 @@
+//! OpenTelemetry spans for timed phases.
+//!
+//! With the `otel` feature enabled, every [`vtime!`](crate::vtime) block
+//! creates a span on the globally registered tracer (instrumentation scope
+//! `"verbosio"`), so a CLI's phase structure shows up in trace viewers next
+//! to its logs. Register a tracer provider with
+//! `opentelemetry::global::set_tracer_provider` during startup; without one,
+//! spans are no-ops.
+use std::borrow::Cow;
+use opentelemetry::global::{self, BoxedSpan};
+use opentelemetry::trace::{Span, Tracer};
+
+/// A span that ends when dropped.
+pub struct SpanGuard {
+    span: BoxedSpan,
+}
+
+/// Starts a span named `name` on the `"verbosio"` tracer.
+pub fn start_span(name: impl Into<Cow<'static, str>>) -> SpanGuard {
+    SpanGuard {
+        span: global::tracer("verbosio").start(name),
+    }
+}
+
+impl Drop for SpanGuard {
+    fn drop(&mut self) {
+        self.span.end();
+    }
+}
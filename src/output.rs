@@ -0,0 +1,534 @@
+//! Output modes for the different consumers of a tool's output.
+//!
+//! In the default [`OutputMode::Human`] mode messages are printed with level
+//! tags, optional colors and timestamps. The other modes translate messages
+//! into the conventions of the program reading the output, or into JSON
+//! lines with [`OutputMode::Json`].
+//!
+//! Modes parse from their names, so a `--output json` flag can be passed
+//! straight to [`set_output_mode`]:
+//!
+//! ```rust
+//! use verbosio::output::{set_output_mode, OutputMode};
+//!
+//! let mode: OutputMode = "json".parse().unwrap();
+//! set_output_mode(mode);
+//! verbosio::vinfo!("ready"); // {"timestamp":"...","level":"info",...,"message":"ready",...}
+//! # set_output_mode(OutputMode::Human);
+//! ```
+//!
+//! In human mode errors go to `stderr` and everything else to `stdout`;
+//! [`set_level_stream`] moves a level to the other stream.
+//!
+//! # Example
+//! ```rust
+//! use verbosio::output::{output_mode, OutputMode};
+//! use verbosio::set_output_mode;
+//!
+//! set_output_mode!(Human);
+//! assert_eq!(output_mode(), OutputMode::Human);
+//! ```
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::time::Duration;
+use crate::Level;
+use crate::format::{json_string, Format};
+use crate::record::Record;
+use crate::util::{format_custom_level, format_level, format_location, format_note, format_rfc3339, format_section, format_section_footer, format_target, format_time_at, strip_ansi};
+
+/// How messages are rendered on the terminal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+pub enum OutputMode {
+    /// Level tags, colors and timestamps for people reading a terminal.
+    #[default]
+    Human,
+    /// Cargo build script directives: `vwarn!` becomes `cargo:warning=`,
+    /// errors become `cargo:warning=error: ...`, everything else is printed
+    /// as plain lines, which cargo only shows with `-vv`.
+    BuildScript,
+    /// GitHub Actions workflow commands: `vsection!` opens a collapsible
+    /// `::group::`, `vwarn!`/`verror!` become `::warning::`/`::error::`
+    /// annotations with the `file` and `line` of the call site. Detected via
+    /// `GITHUB_ACTIONS=true`.
+    GitHubActions,
+    /// TeamCity service messages: `vsection!` opens a `blockOpened` block,
+    /// warnings and errors become `message` records with a status, and
+    /// status lines become `progressMessage`s. Detected via `TEAMCITY_VERSION`.
+    ///
+    /// Jenkins has no equivalent protocol, its plugins parse plain console
+    /// lines instead, so there is no Jenkins mode; use [`Human`](OutputMode::Human)
+    /// output there.
+    #[cfg_attr(feature = "clap", value(name = "teamcity"))]
+    TeamCity,
+    /// One JSON object per line on `stdout`, with the same fields as
+    /// [`Format::Json`]. Section headers, footers and status lines become
+    /// JSON objects too.
+    Json,
+}
+
+impl fmt::Display for OutputMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            OutputMode::Human => "human",
+            OutputMode::BuildScript => "build-script",
+            OutputMode::GitHubActions => "github-actions",
+            OutputMode::TeamCity => "teamcity",
+            OutputMode::Json => "json",
+        })
+    }
+}
+
+impl FromStr for OutputMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "human" | "text" => Ok(OutputMode::Human),
+            "build-script" | "buildscript" => Ok(OutputMode::BuildScript),
+            "github-actions" | "githubactions" => Ok(OutputMode::GitHubActions),
+            "teamcity" => Ok(OutputMode::TeamCity),
+            "json" => Ok(OutputMode::Json),
+            _ => Err(format!(
+                "invalid output mode '{}', expected human, build-script, github-actions, teamcity or json",
+                s
+            )),
+        }
+    }
+}
+
+const UNSET: u8 = u8::MAX;
+
+static OUTPUT_MODE: AtomicU8 = AtomicU8::new(UNSET);
+
+/// Minimum verbosity at which human output shows the record target.
+static SHOW_TARGET_AT: AtomicU8 = AtomicU8::new(UNSET);
+
+/// Minimum verbosity at which the source location of records is shown.
+static SHOW_LOCATION_AT: AtomicU8 = AtomicU8::new(UNSET);
+
+/// Name of the CI group/block opened by the last section, if still open.
+static OPEN_GROUP: Mutex<Option<String>> = Mutex::new(None);
+
+impl OutputMode {
+    fn to_u8(self) -> u8 {
+        match self {
+            OutputMode::Human => 0,
+            OutputMode::BuildScript => 1,
+            OutputMode::GitHubActions => 2,
+            OutputMode::TeamCity => 3,
+            OutputMode::Json => 4,
+        }
+    }
+
+    fn from_u8(value: u8) -> Self {
+        match value {
+            1 => OutputMode::BuildScript,
+            2 => OutputMode::GitHubActions,
+            3 => OutputMode::TeamCity,
+            4 => OutputMode::Json,
+            _ => OutputMode::Human,
+        }
+    }
+}
+
+/// Sets the output mode, overriding environment detection.
+pub fn set_output_mode(mode: OutputMode) {
+    OUTPUT_MODE.store(mode.to_u8(), Ordering::Relaxed);
+}
+
+/// Returns the current output mode.
+///
+/// If no mode has been set, it is detected from the environment once.
+pub fn output_mode() -> OutputMode {
+    match OUTPUT_MODE.load(Ordering::Relaxed) {
+        UNSET => {
+            let mode = detect_output_mode();
+            set_output_mode(mode);
+            mode
+        }
+        value => OutputMode::from_u8(value),
+    }
+}
+
+/// Shows the abbreviated target (e.g. `m::n::http: `) in front of human
+/// output messages while verbosity is at least `lvl`; `None` never shows it.
+///
+/// # Example
+/// ```rust
+/// use verbosio::{set_verbosity, vinfo};
+///
+/// verbosio::output::show_target_at(Some(3));
+/// set_verbosity!(3);
+/// vinfo!("connected"); // from my_app::net: [INFO] m::net: connected
+/// ```
+pub fn show_target_at(lvl: Option<u8>) {
+    SHOW_TARGET_AT.store(lvl.unwrap_or(UNSET), Ordering::Relaxed);
+}
+
+fn target_shown() -> bool {
+    match SHOW_TARGET_AT.load(Ordering::Relaxed) {
+        UNSET => false,
+        lvl => crate::scope::verbosity() >= lvl,
+    }
+}
+
+/// Shows the call site (`src/net.rs:42`) of each message while verbosity is
+/// at least `lvl`; `None` never shows it. On a terminal of known width the
+/// location is dimmed and right-aligned, elsewhere it follows the message.
+/// JSON and logfmt output get `file` and `line` fields.
+///
+/// # Example
+/// ```rust
+/// use verbosio::{set_verbosity, vinfo};
+///
+/// verbosio::output::show_location_at(Some(3));
+/// set_verbosity!(3);
+/// vinfo!("connected"); // [INFO] connected                    src/net.rs:42
+/// # verbosio::output::show_location_at(None);
+/// ```
+pub fn show_location_at(lvl: Option<u8>) {
+    SHOW_LOCATION_AT.store(lvl.unwrap_or(UNSET), Ordering::Relaxed);
+}
+
+pub(crate) fn location_shown() -> bool {
+    match SHOW_LOCATION_AT.load(Ordering::Relaxed) {
+        UNSET => false,
+        lvl => crate::scope::verbosity() >= lvl,
+    }
+}
+
+/// Detects the output mode from the environment.
+///
+/// | Variable              | Mode                          |
+/// |-----------------------|-------------------------------|
+/// | `GITHUB_ACTIONS=true` | [`OutputMode::GitHubActions`] |
+/// | `TEAMCITY_VERSION`    | [`OutputMode::TeamCity`]      |
+/// | otherwise             | [`OutputMode::Human`]         |
+///
+/// Jenkins (`JENKINS_URL`) gets human output, see [`OutputMode::TeamCity`].
+pub fn detect_output_mode() -> OutputMode {
+    if std::env::var("GITHUB_ACTIONS").is_ok_and(|v| v == "true") {
+        OutputMode::GitHubActions
+    } else if std::env::var_os("TEAMCITY_VERSION").is_some() {
+        OutputMode::TeamCity
+    } else {
+        OutputMode::Human
+    }
+}
+
+/// Closes an open CI group or block, if any.
+///
+/// Called on shutdown, so the last section doesn't stay open.
+pub fn close_group() {
+    if let Some(close) = take_open_group(output_mode()) {
+        println!("{}", close);
+    }
+}
+
+/// Records `name` as the open group and returns the command closing the
+/// previous one.
+fn replace_open_group(mode: OutputMode, name: &str) -> Option<String> {
+    let previous = OPEN_GROUP.lock().unwrap_or_else(|e| e.into_inner()).replace(name.to_string());
+    previous.map(|previous| render_group_close(mode, &previous))
+}
+
+fn take_open_group(mode: OutputMode) -> Option<String> {
+    let previous = OPEN_GROUP.lock().unwrap_or_else(|e| e.into_inner()).take();
+    previous.map(|previous| render_group_close(mode, &previous))
+}
+
+fn render_group_close(mode: OutputMode, name: &str) -> String {
+    match mode {
+        OutputMode::TeamCity => format!("##teamcity[blockClosed name='{}']", escape_service_message(name)),
+        _ => "::endgroup::".to_string(),
+    }
+}
+
+/// Prepares logging for a cargo build script.
+///
+/// Switches to [`OutputMode::BuildScript`] and derives the verbosity:
+///
+/// 1. `0` if `QUIET` is set, see [`quiet_from_env`](crate::env::quiet_from_env).
+/// 2. `VERBOSE`, parsed with [`parse_verbosity`](crate::env::parse_verbosity),
+///    so `VERBOSE=debug` is `3` as everywhere else.
+/// 3. `2` if `CARGO_TERM_VERBOSE=true`, `1` otherwise.
+///
+/// The `CARGO_TERM_VERBOSE` fallback is a best effort: cargo passes it on
+/// only when it is set in the environment, not for `cargo build -v` or
+/// `term.verbose` in a config file, so build scripts usually see `1`. Set
+/// `VERBOSE` to be sure. Also tells cargo to rerun the script when one of
+/// these variables changes.
+///
+/// # Example
+/// ```rust
+/// // build.rs
+/// verbosio::output::init_build_script();
+/// verbosio::vwarn!("bundled library not found, building from source");
+/// ```
+pub fn init_build_script() {
+    set_output_mode(OutputMode::BuildScript);
+    println!("cargo:rerun-if-env-changed=VERBOSE");
+    println!("cargo:rerun-if-env-changed=QUIET");
+    println!("cargo:rerun-if-env-changed=CARGO_TERM_VERBOSE");
+
+    let verbosity = match std::env::var("VERBOSE").ok().and_then(|v| crate::env::parse_verbosity(&v)) {
+        _ if crate::env::quiet_from_env() => 0,
+        Some(verbosity) => verbosity,
+        None if std::env::var("CARGO_TERM_VERBOSE").is_ok_and(|v| v == "true") => 2,
+        None => 1,
+    };
+    crate::set_verbosity!(verbosity);
+}
+
+/// The terminal stream a message is written to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Stream {
+    Stdout,
+    Stderr,
+}
+
+/// Streams of the levels, indexed by `Level as usize`.
+static LEVEL_STREAMS: [AtomicU8; 5] = [const { AtomicU8::new(UNSET) }; 5];
+
+/// Sends all messages of `level` to `stream` in [`OutputMode::Human`];
+/// `None` restores the default of `stderr` for errors and `stdout` for
+/// everything else.
+///
+/// The other modes keep their fixed stream, since their consumers read
+/// only one of them.
+///
+/// # Example
+/// ```rust
+/// use verbosio::output::{set_level_stream, Stream};
+/// use verbosio::Level;
+///
+/// // keep stdout free for data
+/// set_level_stream(Level::Warn, Some(Stream::Stderr));
+/// set_level_stream(Level::Info, Some(Stream::Stderr));
+/// # set_level_stream(Level::Warn, None);
+/// # set_level_stream(Level::Info, None);
+/// ```
+pub fn set_level_stream(level: Level, stream: Option<Stream>) {
+    let value = match stream {
+        None => UNSET,
+        Some(Stream::Stdout) => 0,
+        Some(Stream::Stderr) => 1,
+    };
+    LEVEL_STREAMS[level as usize].store(value, Ordering::Relaxed);
+}
+
+/// The stream set with [`set_level_stream`], if any.
+pub fn level_stream(level: Level) -> Option<Stream> {
+    match LEVEL_STREAMS[level as usize].load(Ordering::Relaxed) {
+        0 => Some(Stream::Stdout),
+        1 => Some(Stream::Stderr),
+        _ => None,
+    }
+}
+
+/// Whether a message of `level` goes to stderr in `mode`.
+pub(crate) fn to_stderr(mode: OutputMode, level: Option<Level>) -> bool {
+    match mode {
+        OutputMode::Human => match level.and_then(level_stream) {
+            Some(stream) => stream == Stream::Stderr,
+            None => level == Some(Level::Error),
+        },
+        // cargo and the Actions runner only read commands from stdout
+        OutputMode::BuildScript | OutputMode::GitHubActions | OutputMode::TeamCity => false,
+        // a single stream of JSON lines is easiest to consume
+        OutputMode::Json => false,
+    }
+}
+
+/// Whether `record` goes to stderr in `mode`, following the stream of its
+/// custom level, if any.
+pub(crate) fn record_to_stderr(mode: OutputMode, record: &Record) -> bool {
+    match record.custom_level.and_then(|custom| custom.stream()) {
+        Some(stream) if mode == OutputMode::Human => stream == Stream::Stderr,
+        _ => to_stderr(mode, record.level),
+    }
+}
+
+/// Renders a record for the terminal.
+pub(crate) fn render_message(mode: OutputMode, record: &Record) -> String {
+    let message = record.message_with_notes();
+    match mode {
+        OutputMode::Human => render_human(record, &format_time_at(record.time), crate::capabilities().width),
+        OutputMode::BuildScript => match record.level {
+            Some(Level::Warn) => cargo_warning("", &message),
+            Some(Level::Error) => cargo_warning("error: ", &message),
+            Some(level) => format!("[{}] {}", level, message),
+            None => message,
+        },
+        OutputMode::GitHubActions => match record.level {
+            Some(Level::Warn) => annotation("warning", record, &message),
+            Some(Level::Error) => annotation("error", record, &message),
+            _ => render_message(OutputMode::Human, record),
+        },
+        OutputMode::TeamCity => match record.level {
+            Some(Level::Warn) => service_message(&message, "WARNING"),
+            Some(Level::Error) => service_message(&message, "ERROR"),
+            _ => render_message(OutputMode::Human, record),
+        },
+        OutputMode::Json => Format::Json.render(record),
+    }
+}
+
+/// Renders a record as human text after the level tag's `time` prefix. The
+/// location, if shown, is right-aligned to `width`.
+fn render_human(record: &Record, time: &str, width: Option<u16>) -> String {
+    let label = record.thread_label.as_ref().map(|label| format!("{} | ", label)).unwrap_or_default();
+    let tag = match (record.success, record.custom_level) {
+        (Some(success), _) => format!("{} ", crate::wait::format_mark(success)),
+        (None, Some(custom)) => format_custom_level(custom),
+        (None, None) => record.level.map(|level| format_level(level.as_str())).unwrap_or_default(),
+    };
+    let mut out = if record.named_target {
+        format!("{}{}{}{}{}", label, tag, time, format_target(&record.target), record.message_with_fields())
+    } else if target_shown() {
+        format!("{}{}{}{}: {}", label, tag, time, record.short_target(), record.message_with_fields())
+    } else {
+        format!("{}{}{}{}", label, tag, time, record.message_with_fields())
+    };
+    if let Some(location) = record.location.filter(|_| location_shown()) {
+        let location = location.to_string();
+        // the location goes at the end of the first line
+        let end = out.find('\n').unwrap_or(out.len());
+        let used = strip_ansi(&out[..end]).chars().count();
+        let padding = match width {
+            Some(width) if used + location.len() < usize::from(width) => usize::from(width) - used - location.len(),
+            _ => 1,
+        };
+        out.insert_str(end, &format!("{}{}", " ".repeat(padding), format_location(&location)));
+    }
+    for (kind, text) in &record.notes {
+        out.push('\n');
+        out.push_str(&format_note(kind.as_str(), text));
+    }
+    out
+}
+
+/// Renders a record as human text for the log file: without colors, and
+/// always with a timestamp, whether or not the `time` feature is enabled.
+pub(crate) fn render_file_message(record: &Record) -> String {
+    strip_ansi(&render_human(record, &format!("[{}] ", format_rfc3339(record.time)), None))
+}
+
+/// Renders a status line message for modes that can't animate a spinner.
+///
+/// Returns `None` in [`OutputMode::Human`], where the spinner is drawn.
+#[cfg(feature = "status")]
+pub(crate) fn render_progress(mode: OutputMode, message: &str) -> Option<String> {
+    match mode {
+        OutputMode::Human => None,
+        OutputMode::TeamCity => Some(format!("##teamcity[progressMessage '{}']", escape_service_message(message))),
+        OutputMode::BuildScript | OutputMode::GitHubActions => Some(message.to_string()),
+        OutputMode::Json => {
+            let mut record = Record::new(None, module_path!(), message.to_string());
+            record.fields.push(("progress".to_string(), "true".to_string()));
+            Some(Format::Json.render(&record))
+        }
+    }
+}
+
+/// Renders a section header for the terminal.
+pub(crate) fn render_section(mode: OutputMode, title: &str) -> String {
+    match mode {
+        OutputMode::Human => format!("{}\n", format_section(title)),
+        OutputMode::BuildScript => format!("=== {} ===", title),
+        OutputMode::Json => Format::Json.render_section(title).unwrap_or_default(),
+        OutputMode::GitHubActions | OutputMode::TeamCity => {
+            let open = match mode {
+                OutputMode::TeamCity => format!("##teamcity[blockOpened name='{}']", escape_service_message(title)),
+                _ => format!("::group::{}", escape_workflow_data(title)),
+            };
+            match replace_open_group(mode, title) {
+                Some(close) => format!("{}\n{}", close, open),
+                None => open,
+            }
+        }
+    }
+}
+
+/// Footer of a scoped section, closing its group in CI modes.
+pub(crate) fn render_section_footer(mode: OutputMode, title: &str, elapsed: Duration, failed: bool) -> String {
+    let footer = strip_ansi(&format_section_footer(title, elapsed, failed));
+    match mode {
+        OutputMode::Human => format!("{}\n", format_section_footer(title, elapsed, failed)),
+        OutputMode::BuildScript => footer,
+        OutputMode::Json => Format::Json.render_section_footer(title, elapsed, failed).unwrap_or_default(),
+        OutputMode::GitHubActions | OutputMode::TeamCity => {
+            let mut open = OPEN_GROUP.lock().unwrap_or_else(|e| e.into_inner());
+            if open.as_deref() == Some(title) {
+                open.take();
+                format!("{}\n{}", footer, render_group_close(mode, title))
+            } else {
+                footer
+            }
+        }
+    }
+}
+
+/// One `cargo:warning=` directive per line, since directives can't span lines.
+fn cargo_warning(prefix: &str, message: &str) -> String {
+    message
+        .lines()
+        .map(|line| format!("cargo:warning={}{}", prefix, line))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// A `::warning::`/`::error::` annotation, pointing at the call site if the
+/// record has one, so GitHub shows it next to that line of the diff.
+fn annotation(command: &str, record: &Record, message: &str) -> String {
+    match record.location {
+        Some(location) => format!(
+            "::{} file={},line={}::{}",
+            command,
+            escape_workflow_property(location.file),
+            location.line,
+            escape_workflow_data(message)
+        ),
+        None => format!("::{}::{}", command, escape_workflow_data(message)),
+    }
+}
+
+/// Escapes a workflow command parameter, which additionally can't contain
+/// the `:` and `,` separating parameters.
+fn escape_workflow_property(value: &str) -> String {
+    escape_workflow_data(value).replace(':', "%3A").replace(',', "%2C")
+}
+
+/// Escapes a workflow command message, which has to fit on one line.
+fn escape_workflow_data(message: &str) -> String {
+    message
+        .replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+fn service_message(text: &str, status: &str) -> String {
+    format!("##teamcity[message text='{}' status='{}']", escape_service_message(text), status)
+}
+
+/// Escapes a value inside a TeamCity service message.
+fn escape_service_message(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '|' => out.push_str("||"),
+            '\'' => out.push_str("|'"),
+            '\n' => out.push_str("|n"),
+            '\r' => out.push_str("|r"),
+            '[' => out.push_str("|["),
+            ']' => out.push_str("|]"),
+            c => out.push(c),
+        }
+    }
+    out
+}
@@ -0,0 +1,101 @@
+//! A status region pinned to the bottom of the terminal.
+//!
+//! [`pin_status_region`] reserves the last lines of the terminal for status
+//! and progress text, while regular log output scrolls in the area above
+//! (using a scroll region, `DECSTBM`). Progress stays visible even during
+//! very chatty phases. While the region is pinned, spinners started with
+//! [`status_line!`](crate::status_line) draw into its first line.
+//!
+//! The region size is taken from the terminal size when pinning; pin again
+//! after the terminal was resized. Does nothing if stdout is not an
+//! [interactive](crate::capabilities::Capabilities::interactive) terminal.
+//!
+//! # Example
+//! ```rust
+//! use verbosio::pinned;
+//!
+//! pinned::pin_status_region(2).unwrap();
+//! pinned::set_status_text(1, "42/100 files");
+//! verbosio::vinfo!("this scrolls above the status region");
+//! pinned::unpin_status_region();
+//! ```
+//!
+//! # Features
+//! Requires the `"status"` feature.
+use std::io::{self, Write};
+use std::sync::Mutex;
+use crossterm::terminal;
+
+static REGION: Mutex<Option<Region>> = Mutex::new(None);
+
+struct Region {
+    /// First row of the region, 1-based.
+    top: u16,
+    lines: Vec<String>,
+}
+
+/// Reserves the bottom `lines` rows of the terminal for status text.
+///
+/// Replaces a previously pinned region. Fails if the terminal size can't be
+/// determined or is too small to keep a scrolling area.
+pub fn pin_status_region(lines: u16) -> io::Result<()> {
+    if lines == 0 || !crate::capabilities().interactive {
+        return Ok(());
+    }
+    unpin_status_region();
+    let (_, rows) = terminal::size()?;
+    if rows <= lines {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, "terminal too small for the status region"));
+    }
+    let top = rows - lines + 1;
+    let mut out = io::stdout().lock();
+    // scroll existing output up to make room, confine scrolling above the
+    // region (which homes the cursor) and go back to where output continues
+    write!(out, "{}", "\n".repeat(lines as usize))?;
+    write!(out, "\x1b7\x1b[1;{}r\x1b8\x1b[{}A", top - 1, lines)?;
+    out.flush()?;
+    *REGION.lock().unwrap_or_else(|e| e.into_inner()) = Some(Region {
+        top,
+        lines: vec![String::new(); lines as usize],
+    });
+    Ok(())
+}
+
+/// Restores normal scrolling and clears the status region.
+pub fn unpin_status_region() {
+    let Some(region) = REGION.lock().unwrap_or_else(|e| e.into_inner()).take() else {
+        return;
+    };
+    let mut out = io::stdout().lock();
+    let _ = write!(out, "\x1b7");
+    for idx in 0..region.lines.len() as u16 {
+        let _ = write!(out, "\x1b[{};1H\x1b[2K", region.top + idx);
+    }
+    let _ = write!(out, "\x1b8\x1b[r\x1b8");
+    let _ = out.flush();
+}
+
+/// Whether a status region is pinned.
+pub fn is_pinned() -> bool {
+    REGION.lock().unwrap_or_else(|e| e.into_inner()).is_some()
+}
+
+/// Sets the text of line `line` (0-based) of the status region.
+///
+/// Does nothing if no region is pinned or `line` is outside of it.
+pub fn set_status_text(line: u16, text: impl Into<String>) {
+    let mut region = REGION.lock().unwrap_or_else(|e| e.into_inner());
+    let Some(region) = region.as_mut() else {
+        return;
+    };
+    let Some(slot) = region.lines.get_mut(line as usize) else {
+        return;
+    };
+    *slot = text.into();
+    let width = terminal::size().map(|(cols, _)| cols as usize).unwrap_or(80);
+    let text: String = slot.chars().take(width).collect();
+    let mut out = io::stdout().lock();
+    // save the cursor, draw the line, and return to the scrolling area
+    let _ = write!(out, "\x1b7\x1b[{};1H\x1b[2K{}\x1b8", region.top + line, text);
+    let _ = out.flush();
+}
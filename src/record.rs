@@ -0,0 +1,185 @@
+use std::fmt::{self, Write};
+use std::time::SystemTime;
+use crate::Level;
+use crate::level::CustomLevel;
+
+/// A single log message on its way to the sinks.
+///
+/// Records are built by the logging macros after the verbosity check.
+/// `level` is `None` for untagged `verbose!` messages.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Record {
+    pub level: Option<Level>,
+    /// When the record was created.
+    pub time: SystemTime,
+    /// Where the record comes from, usually the `module_path!()` of the call site.
+    pub target: String,
+    /// Whether `target` was named in the logging macro, as in
+    /// `vinfo!(target: "net", ...)`. Named targets are always shown in
+    /// human output.
+    pub named_target: bool,
+    /// Whether the record goes to `stderr` whatever its level, as for
+    /// `verbose_err!`.
+    pub force_stderr: bool,
+    pub message: String,
+    /// Key-value context attached to the message, in insertion order.
+    pub fields: Vec<(String, String)>,
+    /// Fields set with `set_global_fields!`, shared by all records.
+    pub global_fields: Vec<(String, String)>,
+    /// Display label of the logging thread, see `set_thread_label!`, or its
+    /// name or ID, see [`show_thread`](crate::context::show_thread).
+    pub thread_label: Option<String>,
+    /// Hostname, if enabled with `context::enable_host_fields`.
+    pub hostname: Option<&'static str>,
+    /// Username, if enabled with `context::enable_host_fields`.
+    pub username: Option<&'static str>,
+    /// ID of the innermost open section, see [`span`](crate::span).
+    pub span_id: Option<u64>,
+    /// ID of the section enclosing `span_id`.
+    pub parent_span_id: Option<u64>,
+    /// ID of the run that produced the record, see [`run_id!`](crate::run_id).
+    pub run_id: &'static str,
+    /// Follow-up `help:`/`note:` lines, see [`vwarn!`](crate::vwarn).
+    pub notes: Vec<(NoteKind, String)>,
+    /// The custom level of the record, see
+    /// [`register_level`](crate::level::register_level). `level` holds its
+    /// severity.
+    pub custom_level: Option<CustomLevel>,
+    /// Whether the record reports a step that succeeded (`true`) or failed
+    /// (`false`), see [`vsuccess!`](crate::vsuccess) and
+    /// [`vfail!`](crate::vfail). Human output shows a ✔/✖ mark instead of the
+    /// level tag.
+    pub success: Option<bool>,
+    /// Call site of the logging macro, shown with
+    /// [`show_location_at`](crate::output::show_location_at).
+    pub location: Option<Location>,
+}
+
+/// A source location, as given by `file!()` and `line!()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Location {
+    pub file: &'static str,
+    pub line: u32,
+}
+
+impl Location {
+    /// The location of the caller, see `#[track_caller]`.
+    #[track_caller]
+    pub fn caller() -> Self {
+        let location = std::panic::Location::caller();
+        Location {
+            file: location.file(),
+            line: location.line(),
+        }
+    }
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.file, self.line)
+    }
+}
+
+/// The kind of a follow-up line attached to a record, as in compiler
+/// diagnostics.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NoteKind {
+    Help,
+    Note,
+}
+
+impl NoteKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NoteKind::Help => "help",
+            NoteKind::Note => "note",
+        }
+    }
+}
+
+impl Record {
+    /// Creates a record carrying the current context and global fields.
+    pub fn new(level: Option<Level>, target: &str, message: String) -> Self {
+        let host_fields = crate::context::host_fields_enabled();
+        Record {
+            level,
+            time: SystemTime::now(),
+            target: target.to_string(),
+            named_target: false,
+            force_stderr: false,
+            message,
+            fields: crate::context::fields(),
+            global_fields: crate::context::global_fields(),
+            thread_label: crate::context::thread_label().or_else(crate::context::thread_identity),
+            hostname: if host_fields { crate::context::hostname() } else { None },
+            username: if host_fields { crate::context::username() } else { None },
+            span_id: crate::span::current_span_id(),
+            parent_span_id: crate::span::parent_span_id(),
+            run_id: crate::run::run_id(),
+            notes: Vec::new(),
+            custom_level: None,
+            success: None,
+            location: None,
+        }
+    }
+
+    /// The name of the level, that of the custom level if there is one.
+    pub fn level_name(&self) -> Option<&'static str> {
+        match self.custom_level {
+            Some(custom) => Some(custom.as_str()),
+            None => self.level.map(|level| level.as_str()),
+        }
+    }
+
+    /// The target abbreviated with [`abbreviate_target`].
+    pub fn short_target(&self) -> String {
+        abbreviate_target(&self.target)
+    }
+
+    /// The message followed by its fields as `key=value` pairs.
+    ///
+    /// Global fields are only included if they are shown in human output.
+    /// Values containing whitespace are quoted.
+    pub fn message_with_fields(&self) -> String {
+        let mut out = self.message.clone();
+        let global: &[(String, String)] = if crate::context::global_fields_shown() {
+            &self.global_fields
+        } else {
+            &[]
+        };
+        for (key, value) in self.fields.iter().chain(global) {
+            if value.contains(char::is_whitespace) {
+                let _ = write!(out, " {}={:?}", key, value);
+            } else {
+                let _ = write!(out, " {}={}", key, value);
+            }
+        }
+        out
+    }
+
+    /// [`message_with_fields`](Self::message_with_fields) followed by the
+    /// notes, each on its own line as `   = help: ...`.
+    pub fn message_with_notes(&self) -> String {
+        let mut out = self.message_with_fields();
+        for (kind, text) in &self.notes {
+            let _ = write!(out, "\n   = {}: {}", kind.as_str(), text);
+        }
+        out
+    }
+}
+
+/// Shortens a module path to the initials of all but its last segment,
+/// e.g. `my_app::net::http` becomes `m::n::http`.
+pub fn abbreviate_target(target: &str) -> String {
+    let mut segments: Vec<&str> = target.split("::").collect();
+    let last = segments.pop().unwrap_or_default();
+    let mut out = String::new();
+    for segment in segments {
+        if let Some(initial) = segment.chars().next() {
+            out.push(initial);
+            out.push_str("::");
+        }
+    }
+    out.push_str(last);
+    out
+}
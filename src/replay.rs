@@ -0,0 +1,148 @@
+//! Recording of a session's output and replaying it later.
+//!
+//! While recording, every emitted record and section header is appended to
+//! a session file together with its offset from the start of the recording.
+//! [`replay`] prints the session again, optionally with the original
+//! timing, e.g. for demos or to look at a flaky CI run locally.
+//! [`read_session`] loads the entries for comparisons between runs.
+//!
+//! The file is line-based: `<micros> <tag> <text>`, where the tag is the
+//! first letter of the level (`-` for untagged messages, `S` for section
+//! headers) and backslashes and newlines in the text are escaped.
+//!
+//! # Example
+//! ```rust
+//! use verbosio::{replay, set_verbosity, vinfo, vwarn};
+//!
+//! set_verbosity!(1);
+//! # verbosio::util::set_time_mode(verbosio::util::TimeMode::Off);
+//! let path = std::env::temp_dir().join("verbosio-doc.session");
+//! replay::start_recording(&path).unwrap();
+//! vinfo!("Fetching");
+//! vwarn!("Slow mirror");
+//! replay::stop_recording();
+//!
+//! let session = replay::read_session(&path).unwrap();
+//! assert!(session[1].text.ends_with("Slow mirror"));
+//! replay::replay(&path, false).unwrap();
+//! ```
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::Path;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+use crate::Level;
+use crate::util::{escape_newlines, unescape_newlines};
+
+static RECORDER: Mutex<Option<Recorder>> = Mutex::new(None);
+
+/// Whether [`RECORDER`] is set, checked before rendering a record for it.
+static RECORDING: AtomicBool = AtomicBool::new(false);
+
+struct Recorder {
+    file: File,
+    start: Instant,
+}
+
+/// A single recorded line of output.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SessionEntry {
+    /// Time since the recording started.
+    pub offset: Duration,
+    /// Level of the record, `None` for untagged messages and sections.
+    pub level: Option<Level>,
+    /// Whether the entry is a section header.
+    pub section: bool,
+    /// The line as rendered for the terminal in human output mode.
+    pub text: String,
+}
+
+/// Starts recording all output to a new session file at `path`.
+///
+/// An existing file is truncated, and a running recording is stopped.
+pub fn start_recording<P: AsRef<Path>>(path: P) -> io::Result<()> {
+    let file = File::create(path)?;
+    *RECORDER.lock().unwrap_or_else(|e| e.into_inner()) = Some(Recorder {
+        file,
+        start: Instant::now(),
+    });
+    RECORDING.store(true, Ordering::Relaxed);
+    Ok(())
+}
+
+/// Stops the running recording, if any.
+pub fn stop_recording() {
+    RECORDER.lock().unwrap_or_else(|e| e.into_inner()).take();
+    RECORDING.store(false, Ordering::Relaxed);
+}
+
+/// Whether a recording is running.
+pub fn is_recording() -> bool {
+    RECORDING.load(Ordering::Relaxed)
+}
+
+pub(crate) fn capture(level: Option<Level>, section: bool, text: &str) {
+    let mut recorder = RECORDER.lock().unwrap_or_else(|e| e.into_inner());
+    let Some(recorder) = recorder.as_mut() else {
+        return;
+    };
+    let tag = match (section, level) {
+        (true, _) => 'S',
+        (false, None) => '-',
+        (false, Some(level)) => level.as_str().chars().next().unwrap_or('-'),
+    };
+    let line = format!("{} {} {}\n", recorder.start.elapsed().as_micros(), tag, escape_newlines(text));
+    let _ = recorder.file.write_all(line.as_bytes());
+}
+
+/// Loads the entries of a session file written by [`start_recording`].
+pub fn read_session<P: AsRef<Path>>(path: P) -> io::Result<Vec<SessionEntry>> {
+    let reader = BufReader::new(File::open(path)?);
+    let mut entries = Vec::new();
+    for line in reader.lines() {
+        let line = line?;
+        let mut parts = line.splitn(3, ' ');
+        let (Some(micros), Some(tag), text) = (parts.next(), parts.next(), parts.next()) else {
+            return Err(invalid(&line));
+        };
+        let micros: u64 = micros.parse().map_err(|_| invalid(&line))?;
+        let level = match tag {
+            "E" => Some(Level::Error),
+            "W" => Some(Level::Warn),
+            "I" => Some(Level::Info),
+            "D" => Some(Level::Debug),
+            "T" => Some(Level::Trace),
+            "-" | "S" => None,
+            _ => return Err(invalid(&line)),
+        };
+        entries.push(SessionEntry {
+            offset: Duration::from_micros(micros),
+            level,
+            section: tag == "S",
+            text: unescape_newlines(text.unwrap_or("")),
+        });
+    }
+    Ok(entries)
+}
+
+/// Prints a recorded session to the terminal.
+///
+/// With `realtime`, the original delays between the lines are reproduced;
+/// otherwise everything is printed at once. Errors go to `stderr`, as they
+/// did in the recorded run.
+pub fn replay<P: AsRef<Path>>(path: P, realtime: bool) -> io::Result<()> {
+    let started = Instant::now();
+    for entry in read_session(path)? {
+        if realtime && let Some(wait) = entry.offset.checked_sub(started.elapsed()) {
+            thread::sleep(wait);
+        }
+        crate::sink::write_terminal(entry.level == Some(Level::Error), &entry.text);
+    }
+    Ok(())
+}
+
+fn invalid(line: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, format!("invalid session line: {:?}", line))
+}
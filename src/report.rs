@@ -0,0 +1,189 @@
+//! Polished failure output for panics and errors returned from `main`.
+//!
+//! Install the panic hook once at startup with [`install`], and return
+//! [`Report`] from `main` to have top-level errors rendered with their cause
+//! chain and an optional backtrace, using the same level tags and colors as
+//! the logging macros.
+//!
+//! # Example
+//! ```rust
+//! use verbosio::report::{self, Report};
+//!
+//! fn main() -> Result<(), Report> {
+//!     report::install();
+//!     let _config = std::fs::read_to_string("Cargo.toml")?;
+//!     Ok(())
+//! }
+//! ```
+use std::backtrace::{Backtrace, BacktraceStatus};
+use std::error::Error;
+use std::fmt::{self, Write as _};
+use std::panic::{self, PanicHookInfo};
+use std::path::{Path, PathBuf};
+use crate::util::{format_level, format_section, strip_ansi};
+
+const BACKTRACE_HINT: &str = "Run with RUST_BACKTRACE=1 to display a backtrace.";
+
+/// An error report rendered with sections for its cause chain and backtrace.
+///
+/// Any `std::error::Error` converts into a `Report` via `?`. Its `Debug`
+/// output is the full report, which is what Rust prints when `main` returns
+/// `Err`.
+pub struct Report {
+    error: Box<dyn Error + Send + Sync + 'static>,
+    backtrace: Backtrace,
+}
+
+impl Report {
+    /// Wraps an error, capturing a backtrace if `RUST_BACKTRACE` enables it.
+    pub fn new<E: Error + Send + Sync + 'static>(error: E) -> Self {
+        Report {
+            error: Box::new(error),
+            backtrace: Backtrace::capture(),
+        }
+    }
+
+    /// Returns the wrapped error.
+    pub fn error(&self) -> &(dyn Error + Send + Sync + 'static) {
+        &*self.error
+    }
+
+    /// Renders the full report as it would be printed.
+    pub fn render(&self) -> String {
+        let mut out = format!("{}{}", format_level("ERROR"), self.error);
+
+        let mut causes = Vec::new();
+        let mut source = self.error.source();
+        while let Some(cause) = source {
+            causes.push(cause.to_string());
+            source = cause.source();
+        }
+        if !causes.is_empty() {
+            out.push_str(&format!("\n\n{}", format_section("Caused by")));
+            for (idx, cause) in causes.iter().enumerate() {
+                out.push_str(&format!("\n   {}: {}", idx, cause));
+            }
+        }
+
+        out.push_str(&render_backtrace(&self.backtrace));
+        out
+    }
+}
+
+impl<E: Error + Send + Sync + 'static> From<E> for Report {
+    fn from(error: E) -> Self {
+        Report::new(error)
+    }
+}
+
+impl fmt::Debug for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.render())
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.error, f)
+    }
+}
+
+/// Installs a panic hook that prints panics as verbosio reports on stderr.
+///
+/// Panics are always reported, regardless of the current verbosity.
+pub fn install() {
+    panic::set_hook(Box::new(|info| {
+        eprintln!("{}", render_panic(info, &Backtrace::capture()));
+    }));
+}
+
+/// Number of recent log lines kept for crash reports.
+const CRASH_HISTORY: usize = 200;
+
+/// Environment variables whose names contain one of these are left out of
+/// crash reports.
+const SECRET_MARKERS: &[&str] = &["KEY", "TOKEN", "SECRET", "PASSWORD", "PASSWD", "CREDENTIAL", "AUTH"];
+
+/// Like [`install`], but additionally writes a crash report file into `dir`
+/// and prints its path to stderr.
+///
+/// The report contains the panic message and location, a backtrace
+/// (captured regardless of `RUST_BACKTRACE`), a snapshot of the environment
+/// and the most recent log lines. Enables the [`history`](crate::history)
+/// ring buffer if it isn't already.
+///
+/// # Example
+/// ```rust
+/// verbosio::report::install_crash_reports(std::env::temp_dir());
+/// ```
+pub fn install_crash_reports(dir: impl Into<PathBuf>) {
+    let dir = dir.into();
+    if crate::history::history_capacity() == 0 {
+        crate::history::enable_history(CRASH_HISTORY);
+    }
+    panic::set_hook(Box::new(move |info| {
+        eprintln!("{}", render_panic(info, &Backtrace::capture()));
+        match write_crash_report(&dir, info) {
+            Ok(path) => eprintln!("\nA crash report was written to {}", path.display()),
+            Err(err) => eprintln!("\nFailed to write crash report: {}", err),
+        }
+    }));
+}
+
+fn write_crash_report(dir: &Path, info: &PanicHookInfo<'_>) -> std::io::Result<PathBuf> {
+    let mut out = String::new();
+    let _ = writeln!(out, "{}", strip_ansi(&render_panic(info, &Backtrace::force_capture())));
+
+    let _ = writeln!(out, "\n=== Environment ===");
+    let _ = writeln!(out, "   run id: {}", crate::run::run_id());
+    let _ = writeln!(out, "   args: {:?}", std::env::args().collect::<Vec<_>>());
+    if let Ok(cwd) = std::env::current_dir() {
+        let _ = writeln!(out, "   cwd: {}", cwd.display());
+    }
+    let _ = writeln!(out, "   os: {} ({})", std::env::consts::OS, std::env::consts::ARCH);
+    let _ = writeln!(out, "   verbosity: {}", crate::get_verbosity!());
+    let mut vars: Vec<(String, String)> = std::env::vars()
+        .filter(|(name, _)| {
+            let upper = name.to_ascii_uppercase();
+            !SECRET_MARKERS.iter().any(|marker| upper.contains(marker))
+        })
+        .collect();
+    vars.sort();
+    for (name, value) in vars {
+        let _ = writeln!(out, "   {}={}", name, value);
+    }
+
+    let _ = writeln!(out, "\n=== Recent log ===");
+    for line in crate::history::recent_lines() {
+        let _ = writeln!(out, "   {}", line);
+    }
+
+    std::fs::create_dir_all(dir)?;
+    let path = dir.join(format!("crash-{}.txt", crate::run::run_id()));
+    std::fs::write(&path, out)?;
+    Ok(path)
+}
+
+fn render_panic(info: &PanicHookInfo<'_>, backtrace: &Backtrace) -> String {
+    let payload = info.payload();
+    let message = payload
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("<non-string panic payload>");
+
+    let mut out = format!("{}The application panicked (crashed).", format_level("ERROR"));
+    out.push_str(&format!("\n\n{}\n   {}", format_section("Message"), message));
+    if let Some(location) = info.location() {
+        out.push_str(&format!("\n\n{}\n   {}", format_section("Location"), location));
+    }
+    out.push_str(&render_backtrace(backtrace));
+    out
+}
+
+fn render_backtrace(backtrace: &Backtrace) -> String {
+    match backtrace.status() {
+        BacktraceStatus::Captured => format!("\n\n{}\n{}", format_section("Backtrace"), backtrace),
+        _ => format!("\n\n{}", BACKTRACE_HINT),
+    }
+}
@@ -0,0 +1,83 @@
+//! Support for [`vretry!`](crate::vretry).
+use std::fmt::Display;
+use std::time::Duration;
+use crate::Level;
+
+/// Attempts made by `vretry!` unless `attempts = N` is given.
+pub const DEFAULT_ATTEMPTS: u32 = 3;
+
+/// Delay before the first retry unless `backoff = ...` is given.
+pub const DEFAULT_BACKOFF: Duration = Duration::from_secs(1);
+
+/// Runs `work` until it succeeds or `attempts` attempts have failed,
+/// doubling the delay between attempts starting at `backoff`.
+#[doc(hidden)]
+#[track_caller]
+pub fn retry<T, E: Display>(
+    lvl: u8,
+    attempts: u32,
+    backoff: Duration,
+    target: &str,
+    what: String,
+    mut work: impl FnMut() -> Result<T, E>,
+) -> Result<T, E> {
+    let attempts = attempts.max(1);
+    let mut delay = backoff;
+    let mut attempt = 1;
+    loop {
+        match work() {
+            Ok(value) => {
+                log(lvl, Level::Info, target, format_args!("{} succeeded on attempt {}/{}", what, attempt, attempts));
+                return Ok(value);
+            }
+            Err(err) if attempt == attempts => {
+                log(lvl, Level::Error, target, format_args!("{} failed after {} attempts: {}", what, attempts, err));
+                return Err(err);
+            }
+            Err(err) => {
+                log(
+                    lvl,
+                    Level::Warn,
+                    target,
+                    format_args!("{} failed (attempt {}/{}): {}, retrying in {:?}", what, attempt, attempts, err, delay),
+                );
+                std::thread::sleep(delay);
+                delay = delay.saturating_mul(2);
+                attempt += 1;
+            }
+        }
+    }
+}
+
+#[track_caller]
+fn log(lvl: u8, level: Level, target: &str, args: std::fmt::Arguments<'_>) {
+    if crate::scope::verbosity() >= lvl {
+        crate::sink::emit(Some(level), target, args);
+    }
+}
+
+/// Parses a backoff literal such as `500ms`, `2s`, `1m` or `1h`.
+///
+/// Panics on anything else; `vretry!` evaluates it in a const block, so an
+/// invalid literal is a compile error.
+#[doc(hidden)]
+pub const fn parse_backoff(text: &str) -> Duration {
+    let bytes = text.as_bytes();
+    let mut value: u64 = 0;
+    let mut idx = 0;
+    while idx < bytes.len() && bytes[idx].is_ascii_digit() {
+        value = value * 10 + (bytes[idx] - b'0') as u64;
+        idx += 1;
+    }
+    if idx == 0 {
+        panic!("invalid backoff: expected a number with a unit, e.g. 500ms, 2s, 1m or 1h");
+    }
+    let (_, unit) = bytes.split_at(idx);
+    match unit {
+        b"ms" => Duration::from_millis(value),
+        b"s" => Duration::from_secs(value),
+        b"m" => Duration::from_secs(value * 60),
+        b"h" => Duration::from_secs(value * 3600),
+        _ => panic!("invalid backoff unit: expected ms, s, m or h"),
+    }
+}
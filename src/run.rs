@@ -0,0 +1,43 @@
+//! Identification of a single run of the program.
+//!
+//! Every process gets a short random run ID (8 hex digits), generated at
+//! [`init_guarded`](crate::init_guarded) or on first use. It is carried by
+//! every [`Record`](crate::Record) for structured sinks, so users can quote
+//! one identifier when reporting issues and logs of retried runs can be told
+//! apart.
+//!
+//! # Example
+//! ```rust
+//! use verbosio::run_id;
+//!
+//! let id = run_id!();
+//! assert_eq!(id.len(), 8);
+//! assert_eq!(id, run_id!()); // stable for the whole run
+//! ```
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::time::SystemTime;
+use once_cell::sync::Lazy;
+
+static RUN_ID: Lazy<String> = Lazy::new(generate_run_id);
+
+/// Returns the ID of the current run.
+pub fn run_id() -> &'static str {
+    &RUN_ID
+}
+
+/// Logs the run ID once as `[INFO] run id: ...` if verbosity ≥ `lvl`.
+pub fn announce_run_id(lvl: u8) {
+    crate::vinfo!(@lvl lvl, "run id: {}", run_id());
+}
+
+fn generate_run_id() -> String {
+    // RandomState is randomly seeded per process, which is all the
+    // randomness needed to tell runs apart.
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u32(std::process::id());
+    if let Ok(since_epoch) = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH) {
+        hasher.write_u128(since_epoch.as_nanos());
+    }
+    format!("{:08x}", hasher.finish() as u32)
+}
@@ -0,0 +1,112 @@
+//! Temporary verbosity changes.
+//!
+//! [`push_verbosity`] and [`with_verbosity!`](crate::with_verbosity) change
+//! the global verbosity until the returned [`VerbosityGuard`] is dropped,
+//! e.g. to show every step of one suspicious phase, or to silence a noisy
+//! one. Guards nest, and the previous verbosity is restored even if the
+//! block returns early or panics.
+//!
+//! If the verbosity is changed again while a guard is alive, e.g. with the
+//! `v`/`q` keys of a running spinner or by another thread, that change wins:
+//! the guard leaves it alone instead of restoring a stale value.
+//!
+//! A verbosity can also be set for the current thread only, with
+//! [`push_thread_verbosity`] or
+//! [`with_thread_verbosity!`](crate::with_thread_verbosity), e.g. to debug one
+//! worker without turning up the output of all others. It takes the place of
+//! the global verbosity for messages logged on that thread; per-target
+//! [filter](crate::filter) directives still apply.
+//!
+//! # Example
+//! ```rust
+//! use verbosio::{get_verbosity, set_verbosity, vinfo, with_verbosity};
+//!
+//! set_verbosity!(1);
+//! with_verbosity!(3, {
+//!     vinfo!(@lvl 3, "resolved 12 packages"); // printed
+//! });
+//! assert_eq!(get_verbosity!(), 1);
+//! ```
+use std::cell::Cell;
+use std::sync::atomic::Ordering;
+
+thread_local! {
+    static THREAD_VERBOSITY: Cell<Option<u8>> = const { Cell::new(None) };
+}
+
+/// Restores the previous global verbosity when dropped.
+///
+/// Created by [`push_verbosity`] and [`with_verbosity!`](crate::with_verbosity).
+#[derive(Debug)]
+#[must_use = "the verbosity is restored when the guard is dropped"]
+pub struct VerbosityGuard {
+    previous: u8,
+    verbosity: u8,
+}
+
+impl VerbosityGuard {
+    /// The verbosity restored when the guard is dropped.
+    pub fn previous(&self) -> u8 {
+        self.previous
+    }
+}
+
+/// Sets the global verbosity to `verbosity` until the guard is dropped.
+pub fn push_verbosity(verbosity: u8) -> VerbosityGuard {
+    let previous = crate::VERBOSE.swap(verbosity, Ordering::Relaxed);
+    VerbosityGuard { previous, verbosity }
+}
+
+impl Drop for VerbosityGuard {
+    fn drop(&mut self) {
+        // only undo our own change, not one made while the guard was alive
+        let _ = crate::VERBOSE.compare_exchange(self.verbosity, self.previous, Ordering::Relaxed, Ordering::Relaxed);
+    }
+}
+
+/// Restores the current thread's previous verbosity override when dropped.
+///
+/// Created by [`push_thread_verbosity`] and
+/// [`with_thread_verbosity!`](crate::with_thread_verbosity).
+#[derive(Debug)]
+#[must_use = "the thread's verbosity is restored when the guard is dropped"]
+pub struct ThreadVerbosityGuard {
+    previous: Option<u8>,
+}
+
+/// Sets the verbosity of the current thread to `verbosity` until the guard
+/// is dropped, whatever the global verbosity.
+///
+/// # Example
+/// ```rust
+/// use verbosio::{is_enabled, set_verbosity};
+/// use verbosio::scope::push_thread_verbosity;
+///
+/// set_verbosity!(1);
+/// std::thread::spawn(|| {
+///     let _debug = push_thread_verbosity(3);
+///     assert!(is_enabled!(@lvl 3));
+/// }).join().unwrap();
+/// assert!(!is_enabled!(@lvl 3));
+/// ```
+pub fn push_thread_verbosity(verbosity: u8) -> ThreadVerbosityGuard {
+    let previous = THREAD_VERBOSITY.with(|current| current.replace(Some(verbosity)));
+    ThreadVerbosityGuard { previous }
+}
+
+impl Drop for ThreadVerbosityGuard {
+    fn drop(&mut self) {
+        THREAD_VERBOSITY.with(|current| current.set(self.previous));
+    }
+}
+
+/// The verbosity override of the current thread, if any.
+pub fn thread_verbosity() -> Option<u8> {
+    THREAD_VERBOSITY.with(Cell::get)
+}
+
+/// The verbosity messages of the current thread are checked against: its
+/// override if one is set, otherwise the global verbosity.
+pub fn verbosity() -> u8 {
+    thread_verbosity().unwrap_or_else(|| crate::get_verbosity!())
+}
@@ -0,0 +1,92 @@
+//! Orderly shutdown: stopping spinners and flushing output before exit.
+//!
+//! Bind the guard returned by [`init_guarded`] at the top of `main`. When it
+//! is dropped (including during unwinding) active spinners are stopped and
+//! all output is flushed. With [`ShutdownGuard::with_summary`] the
+//! warning/error summary is printed as well.
+//!
+//! `std::process::exit` does not run destructors, so the guard never sees
+//! it. Use [`exit`] instead, which performs the same cleanup first.
+//!
+//! # Example
+//! ```rust
+//! let _guard = verbosio::init_guarded();
+//! verbosio::vinfo!("working...");
+//! // spinners are stopped and output is flushed when `_guard` goes out of scope
+//! ```
+
+/// Runs the shutdown sequence when dropped. Created by [`init_guarded`].
+#[must_use = "the guard runs the shutdown sequence when dropped; bind it with `let _guard = ...`"]
+pub struct ShutdownGuard {
+    summary: bool,
+}
+
+impl ShutdownGuard {
+    /// Also prints the warning/error summary (see [`vsummary!`](crate::vsummary))
+    /// when the guard is dropped.
+    pub fn with_summary(mut self) -> Self {
+        self.summary = true;
+        self
+    }
+}
+
+/// Returns a guard that stops spinners and flushes output when dropped.
+///
+/// Also generates the run ID (see [`run_id!`](crate::run_id)) and starts the
+/// clock of [`TimeMode::Elapsed`](crate::util::TimeMode::Elapsed), see
+/// [`mark_start`](crate::util::mark_start).
+pub fn init_guarded() -> ShutdownGuard {
+    crate::run::run_id();
+    crate::util::mark_start();
+    ShutdownGuard { summary: false }
+}
+
+impl Drop for ShutdownGuard {
+    fn drop(&mut self) {
+        if self.summary {
+            #[cfg(feature = "status")]
+            crate::status::stop_all_spinners();
+            crate::vsummary!();
+        }
+        shutdown();
+    }
+}
+
+/// Stops spinners and flushes output, then exits with `code`.
+///
+/// Use this instead of `std::process::exit`, which skips the cleanup done
+/// by [`ShutdownGuard`].
+pub fn exit(code: i32) -> ! {
+    shutdown();
+    std::process::exit(code)
+}
+
+/// Prints an `[ERROR]` message whatever the verbosity, then exits with
+/// `code` like [`exit`]. Used by [`vfatal!`](crate::vfatal).
+#[doc(hidden)]
+#[track_caller]
+pub fn fatal(code: i32, target: &str, args: std::fmt::Arguments<'_>) -> ! {
+    // clear spinners first so the message isn't drawn over a status line
+    #[cfg(feature = "status")]
+    {
+        crate::status::stop_all_spinners();
+        crate::pinned::unpin_status_region();
+    }
+    crate::sink::emit(Some(crate::Level::Error), target, args);
+    exit(code)
+}
+
+/// Stops all active spinners, unpins the status region, removes the task
+/// footer, closes open CI groups and flushes every sink. Then archives the
+/// log file if [`on_exit_archive`](crate::archive::on_exit_archive) was called.
+pub fn shutdown() {
+    #[cfg(feature = "status")]
+    {
+        crate::status::stop_all_spinners();
+        crate::pinned::unpin_status_region();
+    }
+    crate::tasks::set_status_footer(false);
+    crate::output::close_group();
+    crate::sink::flush();
+    crate::archive::run();
+}
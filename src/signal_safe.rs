@@ -0,0 +1,97 @@
+//! A minimal error path for signal handlers, see
+//! [`verror_signal_safe!`](crate::verror_signal_safe).
+//!
+//! The regular logging pipeline allocates, takes locks and runs layers, none
+//! of which is allowed inside a signal handler, and all of which can fail or
+//! deadlock while the process is already in trouble. This path only reads
+//! the verbosity, assembles the line in a stack buffer and hands it to a
+//! single `write(2)` on `stderr`. Colors, timestamps, log files and layers
+//! are skipped.
+use std::sync::atomic::Ordering;
+
+/// Longest line written; longer messages are cut off.
+const MAX_LINE: usize = 512;
+
+const TAG: &[u8] = b"[ERROR] ";
+
+/// Writes `[ERROR] message` (followed by `number`, if any) to `stderr` if
+/// the verbosity is at least `lvl`.
+///
+/// Async-signal-safe on Unix. Elsewhere it falls back to locking `stderr`.
+#[doc(hidden)]
+pub fn error(lvl: u8, message: &'static str, number: Option<i64>) {
+    // don't initialize the verbosity from inside a signal handler
+    let verbosity = once_cell::sync::Lazy::get(&crate::VERBOSE).map_or(0, |verbose| verbose.load(Ordering::Relaxed));
+    let quiet = crate::filter::is_quiet() && lvl <= crate::levels::NORMAL;
+    if verbosity < lvl && !quiet {
+        return;
+    }
+    let mut line = Line { buf: [0; MAX_LINE], len: 0 };
+    line.push(TAG);
+    line.push(message.as_bytes());
+    if let Some(number) = number {
+        line.push(b" ");
+        line.push_number(number);
+    }
+    // keep room for the newline even if the message was cut off
+    line.len = line.len.min(MAX_LINE - 1);
+    line.push(b"\n");
+    write_stderr(&line.buf[..line.len]);
+}
+
+struct Line {
+    buf: [u8; MAX_LINE],
+    len: usize,
+}
+
+impl Line {
+    fn push(&mut self, bytes: &[u8]) {
+        let n = bytes.len().min(MAX_LINE - self.len);
+        self.buf[self.len..self.len + n].copy_from_slice(&bytes[..n]);
+        self.len += n;
+    }
+
+    fn push_number(&mut self, number: i64) {
+        let mut digits = [0u8; 20];
+        let mut pos = digits.len();
+        let mut value = number.unsigned_abs();
+        loop {
+            pos -= 1;
+            digits[pos] = b'0' + (value % 10) as u8;
+            value /= 10;
+            if value == 0 {
+                break;
+            }
+        }
+        if number < 0 {
+            self.push(b"-");
+        }
+        self.push(&digits[pos..]);
+    }
+}
+
+#[cfg(unix)]
+fn write_stderr(mut bytes: &[u8]) {
+    use std::os::raw::{c_int, c_void};
+
+    unsafe extern "C" {
+        fn write(fd: c_int, buf: *const c_void, count: usize) -> isize;
+    }
+
+    // retry partial writes and interruptions, but never loop forever
+    for _ in 0..8 {
+        if bytes.is_empty() {
+            return;
+        }
+        let written = unsafe { write(2, bytes.as_ptr().cast(), bytes.len()) };
+        if written > 0 {
+            bytes = &bytes[written as usize..];
+        }
+    }
+}
+
+#[cfg(not(unix))]
+fn write_stderr(bytes: &[u8]) {
+    use std::io::Write;
+    let _ = std::io::stderr().write_all(bytes);
+}
@@ -0,0 +1,556 @@
+//! Output destinations for log messages.
+//!
+//! Every logging macro prints to the terminal (`stdout`, or `stderr` for
+//! errors). Additionally, a log file can be registered with [`set_log_file`]
+//! to receive a copy of every message, without colors and with a timestamp
+//! (also without the `time` feature). Rotated log files are picked up again
+//! with [`reopen_log_file`], or automatically on `SIGHUP` via
+//! [`reopen_on_sighup`] (`signal` feature). With the `encrypt` feature, an
+//! [encrypted log file](crate::encrypt) can be registered as well.
+//!
+//! Several processes can share one log file: by default every record is
+//! written under an advisory lock, see [`set_file_locking`].
+//!
+//! The terminal is the default [`Sink`]. Further sinks, e.g. a GUI pane or a
+//! test collector, are registered with [`add_sink`] and receive every record
+//! that would be printed; the terminal sink can be removed with
+//! [`remove_sink`]`(SinkId::TERMINAL)`.
+//!
+//! The terminal and the log file can each use their own
+//! [`Format`](crate::format::Format), see [`set_terminal_format`] and
+//! [`set_file_format`].
+use std::fmt;
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, RwLock};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::Duration;
+use once_cell::sync::Lazy;
+use crate::Level;
+use crate::format::Format;
+use crate::level::CustomLevel;
+use crate::record::{Location, NoteKind, Record};
+use crate::output::{self, OutputMode};
+use crate::util::{format_time, strip_ansi};
+
+static LOG_FILE: Lazy<Mutex<Option<LogFile>>> = Lazy::new(|| Mutex::new(None));
+
+static TERMINAL_FORMAT: RwLock<Option<Format>> = RwLock::new(None);
+
+static FILE_FORMAT: RwLock<Format> = RwLock::new(Format::Human);
+
+static LOCK_RECORDS: AtomicBool = AtomicBool::new(true);
+
+type SharedSink = Arc<dyn Sink>;
+
+static SINKS: Lazy<RwLock<Vec<(SinkId, SharedSink)>>> =
+    Lazy::new(|| RwLock::new(vec![(SinkId::TERMINAL, Arc::new(TerminalSink) as SharedSink)]));
+
+static NEXT_SINK: AtomicU64 = AtomicU64::new(1);
+
+/// A destination for records, see [`add_sink`].
+///
+/// Sinks are called on the logging thread, after the verbosity check and
+/// the [layers](crate::layer). Section headers, status lines and summaries
+/// are not records and only go to the terminal.
+///
+/// # Example
+/// ```rust
+/// use std::sync::{Arc, Mutex};
+/// use verbosio::sink::{self, Sink, SinkId};
+/// use verbosio::{add_sink, remove_sink, set_verbosity, vinfo, Record};
+///
+/// #[derive(Default)]
+/// struct Collector(Mutex<Vec<String>>);
+///
+/// impl Sink for Collector {
+///     fn write(&self, record: &Record) {
+///         self.0.lock().unwrap().push(record.message.clone());
+///     }
+/// }
+///
+/// set_verbosity!(1);
+/// let collector = Arc::new(Collector::default());
+/// let id = add_sink!(collector.clone());
+/// remove_sink!(SinkId::TERMINAL); // only collect
+/// vinfo!("captured");
+/// assert_eq!(*collector.0.lock().unwrap(), ["captured"]);
+/// remove_sink!(id);
+/// sink::add_sink(sink::TerminalSink);
+/// ```
+pub trait Sink: Send + Sync {
+    fn write(&self, record: &Record);
+
+    /// Called by [`flush`]. Does nothing by default.
+    fn flush(&self) {}
+}
+
+impl<S: Sink + ?Sized> Sink for Arc<S> {
+    fn write(&self, record: &Record) {
+        (**self).write(record);
+    }
+
+    fn flush(&self) {
+        (**self).flush();
+    }
+}
+
+/// Identifies a registered sink, see [`remove_sink`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SinkId(u64);
+
+impl SinkId {
+    /// The default terminal sink, registered from the start.
+    pub const TERMINAL: SinkId = SinkId(0);
+}
+
+/// Registers a sink receiving every record printed from now on.
+///
+/// Records a layer sends to the log file only
+/// ([`Action::FileOnly`](crate::layer::Action::FileOnly)) skip all sinks.
+pub fn add_sink<S: Sink + 'static>(sink: S) -> SinkId {
+    let id = SinkId(NEXT_SINK.fetch_add(1, Ordering::Relaxed));
+    SINKS.write().unwrap_or_else(|e| e.into_inner()).push((id, Arc::new(sink)));
+    id
+}
+
+/// Removes a sink, returning whether it was registered.
+pub fn remove_sink(id: SinkId) -> bool {
+    let mut sinks = SINKS.write().unwrap_or_else(|e| e.into_inner());
+    let len = sinks.len();
+    sinks.retain(|(existing, _)| *existing != id);
+    sinks.len() != len
+}
+
+/// Prints records to `stdout`, or `stderr` for errors, following the
+/// [output mode](crate::output) or the [terminal format](set_terminal_format).
+///
+/// This is the default sink; registering it again after removing
+/// [`SinkId::TERMINAL`] restores terminal output under a new ID.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TerminalSink;
+
+impl Sink for TerminalSink {
+    fn write(&self, record: &Record) {
+        let mode = output::output_mode();
+        let to_stderr = record.force_stderr || output::record_to_stderr(mode, record);
+        match terminal_format() {
+            Some(format) => write_terminal(to_stderr, &format.render(record)),
+            None => write_terminal(to_stderr, &output::render_message(mode, record)),
+        }
+        if record.level == Some(Level::Error) {
+            crate::bell::error_printed();
+        }
+    }
+}
+
+/// How records are protected from interleaving with writes of other
+/// processes sharing the log file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum FileLocking {
+    /// Takes an advisory lock on the file around every record (default).
+    /// Other verbosio processes wait for it, so lines never interleave,
+    /// even for records larger than the operating system writes atomically.
+    #[default]
+    Lock,
+    /// Writes every record with a single append, without locking. Cheaper,
+    /// and sufficient on local file systems as long as records are
+    /// reasonably short.
+    Append,
+}
+
+/// Sets how writes to the log file are protected, see [`FileLocking`].
+///
+/// # Example
+/// ```rust
+/// use verbosio::sink::{self, FileLocking};
+///
+/// // a single instance writing a very chatty log
+/// sink::set_file_locking(FileLocking::Append);
+/// ```
+pub fn set_file_locking(locking: FileLocking) {
+    LOCK_RECORDS.store(locking == FileLocking::Lock, Ordering::Relaxed);
+}
+
+struct LogFile {
+    file: File,
+    path: PathBuf,
+}
+
+fn open_log_file(path: &Path) -> io::Result<File> {
+    OpenOptions::new().create(true).append(true).open(path)
+}
+
+/// Appends all subsequent log messages to the file at `path`.
+///
+/// The file is created if it does not exist. A previously registered log
+/// file is closed.
+///
+/// # Example
+/// ```rust
+/// use verbosio::sink;
+///
+/// let path = std::env::temp_dir().join("verbosio-doc.log");
+/// sink::set_log_file(&path).expect("failed to open log file");
+/// sink::clear_log_file();
+/// ```
+pub fn set_log_file<P: AsRef<Path>>(path: P) -> io::Result<()> {
+    let path = path.as_ref().to_path_buf();
+    let file = open_log_file(&path)?;
+    *LOG_FILE.lock().unwrap_or_else(|e| e.into_inner()) = Some(LogFile { file, path });
+    Ok(())
+}
+
+/// Closes the registered log file and opens it again under the same path.
+///
+/// Call this after the file was moved away by an external log rotation
+/// (e.g. `logrotate`), so new messages go to a fresh file instead of the
+/// rotated one. Does nothing if no log file is registered. If reopening
+/// fails, the old file is kept.
+pub fn reopen_log_file() -> io::Result<()> {
+    let mut log_file = LOG_FILE.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(log_file) = log_file.as_mut() {
+        let _ = log_file.file.flush();
+        log_file.file = open_log_file(&log_file.path)?;
+    }
+    Ok(())
+}
+
+/// Reopens the log file whenever the process receives `SIGHUP`.
+///
+/// This is the conventional way for daemons to cooperate with `logrotate`:
+/// after rotating, `logrotate` sends `SIGHUP` and the process continues
+/// writing to a new file under the original path. The signal is handled on
+/// a background thread; installing the handler replaces the default
+/// `SIGHUP` behavior of terminating the process.
+///
+/// # Example
+/// ```rust
+/// use verbosio::sink;
+///
+/// sink::set_log_file(std::env::temp_dir().join("verbosio-daemon.log")).unwrap();
+/// sink::reopen_on_sighup().expect("failed to install SIGHUP handler");
+/// ```
+///
+/// # Features
+/// Requires the `"signal"` feature and a Unix platform.
+#[cfg(all(feature = "signal", unix))]
+pub fn reopen_on_sighup() -> io::Result<()> {
+    use signal_hook::consts::SIGHUP;
+    use signal_hook::iterator::Signals;
+
+    let mut signals = Signals::new([SIGHUP])?;
+    std::thread::spawn(move || {
+        for _ in signals.forever() {
+            if let Err(err) = reopen_log_file() {
+                crate::verror!("failed to reopen log file: {}", err);
+            }
+        }
+    });
+    Ok(())
+}
+
+pub(crate) fn log_file_path() -> Option<PathBuf> {
+    LOG_FILE.lock().unwrap_or_else(|e| e.into_inner()).as_ref().map(|log_file| log_file.path.clone())
+}
+
+/// Closes the registered log file, if any.
+pub fn clear_log_file() {
+    *LOG_FILE.lock().unwrap_or_else(|e| e.into_inner()) = None;
+}
+
+/// Renders terminal output in `format` instead of following the output mode.
+///
+/// # Example
+/// ```rust
+/// use verbosio::format::Format;
+/// use verbosio::sink;
+///
+/// sink::set_terminal_format(Format::Logfmt);
+/// verbosio::vinfo!("ready"); // time=... level=info target=... msg=ready run_id=...
+/// sink::reset_terminal_format();
+/// ```
+pub fn set_terminal_format(format: Format) {
+    *TERMINAL_FORMAT.write().unwrap_or_else(|e| e.into_inner()) = Some(format);
+}
+
+/// Lets terminal output follow the [output mode](crate::output) again.
+pub fn reset_terminal_format() {
+    *TERMINAL_FORMAT.write().unwrap_or_else(|e| e.into_inner()) = None;
+}
+
+/// Sets the format of the log file, [`Format::Human`] by default.
+pub fn set_file_format(format: Format) {
+    *FILE_FORMAT.write().unwrap_or_else(|e| e.into_inner()) = format;
+}
+
+pub(crate) fn terminal_format() -> Option<Format> {
+    TERMINAL_FORMAT.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+fn file_format() -> Format {
+    FILE_FORMAT.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+fn has_file_output() -> bool {
+    #[cfg(feature = "encrypt")]
+    if crate::encrypt::is_active() {
+        return true;
+    }
+    LOG_FILE.lock().unwrap_or_else(|e| e.into_inner()).is_some()
+}
+
+/// Flushes the terminal streams, the registered sinks, the log files and the
+/// binary log.
+pub fn flush() {
+    let _ = io::stdout().flush();
+    let _ = io::stderr().flush();
+    let sinks = SINKS.read().unwrap_or_else(|e| e.into_inner()).clone();
+    for (_, sink) in sinks {
+        sink.flush();
+    }
+    if let Some(log_file) = LOG_FILE.lock().unwrap_or_else(|e| e.into_inner()).as_mut() {
+        let _ = log_file.file.flush();
+    }
+    crate::binlog::flush();
+    #[cfg(feature = "encrypt")]
+    crate::encrypt::flush();
+}
+
+/// Renders a message and writes it to the terminal and the log file.
+///
+/// The record passes through the registered [`layer`](crate::layer)s first.
+/// Used by the logging macros after the verbosity check; `None` is an
+/// untagged `verbose!` message. `target` is the caller's `module_path!()`.
+#[doc(hidden)]
+#[track_caller]
+pub fn emit(level: Option<Level>, target: &str, args: fmt::Arguments<'_>) {
+    dispatch(new_record(level, target, args, Some(Location::caller())));
+}
+
+/// Like [`emit`], with key-value fields attached to the record in addition
+/// to the context fields, and the source location given explicitly, for
+/// records from outside a macro call site such as the `log` bridge.
+#[doc(hidden)]
+pub fn emit_with_fields(
+    level: Option<Level>,
+    target: &str,
+    args: fmt::Arguments<'_>,
+    fields: Vec<(String, String)>,
+    location: Option<Location>,
+) {
+    let mut record = new_record(level, target, args, location);
+    record.fields.extend(fields);
+    dispatch(record);
+}
+
+/// Like [`emit`], with `help:`/`note:` lines attached to the record.
+#[doc(hidden)]
+#[track_caller]
+pub fn emit_with_notes(level: Option<Level>, target: &str, args: fmt::Arguments<'_>, notes: Vec<(NoteKind, String)>) {
+    let mut record = new_record(level, target, args, Some(Location::caller()));
+    record.notes = notes;
+    dispatch(record);
+}
+
+/// Like [`emit_with_notes`] for a target named in the macro, as in
+/// `vinfo!(target: "net", ...)`, which is shown in front of the message.
+#[doc(hidden)]
+#[track_caller]
+pub fn emit_named(level: Option<Level>, target: &str, args: fmt::Arguments<'_>, notes: Vec<(NoteKind, String)>) {
+    let mut record = new_record(level, target, args, Some(Location::caller()));
+    record.named_target = true;
+    record.notes = notes;
+    dispatch(record);
+}
+
+/// Like [`emit`] for an untagged message, but always writes to `stderr`.
+#[doc(hidden)]
+#[track_caller]
+pub fn emit_stderr(target: &str, args: fmt::Arguments<'_>) {
+    let mut record = new_record(None, target, args, Some(Location::caller()));
+    record.force_stderr = true;
+    dispatch(record);
+}
+
+/// Like [`emit`] for a step that succeeded or failed, logged as `INFO` or
+/// `ERROR` and shown with a ✔/✖ mark.
+#[doc(hidden)]
+#[track_caller]
+pub fn emit_step(success: bool, target: &str, args: fmt::Arguments<'_>) {
+    let level = if success { Level::Info } else { Level::Error };
+    let mut record = new_record(Some(level), target, args, Some(Location::caller()));
+    record.success = Some(success);
+    dispatch(record);
+}
+
+/// Like [`emit_stderr`] for the `file:line:col:` lines of a diagnostic, see
+/// [`set_editor_diagnostics`](crate::snippet::set_editor_diagnostics). Not
+/// counted in the [`tally`](crate::tally), which already counted the
+/// diagnostic itself.
+pub(crate) fn emit_editor_lines(target: &str, args: fmt::Arguments<'_>) {
+    let mut record = new_record(None, target, args, None);
+    record.force_stderr = true;
+    deliver(record, false);
+}
+
+/// Like [`emit_step`] for the result line of a status line that finished,
+/// e.g. `✔ Downloading (1.20s)` after [`vwait!`](crate::vwait). Not counted
+/// in the [`tally`](crate::tally), and not checked against the verbosity,
+/// which the status line already was.
+pub(crate) fn emit_result(success: bool, target: &str, args: fmt::Arguments<'_>) {
+    let level = if success { Level::Info } else { Level::Error };
+    let mut record = new_record(Some(level), target, args, None);
+    record.success = Some(success);
+    deliver(record, false);
+}
+
+/// Like [`emit`] for a custom level, or `INFO` if the level is not
+/// registered.
+#[doc(hidden)]
+#[track_caller]
+pub fn emit_custom(level: Option<CustomLevel>, target: &str, args: fmt::Arguments<'_>) {
+    let severity = level.map_or(Level::Info, |level| level.severity());
+    let mut record = new_record(Some(severity), target, args, Some(Location::caller()));
+    record.custom_level = level;
+    dispatch(record);
+}
+
+/// Like [`emit`] for notices of verbosio itself, such as the
+/// [`watchdog`](crate::watchdog) warning, which are left out of the
+/// [`tally`](crate::tally) so they can't change the exit code.
+#[track_caller]
+pub(crate) fn emit_uncounted(level: Option<Level>, target: &str, args: fmt::Arguments<'_>) {
+    deliver(new_record(level, target, args, Some(Location::caller())), false);
+}
+
+fn new_record(level: Option<Level>, target: &str, args: fmt::Arguments<'_>, location: Option<Location>) -> Record {
+    let mut record = Record::new(level, target, args.to_string());
+    record.location = location;
+    record
+}
+
+fn dispatch(record: Record) {
+    deliver(record, true);
+}
+
+fn deliver(mut record: Record, counted: bool) {
+    crate::util::mark_start();
+    let Some(route) = crate::layer::apply(&mut record) else {
+        return;
+    };
+    if counted {
+        crate::tally::count(record.level);
+    }
+    crate::watchdog::touch();
+    if route.terminal {
+        // clone the list so sinks may log themselves without deadlocking
+        let sinks = SINKS.read().unwrap_or_else(|e| e.into_inner()).clone();
+        for (_, sink) in sinks {
+            sink.write(&record);
+        }
+    }
+    // only rendered for the history and recordings when they are enabled
+    if crate::history::history_capacity() > 0 || crate::replay::is_recording() {
+        let human = output::render_message(OutputMode::Human, &record);
+        crate::history::push(&human);
+        crate::replay::capture(record.level, false, &human);
+    }
+    if route.file {
+        if has_file_output() {
+            write_file(&file_format().render_for_file(&record));
+        }
+        crate::binlog::write(&record);
+    }
+}
+
+/// Renders a section header and writes it to the terminal and the log file.
+#[doc(hidden)]
+pub fn emit_section(args: fmt::Arguments<'_>) {
+    let title = args.to_string();
+    crate::span::next_titled_section(&title);
+    write_section(&title);
+}
+
+pub(crate) fn write_section(title: &str) {
+    crate::watchdog::enter_section(title);
+    match terminal_format() {
+        Some(format) => {
+            if let Some(text) = format.render_section(title) {
+                write_terminal(false, &text);
+            }
+        }
+        None => write_terminal(false, &output::render_section(output::output_mode(), title)),
+    }
+    if crate::replay::is_recording() {
+        crate::replay::capture(None, true, &output::render_section(OutputMode::Human, title));
+    }
+    if let Some(text) = file_format().render_section(title) {
+        write_file(&strip_ansi(&text));
+    }
+}
+
+pub(crate) fn write_section_footer(title: &str, elapsed: Duration, failed: bool) {
+    crate::watchdog::touch();
+    match terminal_format() {
+        Some(format) => {
+            if let Some(text) = format.render_section_footer(title, elapsed, failed) {
+                write_terminal(false, &text);
+            }
+        }
+        None => write_terminal(
+            false,
+            &output::render_section_footer(output::output_mode(), title, elapsed, failed),
+        ),
+    }
+    if crate::replay::is_recording() {
+        let human = output::render_section_footer(OutputMode::Human, title, elapsed, failed);
+        crate::replay::capture(None, true, &human);
+    }
+    if let Some(text) = file_format().render_section_footer(title, elapsed, failed) {
+        write_file(&strip_ansi(&text));
+    }
+}
+
+pub(crate) fn write_terminal(to_stderr: bool, text: &str) {
+    if crate::buffer::hold(to_stderr, text) {
+        return;
+    }
+    crate::tasks::around_footer(|| write_line(to_stderr, text));
+}
+
+fn write_line(to_stderr: bool, text: &str) {
+    #[cfg(feature = "status")]
+    if crate::status::raw_mode_active() {
+        // raw mode (spinner key controls) doesn't return the carriage on \n
+        let text = text.replace('\n', "\r\n");
+        if to_stderr {
+            eprint!("{}\r\n", text);
+        } else {
+            print!("{}\r\n", text);
+        }
+        return;
+    }
+    if to_stderr {
+        eprintln!("{}", text);
+    } else {
+        println!("{}", text);
+    }
+}
+
+fn write_file(text: &str) {
+    if let Some(log_file) = LOG_FILE.lock().unwrap_or_else(|e| e.into_inner()).as_mut() {
+        // one write per record, so O_APPEND keeps records from other processes apart
+        let line = format!("{}\n", text);
+        let locked = LOCK_RECORDS.load(Ordering::Relaxed) && log_file.file.lock().is_ok();
+        let _ = log_file.file.write_all(line.as_bytes());
+        if locked {
+            let _ = log_file.file.unlock();
+        }
+    }
+    #[cfg(feature = "encrypt")]
+    crate::encrypt::write(text);
+}
@@ -0,0 +1,166 @@
+//! Source snippets with a caret underline, for [`vdiagnostic!`](crate::vdiagnostic).
+//!
+//! Tools that parse user files can point at the offending part of the input
+//! the way compilers do, without a full diagnostics framework:
+//!
+//! ```text
+//! [ERROR] expected a value
+//!   --> config.toml:2:8
+//!    |
+//!  2 | name = = "demo"
+//!    |        ^ unexpected `=`
+//! ```
+//!
+//! Editors and IDEs can't parse that, but they understand the
+//! `file:line:col: error: message` lines of compilers. With
+//! [`set_editor_diagnostics`], every diagnostic is additionally written to
+//! stderr in that format, so Vim's quickfix list, Emacs' compilation mode or
+//! an IDE problem matcher can jump straight to the location.
+//!
+//! # Example
+//! ```rust
+//! use verbosio::snippet::Snippet;
+//!
+//! let source = "[package]\nname = = \"demo\"\n";
+//! let snippet = Snippet::new("config.toml", source, 17..18).label("unexpected `=`");
+//! assert_eq!(
+//!     snippet.render(),
+//!     "  --> config.toml:2:8\n   |\n 2 | name = = \"demo\"\n   |        ^ unexpected `=`"
+//! );
+//! ```
+use std::fmt::Write as _;
+use std::ops::Range;
+use std::sync::atomic::{AtomicBool, Ordering};
+use crate::Level;
+use crate::record::NoteKind;
+
+/// Width a tab is expanded to.
+const TAB_WIDTH: usize = 4;
+
+static EDITOR_DIAGNOSTICS: AtomicBool = AtomicBool::new(false);
+
+/// Also writes every [`vdiagnostic!`](crate::vdiagnostic) to stderr as
+/// `file:line:col: severity: message`, which editors and IDEs can parse.
+///
+/// The line is written in addition to the regular output, as an untagged
+/// message on stderr that goes through the layers, sinks and log file like
+/// any other. Severities are `error`, `warning`, `info` and `note` (for debug
+/// messages); `help:`/`note:` lines follow as `file:line:col: help: ...`.
+/// Editors expect the location at the start of the line, so leave the
+/// [time mode](crate::util::TimeMode) off when they read the output.
+///
+/// # Example
+/// ```rust
+/// verbosio::snippet::set_editor_diagnostics(true);
+/// // config.toml:2:8: error: expected a value
+/// ```
+pub fn set_editor_diagnostics(enabled: bool) {
+    EDITOR_DIAGNOSTICS.store(enabled, Ordering::Relaxed);
+}
+
+/// Emits a diagnostic for [`vdiagnostic!`](crate::vdiagnostic) after the
+/// verbosity check.
+#[doc(hidden)]
+#[track_caller]
+pub fn emit(level: Level, target: &str, snippet: &Snippet<'_>, message: String, notes: Vec<(NoteKind, String)>) {
+    if EDITOR_DIAGNOSTICS.load(Ordering::Relaxed) {
+        crate::sink::emit_editor_lines(target, format_args!("{}", snippet.editor_lines(level, &message, &notes)));
+    }
+    crate::sink::emit_with_notes(Some(level), target, format_args!("{}\n{}", message, snippet.render()), notes);
+}
+
+/// A byte span in a source text, rendered as the affected line with the span
+/// underlined.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Snippet<'a> {
+    origin: &'a str,
+    source: &'a str,
+    span: Range<usize>,
+    label: Option<String>,
+}
+
+impl<'a> Snippet<'a> {
+    /// Points at the bytes `span` of `source`, which was read from `origin`
+    /// (usually a file path).
+    ///
+    /// Out-of-range spans are clamped to the source, and spans inside a
+    /// UTF-8 character are widened to whole characters.
+    pub fn new(origin: &'a str, source: &'a str, span: Range<usize>) -> Self {
+        Snippet { origin, source, span, label: None }
+    }
+
+    /// Text shown after the underline.
+    pub fn label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    /// 1-based line and column (in characters) of the start of the span.
+    pub fn location(&self) -> (usize, usize) {
+        let (start, _) = self.bounds();
+        let line_start = self.source[..start].rfind('\n').map_or(0, |idx| idx + 1);
+        let line = self.source[..start].matches('\n').count() + 1;
+        (line, self.source[line_start..start].chars().count() + 1)
+    }
+
+    /// Renders the location line, the source line and the underline.
+    ///
+    /// A span reaching past the end of its line is underlined to the end of
+    /// that line; an empty span gets a single caret.
+    pub fn render(&self) -> String {
+        let (start, end) = self.bounds();
+        let (line, column) = self.location();
+        let line_start = self.source[..start].rfind('\n').map_or(0, |idx| idx + 1);
+        let line_end = self.source[start..].find('\n').map_or(self.source.len(), |idx| start + idx);
+        let text = self.source[line_start..line_end].trim_end_matches('\r');
+
+        let gutter = line.to_string().len();
+        let pad = " ".repeat(gutter);
+        let offset = display_width(&self.source[line_start..start]);
+        let width = display_width(&self.source[start..end.min(line_end)]).max(1);
+
+        let mut out = String::new();
+        let _ = writeln!(out, "{} --> {}:{}:{}", pad, self.origin, line, column);
+        let _ = writeln!(out, "{}  |", pad);
+        let _ = writeln!(out, " {} | {}", line, text.replace('\t', &" ".repeat(TAB_WIDTH)));
+        let _ = write!(out, "{}  | {}{}", pad, " ".repeat(offset), "^".repeat(width));
+        if let Some(label) = &self.label {
+            let _ = write!(out, " {}", label);
+        }
+        out
+    }
+
+    /// Renders `file:line:col: severity: message`, followed by one such line
+    /// per note.
+    pub fn editor_lines(&self, level: Level, message: &str, notes: &[(NoteKind, String)]) -> String {
+        let (line, column) = self.location();
+        let severity = match level {
+            Level::Error => "error",
+            Level::Warn => "warning",
+            Level::Info => "info",
+            Level::Debug | Level::Trace => "note",
+        };
+        let mut out = format!("{}:{}:{}: {}: {}", self.origin, line, column, severity, message);
+        for (kind, text) in notes {
+            let _ = write!(out, "\n{}:{}:{}: {}: {}", self.origin, line, column, kind.as_str(), text);
+        }
+        out
+    }
+
+    /// The span clamped to the source and to character boundaries.
+    fn bounds(&self) -> (usize, usize) {
+        let mut start = self.span.start.min(self.source.len());
+        while !self.source.is_char_boundary(start) {
+            start -= 1;
+        }
+        let mut end = self.span.end.clamp(start, self.source.len());
+        while !self.source.is_char_boundary(end) {
+            end += 1;
+        }
+        (start, end)
+    }
+}
+
+fn display_width(text: &str) -> usize {
+    text.chars().map(|c| if c == '\t' { TAB_WIDTH } else { 1 }).sum()
+}
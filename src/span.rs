@@ -0,0 +1,214 @@
+//! Span IDs for the phase hierarchy of a run.
+//!
+//! Every section gets a unique, incrementing ID. Records carry the ID of the
+//! innermost open section and of its parent, so external tools can rebuild
+//! the phase hierarchy from a structured log stream.
+//!
+//! A plain `vsection!` ends the previous plain section at the same depth and
+//! starts a new one. Scoped sections ([`enter_span`]) nest: sections started
+//! inside them become their children and end with them. `vsection_scoped!`
+//! opens a scoped section with a header and prints a footer when it ends.
+//!
+//! Sections are tracked per thread, so worker threads don't end or nest into
+//! each other's sections: records carry the IDs of the sections open on the
+//! thread that logged them. IDs are unique across all threads.
+//!
+//! # Example
+//! ```rust
+//! use verbosio::{current_span_id, set_verbosity, vsection};
+//!
+//! set_verbosity!(1);
+//! assert_eq!(current_span_id!(), None);
+//! vsection!("Build");
+//! let build = current_span_id!().unwrap();
+//! vsection!("Test");
+//! assert!(current_span_id!().unwrap() > build);
+//! // a worker thread starts outside of any section
+//! std::thread::spawn(|| assert_eq!(current_span_id!(), None)).join().unwrap();
+//! ```
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Open spans of all threads, see [`any_open`].
+static OPEN_SPANS: AtomicUsize = AtomicUsize::new(0);
+
+thread_local! {
+    static STACK: RefCell<Stack> = const { RefCell::new(Stack(Vec::new())) };
+}
+
+/// Open spans of a thread, innermost last.
+struct Stack(Vec<Span>);
+
+impl Stack {
+    fn push(&mut self, span: Span) {
+        OPEN_SPANS.fetch_add(1, Ordering::Relaxed);
+        self.0.push(span);
+    }
+
+    /// Ends the spans from `pos` on.
+    fn end_from(&mut self, pos: usize) {
+        OPEN_SPANS.fetch_sub(self.0.len() - pos, Ordering::Relaxed);
+        for span in self.0.drain(pos..) {
+            crate::timeline::end(span.phase);
+        }
+    }
+}
+
+impl Drop for Stack {
+    // sections still open when their thread exits no longer count
+    fn drop(&mut self) {
+        OPEN_SPANS.fetch_sub(self.0.len(), Ordering::Relaxed);
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Span {
+    id: u64,
+    scoped: bool,
+    /// Timeline phase of a plain section, see [`timeline`](crate::timeline).
+    phase: Option<u64>,
+}
+
+/// Ends the span it was created for, and all spans opened inside it, when
+/// dropped on the thread that opened it.
+#[must_use = "the span ends when the guard is dropped"]
+pub struct SpanGuard {
+    id: u64,
+}
+
+impl SpanGuard {
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+}
+
+impl Drop for SpanGuard {
+    fn drop(&mut self) {
+        // the stack is gone if the guard outlives a thread-local being destroyed
+        let _ = STACK.try_with(|stack| {
+            let mut stack = stack.borrow_mut();
+            if let Some(pos) = stack.0.iter().position(|span| span.id == self.id) {
+                stack.end_from(pos);
+            }
+        });
+    }
+}
+
+/// Opens a nested span that lasts until the returned guard is dropped.
+pub fn enter_span() -> SpanGuard {
+    let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+    STACK.with(|stack| stack.borrow_mut().push(Span { id, scoped: true, phase: None }));
+    SpanGuard { id }
+}
+
+/// Starts the span of a plain section, replacing the previous plain section
+/// at the same depth. Returns the new ID.
+///
+/// Called by `vsection!` even if the header is not printed, so IDs don't
+/// depend on verbosity.
+#[doc(hidden)]
+pub fn next_section() -> u64 {
+    start_section(None)
+}
+
+/// Like [`next_section`] for a printed section, which is also recorded on
+/// the [`timeline`](crate::timeline).
+pub(crate) fn next_titled_section(title: &str) -> u64 {
+    start_section(crate::timeline::begin(title, crate::timeline::Category::Section))
+}
+
+fn start_section(phase: Option<u64>) -> u64 {
+    let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+    STACK.with(|stack| {
+        let mut stack = stack.borrow_mut();
+        if stack.0.last().is_some_and(|span| !span.scoped) {
+            let last = stack.0.len() - 1;
+            stack.end_from(last);
+        }
+        stack.push(Span { id, scoped: false, phase });
+    });
+    id
+}
+
+/// ID of the innermost span open on the current thread, if any.
+pub fn current_span_id() -> Option<u64> {
+    STACK.try_with(|stack| stack.borrow().0.last().map(|span| span.id)).ok().flatten()
+}
+
+/// ID of the span enclosing the innermost open span of the current thread,
+/// if any.
+pub fn parent_span_id() -> Option<u64> {
+    STACK
+        .try_with(|stack| {
+            let stack = stack.borrow();
+            stack.0.len().checked_sub(2).map(|idx| stack.0[idx].id)
+        })
+        .ok()
+        .flatten()
+}
+
+/// Whether a span is open on any thread.
+pub(crate) fn any_open() -> bool {
+    OPEN_SPANS.load(Ordering::Relaxed) > 0
+}
+
+/// A scoped section opened by [`vsection_scoped!`](crate::vsection_scoped).
+///
+/// When dropped, prints a footer with the elapsed time (if the header was
+/// printed) and ends the section's span. A guard dropped while the thread
+/// is panicking marks the section as failed.
+#[must_use = "the section ends when the guard is dropped"]
+pub struct SectionGuard {
+    title: String,
+    start: Instant,
+    printed: bool,
+    _span: SpanGuard,
+    #[cfg(feature = "otel")]
+    _otel: crate::otel::SpanGuard,
+    phase: Option<u64>,
+    /// Dropped after the footer is written, flushing the whole section.
+    _buffer: Option<crate::buffer::BufferGuard>,
+}
+
+impl SectionGuard {
+    /// Opens a scoped section, printing its header if `print` is set.
+    #[doc(hidden)]
+    pub fn enter(print: bool, title: String) -> Self {
+        let span = enter_span();
+        let buffer = crate::buffer::buffered_sections().then(crate::buffer::buffer_output);
+        if print {
+            crate::sink::write_section(&title);
+        }
+        SectionGuard {
+            #[cfg(feature = "otel")]
+            _otel: crate::otel::start_span(title.clone()),
+            phase: crate::timeline::begin(&title, crate::timeline::Category::Section),
+            title,
+            start: Instant::now(),
+            printed: print,
+            _span: span,
+            _buffer: buffer,
+        }
+    }
+
+    pub fn id(&self) -> u64 {
+        self._span.id()
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.start.elapsed()
+    }
+}
+
+impl Drop for SectionGuard {
+    fn drop(&mut self) {
+        if self.printed {
+            crate::sink::write_section_footer(&self.title, self.start.elapsed(), std::thread::panicking());
+            crate::bell::finished(self.start.elapsed());
+        }
+        crate::timeline::end(self.phase);
+    }
+}
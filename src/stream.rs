@@ -0,0 +1,163 @@
+//! Progress reporting for async streams.
+//!
+//! [`VerbosioStreamExt::vprogress`] wraps a
+//! [`Stream`](futures_core::Stream) and reports how many items it has
+//! yielded so far: as a status line counting up on interactive terminals
+//! (`status` feature), otherwise as a log line every few seconds. When the
+//! stream ends, the status line is replaced by a summary like
+//! `✔ processing events: 1204 items (3.52s)`.
+//!
+//! # Example
+//! ```rust
+//! use futures_core::Stream;
+//! use verbosio::stream::VerbosioStreamExt;
+//!
+//! fn watch<S: Stream<Item = u32>>(events: S) -> impl Stream<Item = u32> {
+//!     events.vprogress(1, "processing events")
+//! }
+//! ```
+//!
+//! # Features
+//! Requires the `"async"` feature.
+use std::pin::Pin;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+use futures_core::Stream;
+
+/// How often the item count is logged when no status line is shown.
+const LOG_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Adds [`vprogress`](VerbosioStreamExt::vprogress) to every stream.
+pub trait VerbosioStreamExt: Stream + Sized {
+    /// Reports the progress of this stream if the verbosity is at least
+    /// `lvl`. Below that, items are passed through untouched.
+    fn vprogress(self, lvl: u8, message: impl Into<String>) -> Progress<Self>;
+}
+
+impl<S: Stream> VerbosioStreamExt for S {
+    fn vprogress(self, lvl: u8, message: impl Into<String>) -> Progress<Self> {
+        let shown = crate::filter::level_enabled(module_path!(), Some(crate::Level::Info), lvl);
+        Progress {
+            stream: Box::pin(self),
+            message: shown.then(|| message.into()),
+            ticker: None,
+        }
+    }
+}
+
+/// A stream reporting its progress, see [`VerbosioStreamExt::vprogress`].
+#[must_use = "streams do nothing unless polled"]
+pub struct Progress<S> {
+    stream: Pin<Box<S>>,
+    /// Set until the first poll if progress is shown.
+    message: Option<String>,
+    ticker: Option<Ticker>,
+}
+
+struct Ticker {
+    message: String,
+    count: Arc<AtomicU64>,
+    start: Instant,
+    last_log: Instant,
+    #[cfg(feature = "status")]
+    spinner: Option<crate::status::SpinnerHandle>,
+}
+
+impl Ticker {
+    fn start(message: String) -> Self {
+        let count = Arc::new(AtomicU64::new(0));
+        #[cfg(feature = "status")]
+        let spinner = {
+            let redraws = crate::capabilities().interactive
+                && crate::output::output_mode() == crate::output::OutputMode::Human;
+            redraws.then(|| {
+                let message = message.clone();
+                let count = count.clone();
+                crate::status::start_spinner(move || format!("{} ({})", message, count.load(Ordering::Relaxed)))
+            })
+        };
+        let now = Instant::now();
+        Ticker {
+            message,
+            count,
+            start: now,
+            last_log: now,
+            #[cfg(feature = "status")]
+            spinner,
+        }
+    }
+
+    fn tick(&mut self) {
+        let count = self.count.fetch_add(1, Ordering::Relaxed) + 1;
+        #[cfg(feature = "status")]
+        if self.spinner.is_some() {
+            return;
+        }
+        if self.last_log.elapsed() >= LOG_INTERVAL {
+            self.last_log = Instant::now();
+            crate::sink::emit_with_fields(
+                Some(crate::Level::Info),
+                module_path!(),
+                format_args!("{}: {} items so far", self.message, count),
+                Vec::new(),
+                None,
+            );
+        }
+    }
+
+    /// Removes the status line, if one is shown.
+    fn stop(&mut self) {
+        #[cfg(feature = "status")]
+        if let Some(spinner) = self.spinner.take() {
+            spinner.stop();
+        }
+    }
+
+    fn finish(mut self) {
+        self.stop();
+        let elapsed = self.start.elapsed();
+        let count = self.count.load(Ordering::Relaxed);
+        crate::sink::emit_result(true, module_path!(), format_args!("{}: {} items ({:.2?})", self.message, count, elapsed));
+        crate::bell::finished(elapsed);
+    }
+}
+
+impl<S: Stream> Stream for Progress<S> {
+    type Item = S::Item;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<S::Item>> {
+        if let Some(message) = self.message.take() {
+            self.ticker = Some(Ticker::start(message));
+        }
+        let item = self.stream.as_mut().poll_next(cx);
+        match &item {
+            Poll::Ready(Some(_)) => {
+                if let Some(ticker) = self.ticker.as_mut() {
+                    ticker.tick();
+                }
+            }
+            Poll::Ready(None) => {
+                if let Some(ticker) = self.ticker.take() {
+                    ticker.finish();
+                }
+            }
+            Poll::Pending => {}
+        }
+        item
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.stream.size_hint()
+    }
+}
+
+impl<S> Drop for Progress<S> {
+    fn drop(&mut self) {
+        // a stream dropped before its end gets no summary
+        if let Some(ticker) = self.ticker.as_mut() {
+            ticker.stop();
+        }
+    }
+}
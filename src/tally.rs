@@ -0,0 +1,106 @@
+//! Counts of emitted warnings and errors.
+//!
+//! Every `[WARN]` and `[ERROR]` record that passes the verbosity check is
+//! counted, so applications can branch on the counts and print a summary
+//! with [`vsummary!`](crate::vsummary) at the end of a run.
+//!
+//! # Example
+//! ```rust
+//! use verbosio::{set_verbosity, vwarn};
+//! use verbosio::tally::warning_count;
+//!
+//! set_verbosity!(1);
+//! vwarn!("deprecated option");
+//! assert_eq!(warning_count(), 1);
+//! ```
+use std::sync::atomic::{AtomicUsize, Ordering};
+use crate::Level;
+
+static WARNINGS: AtomicUsize = AtomicUsize::new(0);
+static ERRORS: AtomicUsize = AtomicUsize::new(0);
+
+/// Number of warnings emitted so far.
+pub fn warning_count() -> usize {
+    WARNINGS.load(Ordering::Relaxed)
+}
+
+/// Number of errors emitted so far.
+pub fn error_count() -> usize {
+    ERRORS.load(Ordering::Relaxed)
+}
+
+/// Process exit code derived from the emitted severities.
+///
+/// | Emitted             | Code |
+/// |---------------------|------|
+/// | nothing notable     | `0`  |
+/// | warnings, no errors | `1`  |
+/// | errors              | `2`  |
+///
+/// # Example
+/// ```rust
+/// assert_eq!(verbosio::exit_code(), 0);
+/// ```
+pub fn exit_code() -> i32 {
+    if error_count() > 0 {
+        2
+    } else if warning_count() > 0 {
+        1
+    } else {
+        0
+    }
+}
+
+/// Resets both counts to zero.
+pub fn reset_counts() {
+    WARNINGS.store(0, Ordering::Relaxed);
+    ERRORS.store(0, Ordering::Relaxed);
+}
+
+/// Counts a record of `level`, if it is a warning or error.
+pub(crate) fn count(level: Option<Level>) {
+    match level {
+        Some(Level::Warn) => WARNINGS.fetch_add(1, Ordering::Relaxed),
+        Some(Level::Error) => ERRORS.fetch_add(1, Ordering::Relaxed),
+        _ => 0,
+    };
+}
+
+/// Renders the end-of-run summary, e.g. `finished with 3 warnings, 1 error`.
+pub fn summary() -> String {
+    let warnings = warning_count();
+    let errors = error_count();
+    let text = match (warnings, errors) {
+        (0, 0) => "finished without warnings or errors".to_string(),
+        (w, 0) => format!("finished with {}", plural(w, "warning")),
+        (0, e) => format!("finished with {}", plural(e, "error")),
+        (w, e) => format!("finished with {}, {}", plural(w, "warning"), plural(e, "error")),
+    };
+    color_summary(text, warnings, errors)
+}
+
+fn plural(count: usize, noun: &str) -> String {
+    if count == 1 {
+        format!("1 {}", noun)
+    } else {
+        format!("{} {}s", count, noun)
+    }
+}
+
+#[cfg(feature = "color")]
+fn color_summary(text: String, warnings: usize, errors: usize) -> String {
+    use crate::color::paint;
+    let theme = crate::color::theme();
+    if errors > 0 {
+        paint(&text, theme.error)
+    } else if warnings > 0 {
+        paint(&text, theme.warn)
+    } else {
+        paint(&text, theme.success)
+    }
+}
+
+#[cfg(not(feature = "color"))]
+fn color_summary(text: String, _warnings: usize, _errors: usize) -> String {
+    text
+}
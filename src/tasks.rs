@@ -0,0 +1,148 @@
+//! Live output of many concurrent tasks, each line prefixed with its task.
+//!
+//! A task is registered with [`start_task`] on the thread doing its work.
+//! Messages from that thread are prefixed with the task label (as with
+//! `set_thread_label!`), and [`TaskGuard::pipe_lines`] forwards the output
+//! of a subprocess the same way. With [`set_status_footer`], the bottom
+//! terminal line shows which tasks are still running, e.g.
+//! `currently running: serde, clap, tokio`.
+//!
+//! As an alternative that prints each task as one block when it finishes,
+//! see [`buffer`](crate::buffer).
+//!
+//! # Example
+//! ```rust
+//! use verbosio::{set_verbosity, tasks, vinfo};
+//!
+//! set_verbosity!(1);
+//! tasks::set_status_footer(true);
+//! let workers: Vec<_> = ["serde", "clap"]
+//!     .into_iter()
+//!     .map(|name| {
+//!         std::thread::spawn(move || {
+//!             let task = tasks::start_task(name);
+//!             vinfo!("compiling"); // serde | [INFO] compiling
+//!             task.pipe_lines("warning: unused import\n".as_bytes());
+//!         })
+//!     })
+//!     .collect();
+//! for worker in workers {
+//!     worker.join().unwrap();
+//! }
+//! # tasks::set_status_footer(false);
+//! ```
+use std::io::{self, BufRead, Write};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use crate::output::{self, OutputMode};
+
+/// Labels shown in the footer before collapsing the rest into `+N more`.
+const FOOTER_LABELS: usize = 4;
+
+static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+
+static FOOTER: AtomicBool = AtomicBool::new(false);
+
+/// Running tasks in start order, also serializing footer redraws.
+static RUNNING: Mutex<Vec<(u64, String)>> = Mutex::new(Vec::new());
+
+/// A running task. Ends the task and restores the thread label when dropped.
+#[must_use = "the task ends when the guard is dropped"]
+pub struct TaskGuard {
+    id: u64,
+    previous_label: Option<String>,
+}
+
+/// Starts a task labelled `label` on the current thread.
+pub fn start_task(label: impl Into<String>) -> TaskGuard {
+    let label = label.into();
+    let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+    let previous_label = crate::context::thread_label();
+    crate::context::set_thread_label(label.clone());
+    let mut running = RUNNING.lock().unwrap_or_else(|e| e.into_inner());
+    running.push((id, label));
+    if footer_active() {
+        clear_footer();
+        draw_footer(&running);
+    }
+    TaskGuard { id, previous_label }
+}
+
+/// Shows the running tasks on the last terminal line.
+///
+/// Only takes effect in human output mode when stdout is a terminal. Don't
+/// combine it with `status_line!` spinners, which use the same line.
+pub fn set_status_footer(enabled: bool) {
+    let running = RUNNING.lock().unwrap_or_else(|e| e.into_inner());
+    if !enabled && footer_active() {
+        clear_footer();
+    }
+    FOOTER.store(enabled, Ordering::Relaxed);
+    if footer_active() {
+        draw_footer(&running);
+    }
+}
+
+impl TaskGuard {
+    /// Emits every line read from `reader` as a message of this task, e.g.
+    /// the stdout of a subprocess. Returns once the reader is exhausted.
+    pub fn pipe_lines<R: BufRead>(&self, reader: R) {
+        for line in reader.lines().map_while(Result::ok) {
+            crate::verbose!("{}", line);
+        }
+    }
+}
+
+impl Drop for TaskGuard {
+    fn drop(&mut self) {
+        match self.previous_label.take() {
+            Some(label) => crate::context::set_thread_label(label),
+            None => crate::context::clear_thread_label(),
+        }
+        let mut running = RUNNING.lock().unwrap_or_else(|e| e.into_inner());
+        running.retain(|(id, _)| *id != self.id);
+        if footer_active() {
+            clear_footer();
+            draw_footer(&running);
+        }
+    }
+}
+
+fn footer_active() -> bool {
+    FOOTER.load(Ordering::Relaxed)
+        && output::output_mode() == OutputMode::Human
+        && crate::capabilities().interactive
+}
+
+/// Runs `write` with the footer removed, then draws it again below.
+pub(crate) fn around_footer(write: impl FnOnce()) {
+    if !footer_active() {
+        write();
+        return;
+    }
+    let running = RUNNING.lock().unwrap_or_else(|e| e.into_inner());
+    clear_footer();
+    write();
+    draw_footer(&running);
+}
+
+fn clear_footer() {
+    let mut stdout = io::stdout();
+    let _ = write!(stdout, "\r\x1b[2K");
+    let _ = stdout.flush();
+}
+
+fn draw_footer(running: &[(u64, String)]) {
+    if running.is_empty() {
+        return;
+    }
+    let mut labels: Vec<&str> = running.iter().take(FOOTER_LABELS).map(|(_, label)| label.as_str()).collect();
+    let more = running.len().saturating_sub(FOOTER_LABELS);
+    let more = format!("+{} more", more);
+    if running.len() > FOOTER_LABELS {
+        labels.push(&more);
+    }
+    let mut stdout = io::stdout();
+    let _ = write!(stdout, "currently running: {}", labels.join(", "));
+    let _ = stdout.flush();
+}
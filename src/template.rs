@@ -0,0 +1,295 @@
+//! Placeholders of line format templates, checked at compile time.
+//!
+//! A template describes the layout of a log line, e.g.
+//! `"{time} {level:>7} {target} {msg}"`. Placeholders are written in braces
+//! and may carry an alignment and width after a colon (`<`, `>` or `^`
+//! followed by digits, or just digits). Literal braces are written `{{` and
+//! `}}`.
+//!
+//! [`format_template!`](crate::format_template) validates a template while
+//! compiling, so a typo like `{lvl}` is a build error rather than a
+//! surprise on the first message. [`set_format!`](crate::set_format) does
+//! the same and prints all terminal output in that layout; templates read at
+//! runtime, e.g. from a config file, are checked by [`Template::parse`].
+//!
+//! | Placeholder | Content                                        |
+//! |-------------|------------------------------------------------|
+//! | `{time}`    | Timestamp of the record                        |
+//! | `{level}`   | Level name, e.g. `WARN`, colored as in the theme |
+//! | `{target}`  | Module path of the call site                   |
+//! | `{msg}`     | The message, followed by `help:`/`note:` lines |
+//! | `{fields}`  | Context fields as `key=value` pairs            |
+//! | `{thread}`  | Thread label, name or ID                       |
+//! | `{span}`    | ID of the innermost section                    |
+//! | `{run_id}`  | ID of the current run                          |
+//! | `{host}`    | Hostname, if host fields are enabled           |
+//! | `{user}`    | Username, if host fields are enabled           |
+//! | `{location}`| Call site as `file:line`                       |
+use std::borrow::Cow;
+use std::fmt::Write as _;
+use crate::record::Record;
+
+/// Names that may appear in a template placeholder.
+pub const PLACEHOLDERS: &[&str] = &[
+    "time", "level", "target", "msg", "fields", "thread", "span", "run_id", "host", "user", "location",
+];
+
+/// Why a template is invalid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TemplateError {
+    /// A placeholder name not listed in [`PLACEHOLDERS`].
+    UnknownPlaceholder,
+    /// A `{` without a matching `}`.
+    Unclosed,
+    /// A single `}` outside a placeholder.
+    Unmatched,
+    /// A format spec other than an alignment and width.
+    InvalidSpec,
+}
+
+impl TemplateError {
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            TemplateError::UnknownPlaceholder => "unknown placeholder in format template",
+            TemplateError::Unclosed => "unclosed `{` in format template",
+            TemplateError::Unmatched => "unmatched `}` in format template, use `}}` for a literal brace",
+            TemplateError::InvalidSpec => "invalid format spec in format template, expected e.g. `:>7`",
+        }
+    }
+}
+
+impl std::fmt::Display for TemplateError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::error::Error for TemplateError {}
+
+/// Checks `template`, usable in const contexts.
+///
+/// # Example
+/// ```rust
+/// use verbosio::template::{check, TemplateError};
+///
+/// assert_eq!(check("{time} {level:>7} {msg}"), Ok(()));
+/// assert_eq!(check("{lvl} {msg}"), Err(TemplateError::UnknownPlaceholder));
+/// ```
+pub const fn check(template: &str) -> Result<(), TemplateError> {
+    let bytes = template.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'{' if i + 1 < bytes.len() && bytes[i + 1] == b'{' => i += 2,
+            b'}' if i + 1 < bytes.len() && bytes[i + 1] == b'}' => i += 2,
+            b'}' => return Err(TemplateError::Unmatched),
+            b'{' => {
+                let start = i + 1;
+                let mut end = start;
+                while end < bytes.len() && bytes[end] != b'}' && bytes[end] != b':' {
+                    end += 1;
+                }
+                if end == bytes.len() {
+                    return Err(TemplateError::Unclosed);
+                }
+                if !is_placeholder(bytes, start, end) {
+                    return Err(TemplateError::UnknownPlaceholder);
+                }
+                if bytes[end] == b':' {
+                    let mut spec = end + 1;
+                    if spec < bytes.len() && matches!(bytes[spec], b'<' | b'>' | b'^') {
+                        spec += 1;
+                    }
+                    let digits = spec;
+                    while spec < bytes.len() && bytes[spec].is_ascii_digit() {
+                        spec += 1;
+                    }
+                    if spec == bytes.len() {
+                        return Err(TemplateError::Unclosed);
+                    }
+                    if spec == digits || bytes[spec] != b'}' {
+                        return Err(TemplateError::InvalidSpec);
+                    }
+                    end = spec;
+                }
+                i = end + 1;
+            }
+            _ => i += 1,
+        }
+    }
+    Ok(())
+}
+
+/// Panics (at compile time, in a const context) if `template` is invalid.
+#[doc(hidden)]
+pub const fn validate(template: &str) {
+    if let Err(err) = check(template) {
+        panic!("{}", err.as_str());
+    }
+}
+
+const fn is_placeholder(bytes: &[u8], start: usize, end: usize) -> bool {
+    let mut p = 0;
+    while p < PLACEHOLDERS.len() {
+        let name = PLACEHOLDERS[p].as_bytes();
+        if name.len() == end - start {
+            let mut j = 0;
+            while j < name.len() && name[j] == bytes[start + j] {
+                j += 1;
+            }
+            if j == name.len() {
+                return true;
+            }
+        }
+        p += 1;
+    }
+    false
+}
+
+/// A validated template, see the [module docs](self).
+///
+/// # Example
+/// ```rust
+/// use verbosio::format::Format;
+/// use verbosio::template::Template;
+/// use verbosio::{sink, Level, Record};
+///
+/// let template = Template::parse("{level:>5} | {msg}").unwrap();
+/// let record = Record::new(Some(Level::Info), "my_app", "ready".to_string());
+/// assert_eq!(verbosio::strip_ansi(&template.render(&record)), " INFO | ready");
+/// sink::set_file_format(Format::Template(template));
+/// # sink::set_file_format(Format::Human);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Template {
+    source: Cow<'static, str>,
+    parts: Vec<Part>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Part {
+    Literal(String),
+    Placeholder { name: &'static str, align: Align, width: usize },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Align {
+    Left,
+    Right,
+    Center,
+}
+
+impl Template {
+    /// Checks and parses `template`.
+    pub fn parse(template: impl Into<Cow<'static, str>>) -> Result<Template, TemplateError> {
+        let source = template.into();
+        check(&source)?;
+        let mut parts = Vec::new();
+        let mut literal = String::new();
+        let mut rest: &str = &source;
+        while let Some(c) = rest.chars().next() {
+            if rest.starts_with("{{") || rest.starts_with("}}") {
+                literal.push(c);
+                rest = &rest[2..];
+            } else if c == '{' {
+                // `check` guarantees a closing brace and a known name
+                let end = rest.find('}').unwrap_or(rest.len() - 1);
+                let (name, spec) = rest[1..end].split_once(':').unwrap_or((&rest[1..end], ""));
+                let name = PLACEHOLDERS.iter().find(|known| **known == name).copied().unwrap_or("msg");
+                let (align, width) = match spec.chars().next() {
+                    Some('<') => (Align::Left, &spec[1..]),
+                    Some('>') => (Align::Right, &spec[1..]),
+                    Some('^') => (Align::Center, &spec[1..]),
+                    _ => (Align::Left, spec),
+                };
+                if !literal.is_empty() {
+                    parts.push(Part::Literal(std::mem::take(&mut literal)));
+                }
+                parts.push(Part::Placeholder {
+                    name,
+                    align,
+                    width: width.parse().unwrap_or(0),
+                });
+                rest = &rest[end + 1..];
+            } else {
+                literal.push(c);
+                rest = &rest[c.len_utf8()..];
+            }
+        }
+        if !literal.is_empty() {
+            parts.push(Part::Literal(literal));
+        }
+        Ok(Template { source, parts })
+    }
+
+    /// The template as it was written.
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+
+    /// Renders `record` in this layout. The level is colored if colors are
+    /// enabled; `help:`/`note:` lines follow on their own lines.
+    pub fn render(&self, record: &Record) -> String {
+        let mut out = String::new();
+        for part in &self.parts {
+            match part {
+                Part::Literal(text) => out.push_str(text),
+                Part::Placeholder { name, align, width } => {
+                    let value = placeholder_value(name, record);
+                    let padded = match align {
+                        Align::Left => format!("{:<width$}", value, width = width),
+                        Align::Right => format!("{:>width$}", value, width = width),
+                        Align::Center => format!("{:^width$}", value, width = width),
+                    };
+                    if *name == "level" {
+                        out.push_str(&paint_level(&padded, record));
+                    } else {
+                        out.push_str(&padded);
+                    }
+                }
+            }
+        }
+        for (kind, text) in &record.notes {
+            out.push('\n');
+            out.push_str(&crate::util::format_note(kind.as_str(), text));
+        }
+        out
+    }
+}
+
+fn placeholder_value(name: &str, record: &Record) -> String {
+    match name {
+        "time" => crate::util::format_record_time(record.time),
+        "level" => record.level_name().unwrap_or_default().to_string(),
+        "target" => record.target.clone(),
+        "msg" => record.message.clone(),
+        "fields" => {
+            let mut fields = String::new();
+            for (key, value) in &record.fields {
+                let _ = write!(fields, "{}{}={}", if fields.is_empty() { "" } else { " " }, key, value);
+            }
+            fields
+        }
+        "thread" => record.thread_label.clone().unwrap_or_default(),
+        "span" => record.span_id.map(|id| id.to_string()).unwrap_or_default(),
+        "run_id" => record.run_id.to_string(),
+        "host" => record.hostname.unwrap_or_default().to_string(),
+        "user" => record.username.unwrap_or_default().to_string(),
+        "location" => record.location.map(|location| location.to_string()).unwrap_or_default(),
+        _ => String::new(),
+    }
+}
+
+#[cfg(feature = "color")]
+fn paint_level(padded: &str, record: &Record) -> String {
+    match (record.custom_level, record.level) {
+        (Some(custom), _) => crate::color::paint(padded, custom.color()),
+        (None, Some(level)) => crate::color::paint(padded, crate::color::theme().level(level)),
+        (None, None) => padded.to_string(),
+    }
+}
+
+#[cfg(not(feature = "color"))]
+fn paint_level(padded: &str, _record: &Record) -> String {
+    padded.to_string()
+}
@@ -0,0 +1,214 @@
+//! Timeline export of a run's phases in Chrome tracing format.
+//!
+//! While recording is enabled with [`enable_timeline`], the start and end of
+//! every printed `vsection!`, every `vsection_scoped!` section, every
+//! [`vtime!`](crate::vtime) block and every `#[instrument]`ed function are
+//! collected. [`export_trace`] writes them as a JSON trace that can be
+//! opened in `about://tracing` or [Perfetto](https://ui.perfetto.dev),
+//! showing the phases of each thread as a flame graph.
+//!
+//! Phases still running at export time end at the time of the export.
+//!
+//! # Example
+//! ```rust
+//! use verbosio::{set_verbosity, timeline, vsection, vtime};
+//!
+//! set_verbosity!(1);
+//! timeline::enable_timeline();
+//! vsection!("Build");
+//! vtime!("Compiling", { /* ... */ });
+//!
+//! let path = std::env::temp_dir().join("verbosio-doc-trace.json");
+//! timeline::export_trace(&path).unwrap();
+//! ```
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::Instant;
+use once_cell::sync::Lazy;
+use crate::format::json_string;
+
+static RECORDING: AtomicBool = AtomicBool::new(false);
+
+static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+
+static NEXT_THREAD: AtomicU64 = AtomicU64::new(1);
+
+/// Reference point of all timestamps.
+static START: Lazy<Instant> = Lazy::new(Instant::now);
+
+static TIMELINE: Mutex<Timeline> = Mutex::new(Timeline {
+    open: None,
+    done: Vec::new(),
+    threads: Vec::new(),
+});
+
+thread_local! {
+    /// Trace thread ID of the current thread, 0 until it records a phase.
+    static THREAD: Cell<u64> = const { Cell::new(0) };
+}
+
+/// What kind of phase an event stands for, shown as its category.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Category {
+    Section,
+    Timer,
+    Function,
+}
+
+impl Category {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Category::Section => "section",
+            Category::Timer => "vtime",
+            Category::Function => "function",
+        }
+    }
+}
+
+struct Timeline {
+    /// Phases that have started but not ended, by ID.
+    open: Option<HashMap<u64, Event>>,
+    done: Vec<Event>,
+    /// Trace thread IDs and the names of their threads.
+    threads: Vec<(u64, String)>,
+}
+
+struct Event {
+    name: String,
+    category: Category,
+    thread: u64,
+    start_micros: u64,
+    duration_micros: u64,
+}
+
+/// Starts collecting phases for [`export_trace`].
+pub fn enable_timeline() {
+    Lazy::force(&START);
+    RECORDING.store(true, Ordering::Relaxed);
+}
+
+/// Stops collecting phases. Phases collected so far are kept.
+pub fn disable_timeline() {
+    RECORDING.store(false, Ordering::Relaxed);
+}
+
+/// Discards all collected phases.
+pub fn clear_timeline() {
+    let mut timeline = TIMELINE.lock().unwrap_or_else(|e| e.into_inner());
+    timeline.open = None;
+    timeline.done.clear();
+}
+
+/// Writes the collected phases to `path` in Chrome tracing format.
+///
+/// An existing file is truncated.
+pub fn export_trace<P: AsRef<Path>>(path: P) -> io::Result<()> {
+    let mut out = BufWriter::new(File::create(path)?);
+    write_trace(&mut out)?;
+    out.flush()
+}
+
+/// Writes the collected phases to `out` in Chrome tracing format.
+pub fn write_trace<W: Write>(mut out: W) -> io::Result<()> {
+    let now = micros();
+    let timeline = TIMELINE.lock().unwrap_or_else(|e| e.into_inner());
+    let pid = std::process::id();
+    let done = timeline.done.iter().map(|event| (event, event.duration_micros));
+    let open = timeline
+        .open
+        .iter()
+        .flat_map(|open| open.values())
+        .map(|event| (event, now.saturating_sub(event.start_micros)));
+    let mut events: Vec<(&Event, u64)> = done.chain(open).collect();
+    events.sort_by_key(|(event, _)| event.start_micros);
+
+    write!(out, "{{\"traceEvents\":[")?;
+    let mut first = true;
+    for (thread, name) in &timeline.threads {
+        if !first {
+            write!(out, ",")?;
+        }
+        first = false;
+        write!(
+            out,
+            "\n{{\"name\":\"thread_name\",\"ph\":\"M\",\"pid\":{},\"tid\":{},\"args\":{{\"name\":{}}}}}",
+            pid, thread, json_string(name)
+        )?;
+    }
+    for (event, duration) in events {
+        if !first {
+            write!(out, ",")?;
+        }
+        first = false;
+        write!(
+            out,
+            "\n{{\"name\":{},\"cat\":\"{}\",\"ph\":\"X\",\"ts\":{},\"dur\":{},\"pid\":{},\"tid\":{}}}",
+            json_string(&event.name),
+            event.category.as_str(),
+            event.start_micros,
+            duration,
+            pid,
+            event.thread
+        )?;
+    }
+    write!(
+        out,
+        "\n],\"displayTimeUnit\":\"ms\",\"otherData\":{{\"run_id\":{}}}}}",
+        json_string(crate::run::run_id())
+    )?;
+    writeln!(out)
+}
+
+/// Records the start of a phase, returning its ID if recording is enabled.
+pub(crate) fn begin(name: &str, category: Category) -> Option<u64> {
+    if !RECORDING.load(Ordering::Relaxed) {
+        return None;
+    }
+    let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+    let start_micros = micros();
+    let mut timeline = TIMELINE.lock().unwrap_or_else(|e| e.into_inner());
+    let thread = thread_id(&mut timeline);
+    timeline.open.get_or_insert_with(HashMap::new).insert(id, Event {
+        name: name.to_string(),
+        category,
+        thread,
+        start_micros,
+        duration_micros: 0,
+    });
+    Some(id)
+}
+
+/// Records the end of the phase `id` returned by [`begin`].
+pub(crate) fn end(id: Option<u64>) {
+    let Some(id) = id else {
+        return;
+    };
+    let now = micros();
+    let mut timeline = TIMELINE.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(mut event) = timeline.open.as_mut().and_then(|open| open.remove(&id)) {
+        event.duration_micros = now.saturating_sub(event.start_micros);
+        timeline.done.push(event);
+    }
+}
+
+fn thread_id(timeline: &mut Timeline) -> u64 {
+    THREAD.with(|thread| {
+        if thread.get() == 0 {
+            let id = NEXT_THREAD.fetch_add(1, Ordering::Relaxed);
+            let current = std::thread::current();
+            let name = current.name().map_or_else(|| format!("thread {}", id), str::to_string);
+            timeline.threads.push((id, name));
+            thread.set(id);
+        }
+        thread.get()
+    })
+}
+
+fn micros() -> u64 {
+    START.elapsed().as_micros() as u64
+}
@@ -0,0 +1,160 @@
+//! Timing of labelled phases, used by [`vtime!`](crate::vtime).
+//!
+//! With [`escalate_slow_phases`], phases that run longer than expected
+//! raise the verbosity while they are still running, surfacing diagnostics
+//! exactly when something is unexpectedly slow.
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Once;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// How often the watcher thread checks running phases.
+const WATCH_INTERVAL: Duration = Duration::from_millis(50);
+
+static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+
+static ESCALATION: Mutex<Option<Escalation>> = Mutex::new(None);
+
+static WATCHED: Mutex<Vec<Watched>> = Mutex::new(Vec::new());
+
+static WATCHER: Once = Once::new();
+
+#[derive(Debug, Clone, Copy)]
+struct Escalation {
+    after: Duration,
+    by: u8,
+}
+
+struct Watched {
+    id: u64,
+    label: String,
+    start: Instant,
+    /// Verbosity before and after escalating, once escalated.
+    escalated: Option<(u8, u8)>,
+}
+
+/// Raises the verbosity by `by` while a timed phase runs longer than `after`.
+///
+/// The escalation is undone when the phase ends, unless the verbosity has
+/// been changed in the meantime. A notice is logged when a phase escalates.
+///
+/// # Example
+/// ```rust
+/// use std::time::Duration;
+/// use verbosio::{set_verbosity, vtime};
+///
+/// set_verbosity!(1);
+/// verbosio::timer::escalate_slow_phases(Duration::from_secs(30), 2);
+/// vtime!("Downloading", { /* detail at @lvl 3 shows up after 30s */ });
+/// ```
+pub fn escalate_slow_phases(after: Duration, by: u8) {
+    *ESCALATION.lock().unwrap_or_else(|e| e.into_inner()) = Some(Escalation { after, by });
+    WATCHER.call_once(|| {
+        thread::spawn(watch);
+    });
+}
+
+/// Turns off escalation for phases started from now on.
+pub fn disable_escalation() {
+    *ESCALATION.lock().unwrap_or_else(|e| e.into_inner()) = None;
+}
+
+fn watch() {
+    loop {
+        thread::sleep(WATCH_INTERVAL);
+        let Some(escalation) = *ESCALATION.lock().unwrap_or_else(|e| e.into_inner()) else {
+            continue;
+        };
+        let mut notices = Vec::new();
+        {
+            let mut watched = WATCHED.lock().unwrap_or_else(|e| e.into_inner());
+            for phase in watched.iter_mut() {
+                if phase.escalated.is_none() && phase.start.elapsed() >= escalation.after {
+                    let before = crate::get_verbosity!();
+                    let after = before.saturating_add(escalation.by);
+                    crate::set_verbosity!(after);
+                    phase.escalated = Some((before, after));
+                    notices.push((phase.label.clone(), after));
+                }
+            }
+        }
+        for (label, verbosity) in notices {
+            crate::vinfo!(
+                "{} is taking longer than {:.0?}, raising verbosity to {}",
+                label, escalation.after, verbosity
+            );
+        }
+    }
+}
+
+/// Measures the time since it was started.
+///
+/// With the `otel` feature enabled, the timer also owns an OpenTelemetry
+/// span that ends when the timer is dropped.
+pub struct Timer {
+    id: u64,
+    label: String,
+    start: Instant,
+    #[cfg(feature = "otel")]
+    _span: crate::otel::SpanGuard,
+    phase: Option<u64>,
+}
+
+impl Timer {
+    /// Starts a timer for the phase `label`.
+    pub fn start(label: impl Into<String>) -> Self {
+        Timer::start_phase(label.into(), crate::timeline::Category::Timer)
+    }
+
+    /// Starts a timer for an `#[instrument]`ed function.
+    #[doc(hidden)]
+    pub fn start_function(name: &str) -> Self {
+        Timer::start_phase(name.to_string(), crate::timeline::Category::Function)
+    }
+
+    fn start_phase(label: String, category: crate::timeline::Category) -> Self {
+        let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+        let start = Instant::now();
+        if ESCALATION.lock().unwrap_or_else(|e| e.into_inner()).is_some() {
+            WATCHED.lock().unwrap_or_else(|e| e.into_inner()).push(Watched {
+                id,
+                label: label.clone(),
+                start,
+                escalated: None,
+            });
+        }
+        Timer {
+            id,
+            #[cfg(feature = "otel")]
+            _span: crate::otel::start_span(label.clone()),
+            phase: crate::timeline::begin(&label, category),
+            label,
+            start,
+        }
+    }
+
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.start.elapsed()
+    }
+}
+
+impl Drop for Timer {
+    fn drop(&mut self) {
+        crate::timeline::end(self.phase);
+        let mut watched = WATCHED.lock().unwrap_or_else(|e| e.into_inner());
+        let Some(pos) = watched.iter().position(|phase| phase.id == self.id) else {
+            return;
+        };
+        // leave the verbosity alone if someone changed it while escalated
+        if let Some((before, after)) = watched.remove(pos).escalated
+            && crate::get_verbosity!() == after
+        {
+            crate::set_verbosity!(before);
+        }
+    }
+}
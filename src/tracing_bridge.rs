@@ -0,0 +1,113 @@
+//! Bridge from [`tracing`](https://crates.io/crates/tracing) to verbosio.
+//!
+//! [`VerbosioLayer`] is a `tracing-subscriber` layer that renders events of
+//! `tracing`-instrumented libraries with verbosio's level tags, formatting
+//! and sinks, so verbosio can stay the single human-facing output of an
+//! application with tracing-based dependencies.
+//!
+//! Events are gated on the global verbosity like records of the
+//! [`log` bridge](crate::log_bridge): `ERROR`, `WARN` and `INFO` need
+//! verbosity ≥ 1, `DEBUG` ≥ 3 and `TRACE` ≥ 4. The names of the spans an
+//! event happened in are prepended to its message (`fetch:parse: message`),
+//! and its fields become record fields.
+//!
+//! # Example
+//! ```rust
+//! use tracing_subscriber::layer::SubscriberExt;
+//! use verbosio::set_verbosity;
+//! use verbosio::tracing_bridge::VerbosioLayer;
+//!
+//! let subscriber = tracing_subscriber::registry().with(VerbosioLayer::new());
+//! tracing::subscriber::with_default(subscriber, || {
+//!     set_verbosity!(1);
+//!     let _span = tracing::info_span!("fetch").entered();
+//!     tracing::info!(bytes = 512, "downloaded"); // [INFO] fetch: downloaded bytes=512
+//!     tracing::debug!("needs verbosity 3");
+//! });
+//! ```
+//!
+//! # Features
+//! Requires the `"tracing"` feature.
+use std::fmt::{self, Write as _};
+use tracing_core::field::{Field, Visit};
+use tracing_core::{Event, Subscriber};
+use tracing_subscriber::layer::{Context, Layer};
+use tracing_subscriber::registry::LookupSpan;
+use crate::{Level, levels};
+use crate::record::Location;
+
+/// Prints `tracing` events through verbosio, see the [module docs](self).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct VerbosioLayer {
+    _private: (),
+}
+
+impl VerbosioLayer {
+    pub fn new() -> Self {
+        VerbosioLayer::default()
+    }
+}
+
+/// The verbosio level and minimum verbosity of a `tracing` level.
+fn map_level(level: &tracing_core::Level) -> (Level, u8) {
+    match *level {
+        tracing_core::Level::ERROR => (Level::Error, levels::NORMAL),
+        tracing_core::Level::WARN => (Level::Warn, levels::NORMAL),
+        tracing_core::Level::INFO => (Level::Info, levels::NORMAL),
+        tracing_core::Level::DEBUG => (Level::Debug, levels::DEBUG),
+        _ => (Level::Trace, levels::TRACE),
+    }
+}
+
+impl<S> Layer<S> for VerbosioLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        let metadata = event.metadata();
+        let (level, verbosity) = map_level(metadata.level());
+        if !crate::filter::level_enabled(metadata.target(), Some(level), verbosity) {
+            return;
+        }
+        let mut visitor = Fields::default();
+        event.record(&mut visitor);
+
+        let mut message = String::new();
+        if let Some(scope) = ctx.event_scope(event) {
+            for span in scope.from_root() {
+                let _ = write!(message, "{}:", span.name());
+            }
+            if !message.is_empty() {
+                message.push(' ');
+            }
+        }
+        message.push_str(&visitor.message);
+        let location = metadata.file().zip(metadata.line()).map(|(file, line)| Location { file, line });
+        crate::sink::emit_with_fields(Some(level), metadata.target(), format_args!("{}", message), visitor.fields, location);
+    }
+}
+
+/// Collects the `message` and the other fields of an event.
+#[derive(Default)]
+struct Fields {
+    message: String,
+    fields: Vec<(String, String)>,
+}
+
+impl Visit for Fields {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message = value.to_string();
+        } else {
+            self.fields.push((field.name().to_string(), value.to_string()));
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "message" {
+            self.message = format!("{:?}", value);
+        } else {
+            self.fields.push((field.name().to_string(), format!("{:?}", value)));
+        }
+    }
+}
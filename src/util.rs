@@ -1,11 +1,17 @@
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::OnceLock;
+use std::time::{Instant, SystemTime};
+
 #[cfg(feature = "color")]
 pub fn format_level(level: &str) -> String {
-    use colored::*;
+    use crate::color::paint;
+    let theme = crate::color::theme();
     match level {
-        "INFO" => "[INFO] ".blue().bold().to_string(),
-        "WARN" => "[WARN] ".yellow().bold().to_string(),
-        "DEBUG" => "[DEBUG] ".yellow().bold().to_string(),
-        "ERROR" => "[ERROR] ".red().bold().to_string(),
+        "INFO" => paint("[INFO] ", theme.info),
+        "WARN" => paint("[WARN] ", theme.warn),
+        "DEBUG" => paint("[DEBUG] ", theme.debug),
+        "TRACE" => paint("[TRACE] ", theme.trace),
+        "ERROR" => paint("[ERROR] ", theme.error),
         _ => level.to_string(),
     }
 }
@@ -15,14 +21,418 @@ pub fn format_level(level: &str) -> String {
     format!("[{}]", level)
 }
 
+/// Formats the tag of a custom level, e.g. `[NOTICE] `.
+#[cfg(feature = "color")]
+pub fn format_custom_level(level: crate::level::CustomLevel) -> String {
+    crate::color::paint(&format!("[{}] ", level), level.color())
+}
+
+#[cfg(not(feature = "color"))]
+pub fn format_custom_level(level: crate::level::CustomLevel) -> String {
+    format!("[{}] ", level)
+}
+
+
+#[cfg(feature = "color")]
+pub fn format_section(title: &str) -> String {
+    crate::color::bold(&format!("=== {} ===", title))
+}
+
+#[cfg(not(feature = "color"))]
+pub fn format_section(title: &str) -> String {
+    format!("=== {} ===", title)
+}
+
+#[cfg(feature = "color")]
+pub fn format_section_footer(title: &str, elapsed: std::time::Duration, failed: bool) -> String {
+    use crate::color::paint;
+    let theme = crate::color::theme();
+    if failed {
+        paint(&format!("=== {} failed after {:.2?} ===", title, elapsed), theme.error)
+    } else {
+        paint(&format!("=== {} done in {:.2?} ===", title, elapsed), theme.success)
+    }
+}
+
+#[cfg(not(feature = "color"))]
+pub fn format_section_footer(title: &str, elapsed: std::time::Duration, failed: bool) -> String {
+    if failed {
+        format!("=== {} failed after {:.2?} ===", title, elapsed)
+    } else {
+        format!("=== {} done in {:.2?} ===", title, elapsed)
+    }
+}
+
+/// Formats the prefix of a message with a named target, e.g. `net: `.
+#[cfg(feature = "color")]
+pub fn format_target(target: &str) -> String {
+    match crate::color::target_color(target) {
+        Some(color) => format!("{} ", crate::color::paint(&format!("{}:", target), color)),
+        None => format!("{}: ", target),
+    }
+}
+
+#[cfg(not(feature = "color"))]
+pub fn format_target(target: &str) -> String {
+    format!("{}: ", target)
+}
+
+/// Formats the source location of a message, e.g. `src/net.rs:42`.
+#[cfg(feature = "color")]
+pub fn format_location(location: &str) -> String {
+    crate::color::dim(location)
+}
+
+#[cfg(not(feature = "color"))]
+pub fn format_location(location: &str) -> String {
+    location.to_string()
+}
+
+/// Formats a `help:`/`note:` line following a message, like rustc does.
+#[cfg(feature = "color")]
+pub fn format_note(kind: &str, text: &str) -> String {
+    use crate::color::{bold, paint};
+    format!("   {} {} {}", paint("=", crate::color::theme().accent), bold(&format!("{}:", kind)), text)
+}
+
+#[cfg(not(feature = "color"))]
+pub fn format_note(kind: &str, text: &str) -> String {
+    format!("   = {}: {}", kind, text)
+}
+
+/// What the timestamp in front of human output shows, see
+/// [`set_time_mode!`](crate::set_time_mode).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+pub enum TimeMode {
+    /// Wall-clock time, e.g. `[2025-07-14 09:26:53]`; the default with the
+    /// `time` feature, formatted as set with [`set_time_format`]. Without
+    /// the feature, an RFC 3339 UTC timestamp.
+    Wallclock,
+    /// Time since [`mark_start`] or the first message, e.g. `[+12.032s]`.
+    Elapsed,
+    /// No timestamp; the default without the `time` feature.
+    Off,
+}
+
+impl std::fmt::Display for TimeMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            TimeMode::Wallclock => "wallclock",
+            TimeMode::Elapsed => "elapsed",
+            TimeMode::Off => "off",
+        })
+    }
+}
+
+impl std::str::FromStr for TimeMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "wallclock" => Ok(TimeMode::Wallclock),
+            "elapsed" => Ok(TimeMode::Elapsed),
+            "off" => Ok(TimeMode::Off),
+            _ => Err(format!("invalid time mode '{}', expected wallclock, elapsed or off", s)),
+        }
+    }
+}
+
+const UNSET: u8 = u8::MAX;
+
+static TIME_MODE: AtomicU8 = AtomicU8::new(UNSET);
 
 #[cfg(feature = "time")]
+static TIME_FORMAT: std::sync::RwLock<TimeFormat> = std::sync::RwLock::new(TimeFormat::DEFAULT);
+
+/// Reference point of [`TimeMode::Elapsed`], see [`mark_start`].
+static START: OnceLock<Instant> = OnceLock::new();
+
+/// Makes [`TimeMode::Elapsed`] count from now, unless it already started
+/// counting.
+///
+/// Without a call, it counts from the first message that was emitted.
+/// [`init_guarded`](crate::init_guarded) calls it, so it usually counts from
+/// the start of `main`.
+///
+/// # Example
+/// ```rust
+/// use verbosio::{set_time_mode, set_verbosity, vinfo};
+///
+/// verbosio::mark_start();
+/// set_verbosity!(1);
+/// set_time_mode!(Elapsed);
+/// vinfo!("ready"); // [INFO] [+0.004s] ready
+/// # set_time_mode!(Off);
+/// ```
+pub fn mark_start() {
+    START.get_or_init(Instant::now);
+}
+
+/// Sets what the timestamp in front of human output shows.
+pub fn set_time_mode(mode: TimeMode) {
+    let value = match mode {
+        TimeMode::Wallclock => 0,
+        TimeMode::Elapsed => 1,
+        TimeMode::Off => 2,
+    };
+    TIME_MODE.store(value, Ordering::Relaxed);
+}
+
+/// Returns the current time mode.
+pub fn time_mode() -> TimeMode {
+    match TIME_MODE.load(Ordering::Relaxed) {
+        0 => TimeMode::Wallclock,
+        1 => TimeMode::Elapsed,
+        2 => TimeMode::Off,
+        _ if cfg!(feature = "time") => TimeMode::Wallclock,
+        _ => TimeMode::Off,
+    }
+}
+
+/// Formats the timestamp prefix of a message logged now, e.g.
+/// `[2025-07-14 09:26:53] `, or nothing in [`TimeMode::Off`].
 pub fn format_time() -> String {
-    let time  = chrono::Local::now().format("%Y-%m-%d %H:%M:%S");
-    format!("[{}] ", time)
+    format_time_at(SystemTime::now())
+}
+
+/// Like [`format_time`] for a message logged at `time`.
+pub fn format_time_at(time: SystemTime) -> String {
+    match time_mode() {
+        TimeMode::Wallclock => format!("[{}] ", format_wallclock(time)),
+        TimeMode::Elapsed => format!("[{}] ", format_elapsed(time)),
+        TimeMode::Off => String::new(),
+    }
+}
+
+/// Formats the time of a record for the `{time}` placeholder of a
+/// [template](crate::template), following the [`TimeMode`]. A template
+/// asks for the time explicitly, so [`TimeMode::Off`] shows wall-clock time.
+pub fn format_record_time(time: SystemTime) -> String {
+    match time_mode() {
+        TimeMode::Elapsed => format_elapsed(time),
+        TimeMode::Wallclock | TimeMode::Off => format_wallclock(time),
+    }
+}
+
+/// How wall-clock timestamps are written, see [`set_time_format`].
+///
+/// # Features
+/// Requires the `"time"` feature.
+#[cfg(feature = "time")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TimeFormat {
+    pattern: std::borrow::Cow<'static, str>,
+    utc: bool,
+    precision: u8,
+}
+
+#[cfg(feature = "time")]
+impl TimeFormat {
+    /// `%Y-%m-%d %H:%M:%S` in local time, without fractional seconds.
+    pub const DEFAULT: TimeFormat = TimeFormat {
+        pattern: std::borrow::Cow::Borrowed("%Y-%m-%d %H:%M:%S"),
+        utc: false,
+        precision: 0,
+    };
+
+    /// The default format with the strftime pattern `pattern`, see
+    /// [`chrono::format::strftime`].
+    pub fn new(pattern: impl Into<std::borrow::Cow<'static, str>>) -> Self {
+        TimeFormat {
+            pattern: pattern.into(),
+            ..TimeFormat::DEFAULT
+        }
+    }
+
+    /// Writes UTC instead of local time.
+    pub fn utc(mut self, utc: bool) -> Self {
+        self.utc = utc;
+        self
+    }
+
+    /// Appends `digits` (at most 9) fractional digits to the seconds
+    /// (`%S` or `%T`) of the pattern, e.g. `09:26:53.589` for 3.
+    /// [`set_time_format`] rejects a pattern without seconds then.
+    pub fn precision(mut self, digits: u8) -> Self {
+        self.precision = digits.min(9);
+        self
+    }
+
+    fn format(&self, time: SystemTime) -> String {
+        use chrono::format::{Item, StrftimeItems};
+        let fraction = (self.precision > 0).then(|| {
+            let nanos = time.duration_since(std::time::UNIX_EPOCH).unwrap_or_default().subsec_nanos();
+            let digits = usize::from(self.precision);
+            format!(".{:0digits$}", nanos / 10u32.pow(9 - u32::from(self.precision)), digits = digits)
+        });
+        // the fraction follows every seconds field, however it was written
+        let items: Vec<Item<'_>> = StrftimeItems::new(&self.pattern)
+            .flat_map(|item| {
+                let fraction = fraction.as_deref().filter(|_| is_seconds(&item));
+                std::iter::once(item).chain(fraction.map(|fraction| Item::OwnedLiteral(fraction.into())))
+            })
+            .collect();
+        if self.utc {
+            chrono::DateTime::<chrono::Utc>::from(time).format_with_items(items.iter()).to_string()
+        } else {
+            chrono::DateTime::<chrono::Local>::from(time).format_with_items(items.iter()).to_string()
+        }
+    }
+
+    fn has_seconds(&self) -> bool {
+        chrono::format::StrftimeItems::new(&self.pattern).any(|item| is_seconds(&item))
+    }
+}
+
+#[cfg(feature = "time")]
+fn is_seconds(item: &chrono::format::Item<'_>) -> bool {
+    use chrono::format::{Item, Numeric};
+    matches!(item, Item::Numeric(Numeric::Second, _))
+}
+
+#[cfg(feature = "time")]
+impl Default for TimeFormat {
+    fn default() -> Self {
+        TimeFormat::DEFAULT
+    }
+}
+
+/// Sets how wall-clock timestamps are written, in human output and in the
+/// `{time}` placeholder of [templates](crate::template). Log files keep
+/// RFC 3339 timestamps.
+///
+/// Fails if the pattern is not a valid strftime pattern, or has no seconds
+/// (`%S` or `%T`) to add the [precision](TimeFormat::precision) to.
+///
+/// # Example
+/// ```rust
+/// use verbosio::util::{set_time_format, TimeFormat};
+/// use verbosio::{set_verbosity, vinfo};
+///
+/// set_verbosity!(1);
+/// set_time_format(TimeFormat::new("%H:%M:%S").utc(true).precision(3)).unwrap();
+/// vinfo!("ready"); // [INFO] [09:26:53.589] ready
+/// assert!(set_time_format(TimeFormat::new("%Q")).is_err());
+/// assert!(set_time_format(TimeFormat::new("%H:%M").precision(3)).is_err());
+/// # set_time_format(TimeFormat::DEFAULT).unwrap();
+/// ```
+///
+/// # Features
+/// Requires the `"time"` feature.
+#[cfg(feature = "time")]
+pub fn set_time_format(format: TimeFormat) -> Result<(), String> {
+    use chrono::format::{Item, StrftimeItems};
+    if StrftimeItems::new(&format.pattern).any(|item| matches!(item, Item::Error)) {
+        return Err(format!("invalid time format {:?}", format.pattern));
+    }
+    if format.precision > 0 && !format.has_seconds() {
+        return Err(format!("time format {:?} has no seconds for the fractional digits", format.pattern));
+    }
+    *TIME_FORMAT.write().unwrap_or_else(|e| e.into_inner()) = format;
+    Ok(())
+}
+
+/// Returns the current wall-clock time format.
+///
+/// # Features
+/// Requires the `"time"` feature.
+#[cfg(feature = "time")]
+pub fn time_format() -> TimeFormat {
+    TIME_FORMAT.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+#[cfg(feature = "time")]
+fn format_wallclock(time: SystemTime) -> String {
+    TIME_FORMAT.read().unwrap_or_else(|e| e.into_inner()).format(time)
 }
 
 #[cfg(not(feature = "time"))]
-pub fn format_time() -> String {
-    "".to_string()
-}
\ No newline at end of file
+fn format_wallclock(time: SystemTime) -> String {
+    format_rfc3339(time)
+}
+
+/// Formats the time from [`mark_start`] to `time`, e.g. `+12.032s`.
+///
+/// Measured on the monotonic clock, so changes of the system clock don't
+/// affect it; only the short time since `time` is taken from the system
+/// clock.
+fn format_elapsed(time: SystemTime) -> String {
+    let start = START.get_or_init(Instant::now);
+    let age = SystemTime::now().duration_since(time).unwrap_or_default();
+    let elapsed = start.elapsed().saturating_sub(age);
+    format!("+{}.{:03}s", elapsed.as_secs(), elapsed.subsec_millis())
+}
+
+/// Formats `time` as an RFC 3339 UTC timestamp with milliseconds,
+/// e.g. `2025-07-14T09:26:53.589Z`. Available without the `time` feature.
+pub fn format_rfc3339(time: std::time::SystemTime) -> String {
+    let since_epoch = time.duration_since(std::time::UNIX_EPOCH).unwrap_or_default();
+    let secs = since_epoch.as_secs();
+    let (hour, minute, second) = ((secs / 3600) % 24, (secs / 60) % 60, secs % 60);
+
+    // civil date from days since 1970-01-01 (Howard Hinnant's algorithm)
+    let days = (secs / 86_400) as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+        year, month, day, hour, minute, second, since_epoch.subsec_millis()
+    )
+}
+
+/// Removes ANSI escape sequences (colors, cursor movement) from `text`.
+pub fn strip_ansi(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\u{1b}' {
+            out.push(c);
+            continue;
+        }
+        if chars.peek() == Some(&'[') {
+            chars.next();
+            // parameters and intermediates, up to the final byte
+            for c in chars.by_ref() {
+                if ('\u{40}'..='\u{7e}').contains(&c) {
+                    break;
+                }
+            }
+        } else {
+            chars.next();
+        }
+    }
+    out
+}
+
+/// Escapes backslashes and newlines, so `text` fits on a single line.
+pub(crate) fn escape_newlines(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('\n', "\\n")
+}
+
+/// Reverses [`escape_newlines`].
+pub(crate) fn unescape_newlines(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            match chars.next() {
+                Some('n') => out.push('\n'),
+                Some(other) => out.push(other),
+                None => out.push('\\'),
+            }
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
@@ -0,0 +1,370 @@
+//! Pretty-printing of JSON logs written with [`Format::Json`](crate::format::Format::Json).
+//!
+//! [`view`] reads JSON lines and prints them the way they would have looked
+//! on the terminal, with their original timestamps, optionally filtered by
+//! level, target and time range. See the `view_log` example for a small
+//! command line viewer.
+//!
+//! # Example
+//! ```rust
+//! use verbosio::viewer::{self, ViewFilter};
+//! use verbosio::Level;
+//!
+//! let log = r#"{"timestamp":"2025-07-14T09:26:53.589Z","level":"info","target":"app","message":"started","run_id":"1a2b3c4d"}
+//! {"timestamp":"2025-07-14T09:26:54.102Z","level":"warn","target":"app::net","message":"slow mirror","fields":{"host":"a"},"run_id":"1a2b3c4d"}"#;
+//!
+//! let filter = ViewFilter { level: Some(Level::Warn), ..ViewFilter::default() };
+//! let mut out = Vec::new();
+//! viewer::view(log.as_bytes(), &mut out, &filter).unwrap();
+//! let out = verbosio::strip_ansi(&String::from_utf8(out).unwrap());
+//! assert!(out.starts_with("[WARN]"));
+//! assert!(out.ends_with("[2025-07-14T09:26:54.102Z] app::net: slow mirror host=a\n"));
+//! ```
+use std::io::{self, BufRead, Write};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use crate::Level;
+use crate::record::{NoteKind, Record};
+use crate::util::{format_level, format_note, format_section};
+
+/// Which entries [`view`] prints. All criteria are optional.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ViewFilter {
+    /// Least severe level shown, e.g. `Warn` shows warnings and errors.
+    /// Untagged messages count as `Info`.
+    pub level: Option<Level>,
+    /// Only targets starting with this module path.
+    pub target: Option<String>,
+    /// Only entries at or after this time.
+    pub since: Option<SystemTime>,
+    /// Only entries before this time.
+    pub until: Option<SystemTime>,
+}
+
+/// An entry of a JSON log.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogEntry {
+    pub time: Option<SystemTime>,
+    pub level: Option<Level>,
+    pub target: String,
+    pub message: String,
+    pub fields: Vec<(String, String)>,
+    pub thread_label: Option<String>,
+    /// `help:`/`note:` lines following the message.
+    pub notes: Vec<(NoteKind, String)>,
+    /// Title of a section header, set instead of a message.
+    pub section: Option<String>,
+}
+
+impl ViewFilter {
+    /// Whether `entry` passes the filter.
+    pub fn matches(&self, entry: &LogEntry) -> bool {
+        if let Some(max) = self.level
+            && entry.level.unwrap_or(Level::Info) > max
+        {
+            return false;
+        }
+        if let Some(target) = &self.target
+            && entry.section.is_none()
+            && !target_matches(&entry.target, target)
+        {
+            return false;
+        }
+        match entry.time {
+            Some(time) => {
+                self.since.is_none_or(|since| time >= since) && self.until.is_none_or(|until| time < until)
+            }
+            None => self.since.is_none() && self.until.is_none(),
+        }
+    }
+}
+
+/// Prints the entries of a JSON log from `input` that pass `filter`.
+///
+/// Lines that aren't verbosio JSON entries are skipped. Returns the number
+/// of printed entries.
+pub fn view<R: BufRead, W: Write>(input: R, mut out: W, filter: &ViewFilter) -> io::Result<usize> {
+    let mut printed = 0;
+    for line in input.lines() {
+        let Some(entry) = parse_entry(&line?) else {
+            continue;
+        };
+        if filter.matches(&entry) {
+            writeln!(out, "{}", render(&entry))?;
+            printed += 1;
+        }
+    }
+    Ok(printed)
+}
+
+/// Parses one line of a JSON log; `None` if it isn't a verbosio entry.
+pub fn parse_entry(line: &str) -> Option<LogEntry> {
+    let object = json::parse_object(line.trim())?;
+    let string = |key: &str| {
+        object.iter().find(|(k, _)| k == key).and_then(|(_, value)| match value {
+            json::Value::String(s) => Some(s.clone()),
+            _ => None,
+        })
+    };
+    let section = string("section");
+    let message = string("message");
+    if section.is_none() && message.is_none() {
+        return None;
+    }
+    let level = match string("level").as_deref() {
+        Some("error") => Some(Level::Error),
+        Some("warn") => Some(Level::Warn),
+        Some("info") => Some(Level::Info),
+        Some("debug") => Some(Level::Debug),
+        Some("trace") => Some(Level::Trace),
+        _ => None,
+    };
+    let fields = object
+        .iter()
+        .find(|(key, _)| key == "fields")
+        .and_then(|(_, value)| match value {
+            json::Value::Object(fields) => Some(
+                fields
+                    .iter()
+                    .map(|(key, value)| (key.clone(), value.to_string()))
+                    .collect(),
+            ),
+            _ => None,
+        })
+        .unwrap_or_default();
+    let notes = object
+        .iter()
+        .find(|(key, _)| key == "notes")
+        .and_then(|(_, value)| match value {
+            json::Value::Array(notes) => Some(notes.iter().filter_map(note).collect()),
+            _ => None,
+        })
+        .unwrap_or_default();
+    Some(LogEntry {
+        time: string("timestamp").as_deref().and_then(parse_rfc3339),
+        level,
+        target: string("target").unwrap_or_default(),
+        message: message.unwrap_or_default(),
+        fields,
+        thread_label: string("thread"),
+        notes,
+        section,
+    })
+}
+
+/// Renders an entry like the human formatter, with its original timestamp.
+pub fn render(entry: &LogEntry) -> String {
+    if let Some(title) = &entry.section {
+        return format_section(title);
+    }
+    let record = Record {
+        level: entry.level,
+        time: entry.time.unwrap_or(UNIX_EPOCH),
+        target: entry.target.clone(),
+        named_target: false,
+        force_stderr: false,
+        message: entry.message.clone(),
+        fields: entry.fields.clone(),
+        global_fields: Vec::new(),
+        thread_label: None,
+        hostname: None,
+        username: None,
+        span_id: None,
+        parent_span_id: None,
+        run_id: "",
+        notes: entry.notes.clone(),
+        custom_level: None,
+        success: None,
+        location: None,
+    };
+    let label = entry.thread_label.as_ref().map(|label| format!("{} | ", label)).unwrap_or_default();
+    let tag = entry.level.map(|level| format_level(level.as_str())).unwrap_or_default();
+    let time = entry.time.map(|time| format!("[{}] ", crate::util::format_rfc3339(time))).unwrap_or_default();
+    let target = if entry.target.is_empty() { String::new() } else { format!("{}: ", entry.target) };
+    let mut out = format!("{}{}{}{}{}", label, tag, time, target, record.message_with_fields());
+    for (kind, text) in &record.notes {
+        out.push('\n');
+        out.push_str(&format_note(kind.as_str(), text));
+    }
+    out
+}
+
+fn note(value: &json::Value) -> Option<(NoteKind, String)> {
+    let json::Value::Object(note) = value else {
+        return None;
+    };
+    let field = |key: &str| note.iter().find(|(k, _)| k == key).map(|(_, value)| value.to_string());
+    let kind = match field("kind")?.as_str() {
+        "help" => NoteKind::Help,
+        "note" => NoteKind::Note,
+        _ => return None,
+    };
+    Some((kind, field("text")?))
+}
+
+fn target_matches(target: &str, prefix: &str) -> bool {
+    target == prefix || target.strip_prefix(prefix).is_some_and(|rest| rest.starts_with("::"))
+}
+
+/// Parses an RFC 3339 UTC timestamp as written by verbosio, e.g.
+/// `2025-07-14T09:26:53.589Z`. Fractional seconds are optional.
+pub fn parse_rfc3339(text: &str) -> Option<SystemTime> {
+    let text = text.strip_suffix('Z')?;
+    let (date, time) = text.split_once('T')?;
+    let mut date = date.splitn(3, '-').map(|part| part.parse::<i64>().ok());
+    let (year, month, day) = (date.next()??, date.next()??, date.next()??);
+    let (time, fraction) = time.split_once('.').unwrap_or((time, ""));
+    let mut time = time.splitn(3, ':').map(|part| part.parse::<u64>().ok());
+    let (hour, minute, second) = (time.next()??, time.next()??, time.next()??);
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) || hour > 23 || minute > 59 || second > 60 {
+        return None;
+    }
+    let nanos = if fraction.is_empty() {
+        0
+    } else {
+        let digits: String = fraction.chars().take(9).collect();
+        digits.parse::<u32>().ok()? * 10u32.pow(9 - digits.len() as u32)
+    };
+
+    // days since 1970-01-01 from a civil date (Howard Hinnant's algorithm)
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year.rem_euclid(400);
+    let day_of_year = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let days = u64::try_from(era * 146_097 + day_of_era - 719_468).ok()?;
+
+    let secs = days * 86_400 + hour * 3600 + minute * 60 + second;
+    Some(UNIX_EPOCH + Duration::new(secs, nanos))
+}
+
+/// Just enough JSON to read verbosio's own log lines.
+mod json {
+    use std::fmt;
+    use std::iter::Peekable;
+    use std::str::Chars;
+
+    pub enum Value {
+        String(String),
+        /// Numbers, booleans and `null`, kept as written.
+        Literal(String),
+        Object(Vec<(String, Value)>),
+        Array(Vec<Value>),
+    }
+
+    impl fmt::Display for Value {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                Value::String(s) | Value::Literal(s) => f.write_str(s),
+                Value::Object(_) => f.write_str("{..}"),
+                Value::Array(_) => f.write_str("[..]"),
+            }
+        }
+    }
+
+    pub fn parse_object(text: &str) -> Option<Vec<(String, Value)>> {
+        let mut chars = text.chars().peekable();
+        let object = object(&mut chars)?;
+        skip_whitespace(&mut chars);
+        chars.next().is_none().then_some(object)
+    }
+
+    fn object(chars: &mut Peekable<Chars<'_>>) -> Option<Vec<(String, Value)>> {
+        skip_whitespace(chars);
+        if chars.next()? != '{' {
+            return None;
+        }
+        let mut entries = Vec::new();
+        skip_whitespace(chars);
+        if chars.peek() == Some(&'}') {
+            chars.next();
+            return Some(entries);
+        }
+        loop {
+            skip_whitespace(chars);
+            let key = string(chars)?;
+            skip_whitespace(chars);
+            if chars.next()? != ':' {
+                return None;
+            }
+            entries.push((key, value(chars)?));
+            skip_whitespace(chars);
+            match chars.next()? {
+                ',' => continue,
+                '}' => return Some(entries),
+                _ => return None,
+            }
+        }
+    }
+
+    fn value(chars: &mut Peekable<Chars<'_>>) -> Option<Value> {
+        skip_whitespace(chars);
+        match chars.peek()? {
+            '"' => string(chars).map(Value::String),
+            '{' => object(chars).map(Value::Object),
+            '[' => array(chars).map(Value::Array),
+            _ => {
+                let mut literal = String::new();
+                while let Some(&c) = chars.peek() {
+                    if c == ',' || c == '}' || c == ']' || c.is_whitespace() {
+                        break;
+                    }
+                    literal.push(c);
+                    chars.next();
+                }
+                (!literal.is_empty()).then_some(Value::Literal(literal))
+            }
+        }
+    }
+
+    fn array(chars: &mut Peekable<Chars<'_>>) -> Option<Vec<Value>> {
+        if chars.next()? != '[' {
+            return None;
+        }
+        let mut values = Vec::new();
+        skip_whitespace(chars);
+        if chars.peek() == Some(&']') {
+            chars.next();
+            return Some(values);
+        }
+        loop {
+            values.push(value(chars)?);
+            skip_whitespace(chars);
+            match chars.next()? {
+                ',' => continue,
+                ']' => return Some(values),
+                _ => return None,
+            }
+        }
+    }
+
+    fn string(chars: &mut Peekable<Chars<'_>>) -> Option<String> {
+        if chars.next()? != '"' {
+            return None;
+        }
+        let mut out = String::new();
+        loop {
+            match chars.next()? {
+                '"' => return Some(out),
+                '\\' => match chars.next()? {
+                    'n' => out.push('\n'),
+                    'r' => out.push('\r'),
+                    't' => out.push('\t'),
+                    'b' => out.push('\u{8}'),
+                    'f' => out.push('\u{c}'),
+                    'u' => {
+                        let hex: String = (0..4).filter_map(|_| chars.next()).collect();
+                        out.push(char::from_u32(u32::from_str_radix(&hex, 16).ok()?)?);
+                    }
+                    other => out.push(other),
+                },
+                c => out.push(c),
+            }
+        }
+    }
+
+    fn skip_whitespace(chars: &mut Peekable<Chars<'_>>) {
+        while chars.peek().is_some_and(|c| c.is_whitespace()) {
+            chars.next();
+        }
+    }
+}
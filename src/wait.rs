@@ -0,0 +1,114 @@
+//! Support for [`vwait!`](crate::vwait) and [`vwait_async!`](crate::vwait_async).
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+/// Whether the value returned by waited-for work counts as success.
+///
+/// Decides between the `✔` and `✖` marks printed by
+/// [`vwait!`](crate::vwait).
+pub trait Outcome {
+    fn is_success(&self) -> bool;
+}
+
+impl<T, E> Outcome for Result<T, E> {
+    fn is_success(&self) -> bool {
+        self.is_ok()
+    }
+}
+
+impl<T> Outcome for Option<T> {
+    fn is_success(&self) -> bool {
+        self.is_some()
+    }
+}
+
+impl Outcome for bool {
+    fn is_success(&self) -> bool {
+        *self
+    }
+}
+
+impl Outcome for () {
+    fn is_success(&self) -> bool {
+        true
+    }
+}
+
+/// Runs `work` with a status line if `show` is set.
+#[doc(hidden)]
+pub fn wait<T: Outcome>(show: bool, message: String, work: impl FnOnce() -> T) -> T {
+    if !show {
+        return work();
+    }
+    let waiting = Waiting::start(message);
+    let value = work();
+    waiting.finish(value.is_success());
+    value
+}
+
+/// Awaits `work` with a status line if `show` is set.
+#[doc(hidden)]
+pub async fn wait_async<T: Outcome>(show: bool, message: String, work: impl Future<Output = T>) -> T {
+    if !show {
+        return work.await;
+    }
+    let waiting = Waiting::start(message);
+    let value = work.await;
+    waiting.finish(value.is_success());
+    value
+}
+
+struct Waiting {
+    message: String,
+    start: Instant,
+    #[cfg(feature = "status")]
+    spinner: crate::status::SpinnerHandle,
+}
+
+impl Waiting {
+    fn start(message: String) -> Self {
+        Waiting {
+            #[cfg(feature = "status")]
+            spinner: {
+                let message = message.clone();
+                crate::status::start_spinner(move || message.clone())
+            },
+            message,
+            start: Instant::now(),
+        }
+    }
+
+    fn finish(self, success: bool) {
+        #[cfg(feature = "status")]
+        self.spinner.stop();
+        crate::sink::emit_result(success, module_path!(), format_args!("{} ({:.2?})", self.message, self.start.elapsed()));
+        crate::bell::finished(self.start.elapsed());
+    }
+}
+
+/// The ✔/✖ mark, or `ok`/`failed` on terminals without Unicode.
+pub(crate) fn mark(success: bool) -> &'static str {
+    match (crate::capabilities().unicode, success) {
+        (true, true) => "✔",
+        (true, false) => "✖",
+        (false, true) => "ok",
+        (false, false) => "failed",
+    }
+}
+
+/// The mark, green on success and red on failure.
+#[cfg(feature = "color")]
+pub(crate) fn format_mark(success: bool) -> String {
+    use crate::color::paint;
+    let theme = crate::color::theme();
+    if success {
+        paint(mark(true), theme.success)
+    } else {
+        paint(mark(false), theme.error)
+    }
+}
+
+#[cfg(not(feature = "color"))]
+pub(crate) fn format_mark(success: bool) -> String {
+    mark(success).to_string()
+}
@@ -0,0 +1,104 @@
+//! Warnings when a long-running section stops producing output.
+//!
+//! With [`enable_silence_watchdog`], a warning is logged whenever a section
+//! has been open for a while without any record, section header or footer
+//! being produced. Users of long-running tools can then tell a slow step
+//! from a hung one. The warning repeats for every further period of silence.
+//! It is not counted in the [`tally`](crate::tally), so a run that is slow
+//! but succeeds still exits with `0`.
+//!
+//! # Example
+//! ```rust
+//! use std::time::Duration;
+//! use verbosio::{set_verbosity, vsection};
+//!
+//! set_verbosity!(1);
+//! verbosio::watchdog::enable_silence_watchdog(Duration::from_secs(60));
+//! vsection!("Linking");
+//! // after a minute without output:
+//! // [WARN] no output for 60s during "Linking", still running
+//! ```
+use std::sync::{Mutex, Once};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+use once_cell::sync::Lazy;
+use crate::Level;
+
+/// How often the watchdog thread checks for silence.
+const WATCH_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Disabled while zero.
+static SILENCE_MILLIS: AtomicU64 = AtomicU64::new(0);
+
+static START: Lazy<Instant> = Lazy::new(Instant::now);
+
+/// Milliseconds after [`START`] of the most recent output.
+static LAST_ACTIVITY: AtomicU64 = AtomicU64::new(0);
+
+/// Title of the most recently printed section.
+static SECTION: Mutex<Option<String>> = Mutex::new(None);
+
+static WATCHDOG: Once = Once::new();
+
+/// Logs a warning whenever a section produces no output for `after`.
+///
+/// Only applies while a section (`vsection!` or `vsection_scoped!`) is
+/// open. Replaces a previously set duration.
+pub fn enable_silence_watchdog(after: Duration) {
+    touch();
+    SILENCE_MILLIS.store((after.as_millis() as u64).max(1), Ordering::Relaxed);
+    WATCHDOG.call_once(|| {
+        thread::spawn(watch);
+    });
+}
+
+/// Stops the warnings of [`enable_silence_watchdog`].
+pub fn disable_silence_watchdog() {
+    SILENCE_MILLIS.store(0, Ordering::Relaxed);
+}
+
+/// Records that output was just produced.
+pub(crate) fn touch() {
+    LAST_ACTIVITY.store(START.elapsed().as_millis() as u64, Ordering::Relaxed);
+}
+
+/// Records that the section `title` was printed.
+pub(crate) fn enter_section(title: &str) {
+    touch();
+    if SILENCE_MILLIS.load(Ordering::Relaxed) != 0 {
+        *SECTION.lock().unwrap_or_else(|e| e.into_inner()) = Some(title.to_string());
+    }
+}
+
+fn watch() {
+    loop {
+        thread::sleep(WATCH_INTERVAL);
+        let silence = SILENCE_MILLIS.load(Ordering::Relaxed);
+        if silence == 0 || !crate::span::any_open() {
+            continue;
+        }
+        let now = START.elapsed().as_millis() as u64;
+        if now.saturating_sub(LAST_ACTIVITY.load(Ordering::Relaxed)) < silence {
+            continue;
+        }
+        let silence = Duration::from_millis(silence);
+        // not counted as a warning: a slow run that succeeds still exits with 0
+        if crate::filter::level_enabled(module_path!(), Some(Level::Warn), 1) {
+            match SECTION.lock().unwrap_or_else(|e| e.into_inner()).clone() {
+                Some(title) => crate::sink::emit_uncounted(
+                    Some(Level::Warn),
+                    module_path!(),
+                    format_args!("no output for {:.0?} during \"{}\", still running", silence, title),
+                ),
+                None => crate::sink::emit_uncounted(
+                    Some(Level::Warn),
+                    module_path!(),
+                    format_args!("no output for {:.0?}, still running", silence),
+                ),
+            }
+        }
+        // the warning may not have been printed; start the next period anyway
+        touch();
+    }
+}
@@ -0,0 +1,132 @@
+//! Procedural macros for [`verbosio`](https://crates.io/crates/verbosio).
+//!
+//! Use them through the re-exports in `verbosio` (feature `instrument`)
+//! rather than depending on this crate directly.
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{quote, ToTokens};
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::{parse_macro_input, Expr, FnArg, Ident, ItemFn, Pat, ReturnType, Token, Type};
+
+/// Options of `#[instrument(...)]`.
+struct Options {
+    lvl: Expr,
+    args: bool,
+    err: bool,
+}
+
+impl Parse for Options {
+    fn parse(input: ParseStream<'_>) -> syn::Result<Self> {
+        let mut options = Options {
+            lvl: syn::parse_quote!(1),
+            args: false,
+            err: false,
+        };
+        let items = Punctuated::<Setting, Token![,]>::parse_terminated(input)?;
+        for item in items {
+            match item {
+                Setting::Lvl(lvl) => options.lvl = lvl,
+                Setting::Args => options.args = true,
+                Setting::Err => options.err = true,
+            }
+        }
+        Ok(options)
+    }
+}
+
+enum Setting {
+    Lvl(Expr),
+    Args,
+    Err,
+}
+
+impl Parse for Setting {
+    fn parse(input: ParseStream<'_>) -> syn::Result<Self> {
+        let name: Ident = input.parse()?;
+        match name.to_string().as_str() {
+            "lvl" => {
+                input.parse::<Token![=]>()?;
+                Ok(Setting::Lvl(input.parse()?))
+            }
+            "args" => Ok(Setting::Args),
+            "err" => Ok(Setting::Err),
+            _ => Err(syn::Error::new(name.span(), "expected `lvl = N`, `args` or `err`")),
+        }
+    }
+}
+
+/// Logs entry, exit and duration of a function, see `verbosio::instrument`.
+#[proc_macro_attribute]
+pub fn instrument(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let options = parse_macro_input!(attr as Options);
+    let function = parse_macro_input!(item as ItemFn);
+    match expand(options, function) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+fn expand(options: Options, function: ItemFn) -> syn::Result<TokenStream2> {
+    if let Some(asyncness) = &function.sig.asyncness {
+        return Err(syn::Error::new_spanned(asyncness, "#[instrument] does not support async functions"));
+    }
+    let ItemFn { attrs, vis, sig, block } = function;
+    let name = sig.ident.to_string();
+    let lvl = &options.lvl;
+
+    let mut arg_format = Vec::new();
+    let mut arg_values = Vec::new();
+    if options.args {
+        for input in &sig.inputs {
+            if let FnArg::Typed(typed) = input
+                && let Pat::Ident(pat) = &*typed.pat
+            {
+                arg_format.push(format!("{} = {{:?}}", pat.ident));
+                let ident = &pat.ident;
+                arg_values.push(quote!(#ident));
+            }
+        }
+    }
+    let entry_format = if options.args {
+        format!("→ {}({})", name, arg_format.join(", "))
+    } else {
+        format!("→ {}", name)
+    };
+
+    // an explicit return type lets `?` inside the closure infer its error type
+    let closure_return = match &sig.output {
+        ReturnType::Default => quote!(-> ()),
+        ReturnType::Type(_, ty) if matches!(**ty, Type::ImplTrait(_)) => quote!(),
+        ReturnType::Type(arrow, ty) => quote!(#arrow #ty),
+    };
+    let on_error = if options.err {
+        quote! {
+            if let ::core::result::Result::Err(err) = &__verbosio_result {
+                ::verbosio::verror!(@lvl #lvl, "{} failed: {}", #name, err);
+            }
+        }
+    } else {
+        quote!()
+    };
+
+    let body = quote! {
+        {
+            ::verbosio::verbose!(@lvl #lvl, #entry_format #(, #arg_values)*);
+            let __verbosio_timer = ::verbosio::timer::Timer::start_function(#name);
+            #[allow(clippy::redundant_closure_call)]
+            let __verbosio_result = (|| #closure_return #block)();
+            #on_error
+            ::verbosio::verbose!(@lvl #lvl, "← {} took {:.2?}", #name, __verbosio_timer.elapsed());
+            __verbosio_result
+        }
+    };
+    let mut tokens = TokenStream2::new();
+    for attr in &attrs {
+        attr.to_tokens(&mut tokens);
+    }
+    vis.to_tokens(&mut tokens);
+    sig.to_tokens(&mut tokens);
+    body.to_tokens(&mut tokens);
+    Ok(tokens)
+}